@@ -0,0 +1,183 @@
+//! OAuth 2.0 Demonstrating Proof of Possession (DPoP) support.
+
+mod dpop_proof;
+mod dpop_proof_builder;
+mod dpop_proof_validator;
+
+pub use crate::dpop::dpop_proof::DpopProof;
+pub use crate::dpop::dpop_proof_builder::DpopProofBuilder;
+pub use crate::dpop::dpop_proof_validator::DpopProofValidator;
+
+use anyhow::bail;
+
+use crate::util;
+use crate::JoseError;
+
+/// The media type of a DPoP proof JWT (RFC 9449).
+pub const DPOP_PROOF_TYPE: &str = "dpop+jwt";
+
+/// Return the hash of an access token for the ath claim.
+///
+/// # Arguments
+///
+/// * `access_token` - a access token
+pub fn access_token_hash(access_token: impl AsRef<[u8]>) -> String {
+    let digest = openssl::sha::sha256(access_token.as_ref());
+    util::encode_base64_urlsafe_nopad(digest)
+}
+
+/// Return the HTTP target URI that is normalized for the htu claim comparison.
+///
+/// The query and fragment parts are removed, and the syntax-based normalization
+/// of RFC 3986 section 6.2.2 and the scheme-based normalization of
+/// the default port are applied.
+///
+/// # Arguments
+///
+/// * `uri` - a HTTP target URI
+pub fn normalize_htu(uri: &str) -> Result<String, JoseError> {
+    (|| -> anyhow::Result<String> {
+        let uri = match uri.find(['?', '#']) {
+            Some(index) => &uri[..index],
+            None => uri,
+        };
+
+        let (scheme, rest) = match uri.find("://") {
+            Some(index) if index > 0 => (uri[..index].to_ascii_lowercase(), &uri[(index + 3)..]),
+            _ => bail!("The URI must be absolute: {}", uri),
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        if authority.is_empty() {
+            bail!("The URI must have a host: {}", uri);
+        }
+
+        let (userinfo, host_port) = match authority.rfind('@') {
+            Some(index) => (&authority[..(index + 1)], &authority[(index + 1)..]),
+            None => ("", authority),
+        };
+        let port_index = match host_port.rfind(':') {
+            Some(index) if !host_port[index..].contains(']') => Some(index),
+            _ => None,
+        };
+        let (host, port) = match port_index {
+            Some(index) => (&host_port[..index], &host_port[(index + 1)..]),
+            None => (host_port, ""),
+        };
+        let default_port = match scheme.as_str() {
+            "https" => "443",
+            "http" => "80",
+            _ => "",
+        };
+
+        let mut normalized = String::with_capacity(uri.len());
+        normalized.push_str(&scheme);
+        normalized.push_str("://");
+        normalized.push_str(&normalize_percent_encoding(userinfo));
+        normalized.push_str(&normalize_percent_encoding(host).to_ascii_lowercase());
+        if !port.is_empty() && port != default_port {
+            normalized.push(':');
+            normalized.push_str(port);
+        }
+        normalized.push_str(&remove_dot_segments(&normalize_percent_encoding(path)));
+        Ok(normalized)
+    })()
+    .map_err(JoseError::InvalidClaim)
+}
+
+fn normalize_percent_encoding(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(index) = rest.find('%') {
+        output.push_str(&rest[..index]);
+
+        let hex = rest
+            .get((index + 1)..(index + 3))
+            .filter(|val| val.bytes().all(|b| b.is_ascii_hexdigit()));
+        match hex {
+            Some(hex) => {
+                let val = u8::from_str_radix(hex, 16).unwrap();
+                if val.is_ascii_alphanumeric() || b"-._~".contains(&val) {
+                    output.push(val as char);
+                } else {
+                    output.push('%');
+                    output.push_str(&hex.to_ascii_uppercase());
+                }
+                rest = &rest[(index + 3)..];
+            }
+            None => {
+                output.push('%');
+                rest = &rest[(index + 1)..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+fn remove_dot_segments(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    let parts: Vec<&str> = path.split('/').skip(1).collect();
+    for (i, part) in parts.iter().enumerate() {
+        let is_last = i == parts.len() - 1;
+        match *part {
+            "." => {
+                if is_last {
+                    segments.push("");
+                }
+            }
+            ".." => {
+                segments.pop();
+                if is_last {
+                    segments.push("");
+                }
+            }
+            val => segments.push(val),
+        }
+    }
+    format!("/{}", segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::dpop;
+
+    #[test]
+    fn test_normalize_htu() -> Result<()> {
+        assert_eq!(
+            dpop::normalize_htu("HTTPS://Server.Example.COM:443/token?x=1#frag")?,
+            "https://server.example.com/token"
+        );
+        assert_eq!(
+            dpop::normalize_htu("https://server.example.com")?,
+            "https://server.example.com/"
+        );
+        assert_eq!(
+            dpop::normalize_htu("http://server.example.com:8080/a/./b/../c")?,
+            "http://server.example.com:8080/a/c"
+        );
+        assert_eq!(
+            dpop::normalize_htu("https://[::1]:443/%7euser/%2f")?,
+            "https://[::1]/~user/%2F"
+        );
+        assert!(dpop::normalize_htu("/token").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_access_token_hash() -> Result<()> {
+        // RFC 9449 section 7.1
+        assert_eq!(
+            dpop::access_token_hash("Kz~8mXK1EalYznwH-LC-1fBAo.4Ljp~zsPE_NeO.gxU"),
+            "fUHyO2r2Z3DZ53EsNrWBb0xWXoaNy59IiKCAqksmQEo"
+        );
+
+        Ok(())
+    }
+}
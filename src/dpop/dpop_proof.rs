@@ -0,0 +1,76 @@
+use crate::jwk::Jwk;
+use crate::jws::JwsHeader;
+use crate::jwt::JwtPayload;
+use crate::Value;
+
+/// Represents a decoded and validated DPoP proof JWT (RFC 9449).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DpopProof {
+    payload: JwtPayload,
+    header: JwsHeader,
+    jwk: Jwk,
+    jwk_thumbprint: String,
+}
+
+impl DpopProof {
+    pub(crate) fn new(
+        payload: JwtPayload,
+        header: JwsHeader,
+        jwk: Jwk,
+        jwk_thumbprint: String,
+    ) -> Self {
+        Self {
+            payload,
+            header,
+            jwk,
+            jwk_thumbprint,
+        }
+    }
+
+    /// Return the payload of the DPoP proof.
+    pub fn payload(&self) -> &JwtPayload {
+        &self.payload
+    }
+
+    /// Return the header of the DPoP proof.
+    pub fn header(&self) -> &JwsHeader {
+        &self.header
+    }
+
+    /// Return the public key that is embedded in the header.
+    pub fn jwk(&self) -> &Jwk {
+        &self.jwk
+    }
+
+    /// Return the SHA-256 JWK thumbprint of the public key for the jkt confirmation.
+    pub fn jwk_thumbprint(&self) -> &str {
+        &self.jwk_thumbprint
+    }
+
+    /// Return the value for HTTP method claim (htm).
+    pub fn http_method(&self) -> Option<&str> {
+        self.string_claim("htm")
+    }
+
+    /// Return the value for HTTP target URI claim (htu).
+    pub fn http_uri(&self) -> Option<&str> {
+        self.string_claim("htu")
+    }
+
+    /// Return the value for access token hash claim (ath).
+    pub fn access_token_hash(&self) -> Option<&str> {
+        self.string_claim("ath")
+    }
+
+    /// Return the value for nonce claim.
+    pub fn nonce(&self) -> Option<&str> {
+        self.string_claim("nonce")
+    }
+
+    fn string_claim(&self, key: &str) -> Option<&str> {
+        match self.payload.claim(key) {
+            Some(Value::String(val)) => Some(val),
+            _ => None,
+        }
+    }
+}
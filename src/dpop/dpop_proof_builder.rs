@@ -0,0 +1,113 @@
+use std::convert::Into;
+use std::time::SystemTime;
+
+use crate::dpop::{self, DPOP_PROOF_TYPE};
+use crate::jwk::Jwk;
+use crate::jws::{JwsHeader, JwsSigner};
+use crate::jwt::{self, JwtPayload};
+use crate::util;
+use crate::{JoseError, Value};
+
+/// Represents DPoP proof JWT builder for clients (RFC 9449).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DpopProofBuilder {
+    http_method: String,
+    http_uri: String,
+    access_token: Option<String>,
+    nonce: Option<String>,
+    issued_at: Option<SystemTime>,
+    jwt_id: Option<String>,
+}
+
+impl DpopProofBuilder {
+    /// Return a new DpopProofBuilder.
+    ///
+    /// # Arguments
+    ///
+    /// * `http_method` - a HTTP method of the request like "POST".
+    /// * `http_uri` - a HTTP target URI of the request. The query and fragment parts are removed.
+    pub fn new(http_method: impl Into<String>, http_uri: impl Into<String>) -> Self {
+        let http_uri: String = http_uri.into();
+        let http_uri = match http_uri.find(['?', '#']) {
+            Some(index) => http_uri[..index].to_string(),
+            None => http_uri,
+        };
+
+        Self {
+            http_method: http_method.into(),
+            http_uri,
+            access_token: None,
+            nonce: None,
+            issued_at: None,
+            jwt_id: None,
+        }
+    }
+
+    /// Set a access token that the proof is bound to by the ath claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a access token
+    pub fn set_access_token(&mut self, value: impl Into<String>) {
+        self.access_token = Some(value.into());
+    }
+
+    /// Set a nonce value that is provided by the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a nonce value
+    pub fn set_nonce(&mut self, value: impl Into<String>) {
+        self.nonce = Some(value.into());
+    }
+
+    /// Set a value for issued at claim (iat). The default value is the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issued time
+    pub fn set_issued_at(&mut self, value: SystemTime) {
+        self.issued_at = Some(value);
+    }
+
+    /// Set a value for JWT ID claim (jti). The default value is a random string.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a JWT ID
+    pub fn set_jwt_id(&mut self, value: impl Into<String>) {
+        self.jwt_id = Some(value.into());
+    }
+
+    /// Return the string repsentation of the DPoP proof signed by the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    /// * `jwk` - a JWK of the signing key. Only the public key parameters are embedded.
+    pub fn sign(&self, signer: &dyn JwsSigner, jwk: &Jwk) -> Result<String, JoseError> {
+        let mut header = JwsHeader::new();
+        header.set_token_type(DPOP_PROOF_TYPE);
+        header.set_jwk(jwk.to_public_key()?);
+
+        let mut payload = JwtPayload::new();
+        match &self.jwt_id {
+            Some(val) => payload.set_jwt_id(val),
+            None => payload.set_jwt_id(util::encode_base64_urlsafe_nopad(util::random_bytes(16))),
+        }
+        payload.set_claim("htm", Some(Value::String(self.http_method.clone())))?;
+        payload.set_claim("htu", Some(Value::String(self.http_uri.clone())))?;
+        match self.issued_at {
+            Some(val) => payload.set_issued_at(&val),
+            None => payload.set_issued_at(&SystemTime::now()),
+        }
+        if let Some(val) = &self.access_token {
+            payload.set_claim("ath", Some(Value::String(dpop::access_token_hash(val))))?;
+        }
+        if let Some(val) = &self.nonce {
+            payload.set_claim("nonce", Some(Value::String(val.clone())))?;
+        }
+
+        jwt::encode_with_signer(&payload, &header, signer)
+    }
+}
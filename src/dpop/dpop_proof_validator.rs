@@ -0,0 +1,337 @@
+use std::convert::Into;
+use std::time::Duration;
+
+use anyhow::bail;
+
+use crate::dpop::{self, DpopProof, DPOP_PROOF_TYPE};
+use crate::jwk::Jwk;
+use crate::jws;
+use crate::jwt::{JwtContext, JwtHeaderValidator, JwtPayloadValidator};
+use crate::util::SHA_256;
+use crate::{JoseError, Value};
+
+/// Represents DPoP proof JWT validator for servers (RFC 9449).
+#[derive(Debug)]
+pub struct DpopProofValidator {
+    context: JwtContext,
+    header_validator: JwtHeaderValidator,
+    payload_validator: JwtPayloadValidator,
+    http_method: String,
+    http_uri: String,
+    access_token: Option<String>,
+    nonce: Option<String>,
+    jwk_thumbprint: Option<String>,
+}
+
+impl DpopProofValidator {
+    /// Return a new DpopProofValidator.
+    ///
+    /// The proof must be issued within 5 minutes by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `http_method` - a HTTP method of the request like "POST".
+    /// * `http_uri` - a HTTP target URI of the request.
+    pub fn new(http_method: impl Into<String>, http_uri: impl Into<String>) -> Self {
+        let mut header_validator = JwtHeaderValidator::new();
+        header_validator.set_token_type(DPOP_PROOF_TYPE);
+        header_validator.set_algorithms(vec![
+            "RS256", "RS384", "RS512", "PS256", "PS384", "PS512", "ES256", "ES256K", "ES384",
+            "ES512", "EdDSA",
+        ]);
+
+        let mut payload_validator = JwtPayloadValidator::new();
+        payload_validator.set_max_age(Duration::from_secs(300));
+        payload_validator.add_required_claim("jti");
+
+        Self {
+            context: JwtContext::new(),
+            header_validator,
+            payload_validator,
+            http_method: http_method.into(),
+            http_uri: http_uri.into(),
+            access_token: None,
+            nonce: None,
+            jwk_thumbprint: None,
+        }
+    }
+
+    /// Return the mutable header validator that checks the typ and alg header claims.
+    pub fn header_validator_mut(&mut self) -> &mut JwtHeaderValidator {
+        &mut self.header_validator
+    }
+
+    /// Return the mutable payload validator that checks the freshness of the proof.
+    ///
+    /// A replay guard can be set to reject a jti that is seen before.
+    pub fn payload_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.payload_validator
+    }
+
+    /// Set a access token that is presented with the proof.
+    ///
+    /// The ath claim is required and must be the hash of the access token.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a access token
+    pub fn set_access_token(&mut self, value: impl Into<String>) {
+        self.access_token = Some(value.into());
+    }
+
+    /// Set a nonce value that is provided by the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a nonce value
+    pub fn set_nonce(&mut self, value: impl Into<String>) {
+        self.nonce = Some(value.into());
+    }
+
+    /// Set a JWK thumbprint that the proof key must match like the jkt confirmation
+    /// of the access token.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a base64url encoded SHA-256 JWK thumbprint
+    pub fn set_jwk_thumbprint(&mut self, value: impl Into<String>) {
+        self.jwk_thumbprint = Some(value.into());
+    }
+
+    /// Return the DPoP proof decoded and validated by the embedded public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a DPoP proof string representation.
+    pub fn validate(&self, input: impl AsRef<[u8]>) -> Result<DpopProof, JoseError> {
+        let input = input.as_ref();
+        let header = self.context.decode_header(input)?;
+        self.header_validator.validate(header.as_ref())?;
+
+        let (alg, jwk) = (|| -> anyhow::Result<(String, Jwk)> {
+            let alg = match header.claim("alg") {
+                Some(Value::String(val)) => val.to_string(),
+                _ => bail!("The alg header claim is required."),
+            };
+            let jwk = match header.claim("jwk") {
+                Some(Value::Object(val)) => Jwk::from_map(val.clone())?,
+                Some(_) => bail!("The jwk header claim must be a object."),
+                None => bail!("The jwk header claim is required."),
+            };
+            if jwk.key_type() == "oct" || jwk.parameter("d").is_some() {
+                bail!("The jwk header claim must be a public key.");
+            }
+            Ok((alg, jwk))
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })?;
+
+        let verifier = jws::verifier_from_jwk(&alg, &jwk)?;
+        let (payload, header) = self
+            .context
+            .decode_with_verifier(input, verifier.as_ref())?;
+        let jwk_thumbprint = jwk.thumbprint(SHA_256)?;
+
+        (|| -> anyhow::Result<()> {
+            if let Some(expected) = &self.jwk_thumbprint {
+                if expected != &jwk_thumbprint {
+                    bail!(
+                        "The proof key is not bound to the access token: {}",
+                        jwk_thumbprint
+                    );
+                }
+            }
+
+            match payload.claim("htm") {
+                Some(Value::String(val)) => {
+                    if val != &self.http_method {
+                        bail!("Key htm is invalid: {}", val);
+                    }
+                }
+                Some(val) => bail!("Key htm must be a string: {}", val),
+                None => bail!(JoseError::MissingClaim("htm".to_string())),
+            }
+
+            match payload.claim("htu") {
+                Some(Value::String(val)) => {
+                    if dpop::normalize_htu(val)? != dpop::normalize_htu(&self.http_uri)? {
+                        bail!("Key htu is invalid: {}", val);
+                    }
+                }
+                Some(val) => bail!("Key htu must be a string: {}", val),
+                None => bail!(JoseError::MissingClaim("htu".to_string())),
+            }
+
+            self.payload_validator.validate(&payload)?;
+
+            if let Some(access_token) = &self.access_token {
+                match payload.claim("ath") {
+                    Some(Value::String(val)) => {
+                        if val != &dpop::access_token_hash(access_token) {
+                            bail!("Key ath is invalid: {}", val);
+                        }
+                    }
+                    Some(val) => bail!("Key ath must be a string: {}", val),
+                    None => bail!(JoseError::MissingClaim("ath".to_string())),
+                }
+            }
+
+            if let Some(nonce) = &self.nonce {
+                match payload.claim("nonce") {
+                    Some(Value::String(val)) => {
+                        if val != nonce {
+                            bail!("Key nonce is invalid: {}", val);
+                        }
+                    }
+                    Some(val) => bail!("Key nonce must be a string: {}", val),
+                    None => bail!(JoseError::MissingClaim("nonce".to_string())),
+                }
+            }
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })?;
+
+        Ok(DpopProof::new(payload, header, jwk, jwk_thumbprint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+
+    use crate::dpop::{DpopProofBuilder, DpopProofValidator};
+    use crate::jwk::alg::ec::EcCurve;
+    use crate::jwk::Jwk;
+    use crate::jws::{JwsHeader, ES256, HS256};
+    use crate::jwt::{self, JwtPayload, MemoryReplayGuard};
+    use crate::util::SHA_256;
+
+    #[test]
+    fn test_dpop_proof() -> Result<()> {
+        let jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let signer = ES256.signer_from_jwk(&jwk)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut builder = DpopProofBuilder::new("POST", "https://server.example.com/token?a=b");
+        builder.set_issued_at(now);
+        builder.set_access_token("access-token");
+        builder.set_nonce("server-nonce");
+        let proof = builder.sign(&signer, &jwk)?;
+
+        let mut validator = DpopProofValidator::new("POST", "https://SERVER.example.com:443/token");
+        validator
+            .payload_validator_mut()
+            .set_base_time(now + Duration::from_secs(10));
+        validator
+            .payload_validator_mut()
+            .set_replay_guard(Arc::new(MemoryReplayGuard::new()));
+        validator.set_access_token("access-token");
+        validator.set_nonce("server-nonce");
+        validator.set_jwk_thumbprint(jwk.thumbprint(SHA_256)?);
+        let dst = validator.validate(&proof)?;
+
+        assert_eq!(dst.http_method(), Some("POST"));
+        assert_eq!(dst.http_uri(), Some("https://server.example.com/token"));
+        assert_eq!(dst.nonce(), Some("server-nonce"));
+        assert_eq!(dst.jwk(), &jwk.to_public_key()?);
+        assert_eq!(dst.jwk_thumbprint(), jwk.thumbprint(SHA_256)?);
+        assert_eq!(dst.header().token_type(), Some("dpop+jwt"));
+
+        // replayed
+        assert!(validator.validate(&proof).is_err());
+
+        let mut validator2 = DpopProofValidator::new("GET", "https://server.example.com/token");
+        validator2.payload_validator_mut().set_base_time(now);
+        assert!(validator2.validate(&proof).is_err());
+
+        let mut validator2 = DpopProofValidator::new("POST", "https://server.example.com/other");
+        validator2.payload_validator_mut().set_base_time(now);
+        assert!(validator2.validate(&proof).is_err());
+
+        let mut validator2 = DpopProofValidator::new("POST", "https://server.example.com/token");
+        validator2
+            .payload_validator_mut()
+            .set_base_time(now + Duration::from_secs(600));
+        assert!(validator2.validate(&proof).is_err());
+
+        let mut validator2 = DpopProofValidator::new("POST", "https://server.example.com/token");
+        validator2.payload_validator_mut().set_base_time(now);
+        validator2.set_access_token("other-token");
+        assert!(validator2.validate(&proof).is_err());
+
+        let mut validator2 = DpopProofValidator::new("POST", "https://server.example.com/token");
+        validator2.payload_validator_mut().set_base_time(now);
+        validator2.set_jwk_thumbprint(Jwk::generate_ec_key(EcCurve::P256)?.thumbprint(SHA_256)?);
+        assert!(validator2.validate(&proof).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dpop_proof_rejects_invalid_key() -> Result<()> {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut payload = JwtPayload::new();
+        payload.set_jwt_id("id");
+        payload.set_issued_at(&now);
+        payload.set_claim("htm", Some("POST".into()))?;
+        payload.set_claim("htu", Some("https://server.example.com/token".into()))?;
+
+        let mut validator = DpopProofValidator::new("POST", "https://server.example.com/token");
+        validator.payload_validator_mut().set_base_time(now);
+
+        let oct = Jwk::generate_oct_key(32)?;
+        let mut header = JwsHeader::new();
+        header.set_token_type("dpop+jwt");
+        header.set_jwk(oct.clone());
+        let proof = jwt::encode_with_signer(&payload, &header, &HS256.signer_from_jwk(&oct)?)?;
+        assert!(validator.validate(&proof).is_err());
+
+        let jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let mut header = JwsHeader::new();
+        header.set_token_type("dpop+jwt");
+        header.set_jwk(jwk.clone());
+        let proof = jwt::encode_with_signer(&payload, &header, &ES256.signer_from_jwk(&jwk)?)?;
+        assert!(validator.validate(&proof).is_err());
+
+        header.set_jwk(jwk.to_public_key()?);
+        let proof = jwt::encode_with_signer(&payload, &header, &ES256.signer_from_jwk(&jwk)?)?;
+        validator.validate(&proof)?;
+
+        header.set_token_type("JWT");
+        let proof = jwt::encode_with_signer(&payload, &header, &ES256.signer_from_jwk(&jwk)?)?;
+        assert!(validator.validate(&proof).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dpop_proof_rejects_multibyte_token_type() -> Result<()> {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut payload = JwtPayload::new();
+        payload.set_jwt_id("id");
+        payload.set_issued_at(&now);
+        payload.set_claim("htm", Some("POST".into()))?;
+        payload.set_claim("htu", Some("https://server.example.com/token".into()))?;
+
+        let mut validator = DpopProofValidator::new("POST", "https://server.example.com/token");
+        validator.payload_validator_mut().set_base_time(now);
+
+        let jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let mut header = JwsHeader::new();
+        header.set_token_type("aéééééé");
+        header.set_jwk(jwk.to_public_key()?);
+        let proof = jwt::encode_with_signer(&payload, &header, &ES256.signer_from_jwk(&jwk)?)?;
+        assert!(validator.validate(&proof).is_err());
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::SystemTime;
+
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -28,4 +30,129 @@ pub enum JoseError {
 
     #[error("Invalid signature: {0}")]
     InvalidSignature(#[source] anyhow::Error),
+
+    #[error("The token has expired: {}", time::OffsetDateTime::from(*.exp))]
+    Expired { exp: SystemTime, now: SystemTime },
+
+    #[error("The token is not yet valid: {}", time::OffsetDateTime::from(*.nbf))]
+    NotYetValid { nbf: SystemTime, now: SystemTime },
+
+    #[error("The audience is mismatched: {}", .0.join(", "))]
+    AudienceMismatch(Vec<String>),
+
+    #[error("The issuer is mismatched: {0}")]
+    IssuerMismatch(String),
+
+    #[error("The claim is missing: {0}")]
+    MissingClaim(String),
+
+    #[error("A key is not found: {}", .0.as_deref().unwrap_or("(no kid)"))]
+    KeyNotFound(Option<String>),
+
+    #[error("The alg header claim is not {expected}: {actual}")]
+    AlgorithmMismatch { expected: String, actual: String },
+
+    #[error("The critical name '{0}' is not supported.")]
+    CriticalHeaderUnsupported(String),
+}
+
+impl JoseError {
+    /// Return a HTTP status code that is suitable for responding to the error.
+    ///
+    /// Errors caused by a presented token are 401 (Unauthorized),
+    /// and errors caused by a local key or configuration are 500 (Internal Server Error).
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Self::UnsupportedSignatureAlgorithm(_)
+            | Self::InvalidJwkFormat(_)
+            | Self::InvalidKeyFormat(_) => 500,
+            _ => 401,
+        }
+    }
+
+    /// Return a error code of the WWW-Authenticate response header (RFC 6750).
+    pub fn www_authenticate_error(&self) -> Option<&'static str> {
+        match self.http_status() {
+            401 => Some("invalid_token"),
+            _ => None,
+        }
+    }
+
+    /// Return a error description of the WWW-Authenticate response header (RFC 6750).
+    ///
+    /// The description doesn't contain any value of the token.
+    pub fn www_authenticate_error_description(&self) -> Option<&'static str> {
+        let description = match self {
+            Self::Expired { .. } => "The token has expired",
+            Self::NotYetValid { .. } => "The token is not yet valid",
+            Self::AudienceMismatch(_) => "The audience is invalid",
+            Self::IssuerMismatch(_) => "The issuer is invalid",
+            Self::MissingClaim(_) => "A required claim is missing",
+            Self::KeyNotFound(_) => "The signing key is unknown",
+            Self::AlgorithmMismatch { .. } => "The algorithm is not allowed",
+            Self::CriticalHeaderUnsupported(_) => "A critical header is not supported",
+            Self::InvalidSignature(_) => "The signature is invalid",
+            Self::InvalidClaim(_) => "The token claims are invalid",
+            Self::InvalidJwtFormat(_)
+            | Self::InvalidJwsFormat(_)
+            | Self::InvalidJweFormat(_)
+            | Self::InvalidJson(_) => "The token is malformed",
+            Self::UnsupportedSignatureAlgorithm(_)
+            | Self::InvalidJwkFormat(_)
+            | Self::InvalidKeyFormat(_) => return None,
+        };
+        Some(description)
+    }
+
+    /// Return a WWW-Authenticate response header value of the Bearer scheme.
+    ///
+    /// # Arguments
+    ///
+    /// * `realm` - a protection space or None
+    pub fn www_authenticate(&self, realm: Option<&str>) -> String {
+        let mut params = Vec::new();
+        if let Some(val) = realm {
+            params.push(format!("realm=\"{}\"", val.replace(['\\', '"'], "")));
+        }
+        if let Some(val) = self.www_authenticate_error() {
+            params.push(format!("error=\"{}\"", val));
+        }
+        if let Some(val) = self.www_authenticate_error_description() {
+            params.push(format!("error_description=\"{}\"", val));
+        }
+
+        if params.is_empty() {
+            "Bearer".to_string()
+        } else {
+            format!("Bearer {}", params.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::anyhow;
+
+    use crate::JoseError;
+
+    #[test]
+    fn test_http_status() {
+        let err = JoseError::Expired {
+            exp: SystemTime::UNIX_EPOCH,
+            now: SystemTime::UNIX_EPOCH + Duration::from_secs(1),
+        };
+        assert_eq!(err.http_status(), 401);
+        assert_eq!(err.www_authenticate_error(), Some("invalid_token"));
+        assert_eq!(
+            err.www_authenticate(Some("example")),
+            "Bearer realm=\"example\", error=\"invalid_token\", error_description=\"The token has expired\""
+        );
+
+        let err = JoseError::InvalidKeyFormat(anyhow!("broken key"));
+        assert_eq!(err.http_status(), 500);
+        assert_eq!(err.www_authenticate_error(), None);
+        assert_eq!(err.www_authenticate(None), "Bearer");
+    }
 }
@@ -0,0 +1,524 @@
+use std::fmt::Display;
+
+use anyhow::bail;
+
+use crate::util;
+use crate::{JoseError, Map, Value};
+
+/// Represents a kind of JOSE object.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum JoseObjectKind {
+    /// JSON Web Signature
+    Jws,
+    /// JSON Web Encryption
+    Jwe,
+}
+
+impl Display for JoseObjectKind {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        fmt.write_str(match self {
+            Self::Jws => "JWS",
+            Self::Jwe => "JWE",
+        })
+    }
+}
+
+/// Represents a serialization of JOSE object.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum JoseSerialization {
+    /// Compact Serialization
+    Compact,
+    /// Flattened JSON Serialization
+    FlattenedJson,
+    /// General JSON Serialization
+    GeneralJson,
+}
+
+impl Display for JoseSerialization {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        fmt.write_str(match self {
+            Self::Compact => "compact",
+            Self::FlattenedJson => "flattened JSON",
+            Self::GeneralJson => "general JSON",
+        })
+    }
+}
+
+/// Represents a signature of JWS or a recipient of JWE.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct JoseObjectEntry {
+    protected_header: Option<Map<String, Value>>,
+    header: Option<Map<String, Value>>,
+    value: Vec<u8>,
+}
+
+impl JoseObjectEntry {
+    /// Return the protected header of the signature. JWE recipients don't have it.
+    pub fn protected_header(&self) -> Option<&Map<String, Value>> {
+        self.protected_header.as_ref()
+    }
+
+    /// Return the unprotected header of the signature or the recipient.
+    pub fn header(&self) -> Option<&Map<String, Value>> {
+        self.header.as_ref()
+    }
+
+    /// Return the signature of JWS or the encrypted key of JWE.
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+/// Represents a JOSE object parsed without any key.
+///
+/// Nothing is verified or decrypted. The contents must not be trusted
+/// and are intended for routing, logging and debugging.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct JoseObject {
+    kind: JoseObjectKind,
+    serialization: JoseSerialization,
+    size: usize,
+    segments: Vec<String>,
+    protected_header: Option<Map<String, Value>>,
+    unprotected_header: Option<Map<String, Value>>,
+    entries: Vec<JoseObjectEntry>,
+    payload: Option<Vec<u8>>,
+    initialization_vector: Option<Vec<u8>>,
+    ciphertext: Option<Vec<u8>>,
+    tag: Option<Vec<u8>>,
+    aad: Option<Vec<u8>>,
+}
+
+impl JoseObject {
+    /// Return the JOSE object parsed from a compact or a JSON serialization of JWS or JWE.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a JWS or JWE string representation.
+    pub fn parse(input: impl AsRef<[u8]>) -> Result<Self, JoseError> {
+        let input = input.as_ref();
+        let trimmed = match std::str::from_utf8(input) {
+            Ok(val) => val.trim(),
+            Err(err) => return Err(JoseError::InvalidJwtFormat(err.into())),
+        };
+
+        if trimmed.starts_with('{') {
+            Self::parse_json(trimmed, input.len())
+        } else {
+            Self::parse_compact(trimmed, input.len())
+        }
+    }
+
+    /// Return the kind of the object.
+    pub fn kind(&self) -> JoseObjectKind {
+        self.kind
+    }
+
+    /// Return the serialization of the object.
+    pub fn serialization(&self) -> JoseSerialization {
+        self.serialization
+    }
+
+    /// Return the byte size of the input.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Return the base64url encoded segments of the compact serialization.
+    /// It is empty for the JSON serializations.
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// Return the protected header. For JWS, it is the protected header
+    /// of the first signature.
+    pub fn protected_header(&self) -> Option<&Map<String, Value>> {
+        match self.kind {
+            JoseObjectKind::Jws => self.entries.first()?.protected_header(),
+            JoseObjectKind::Jwe => self.protected_header.as_ref(),
+        }
+    }
+
+    /// Return the shared unprotected header. For JWS, it is the unprotected
+    /// header of the first signature.
+    pub fn unprotected_header(&self) -> Option<&Map<String, Value>> {
+        match self.kind {
+            JoseObjectKind::Jws => self.entries.first()?.header(),
+            JoseObjectKind::Jwe => self.unprotected_header.as_ref(),
+        }
+    }
+
+    /// Return a header claim of the first signature or recipient
+    /// looked up in the protected, shared unprotected and per-entry headers in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of header claim
+    pub fn header_claim(&self, key: &str) -> Option<&Value> {
+        let entry = self.entries.first();
+        [
+            self.protected_header(),
+            self.unprotected_header.as_ref(),
+            entry.and_then(|val| val.header()),
+        ]
+        .into_iter()
+        .flatten()
+        .find_map(|header| header.get(key))
+    }
+
+    /// Return the signatures of JWS.
+    pub fn signatures(&self) -> &[JoseObjectEntry] {
+        match self.kind {
+            JoseObjectKind::Jws => &self.entries,
+            JoseObjectKind::Jwe => &[],
+        }
+    }
+
+    /// Return the recipients of JWE.
+    pub fn recipients(&self) -> &[JoseObjectEntry] {
+        match self.kind {
+            JoseObjectKind::Jws => &[],
+            JoseObjectKind::Jwe => &self.entries,
+        }
+    }
+
+    /// Return the unverified payload of JWS. It is None when the payload is detached.
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.payload.as_deref()
+    }
+
+    /// Return the initialization vector of JWE.
+    pub fn initialization_vector(&self) -> Option<&[u8]> {
+        self.initialization_vector.as_deref()
+    }
+
+    /// Return the ciphertext of JWE.
+    pub fn ciphertext(&self) -> Option<&[u8]> {
+        self.ciphertext.as_deref()
+    }
+
+    /// Return the authentication tag of JWE.
+    pub fn tag(&self) -> Option<&[u8]> {
+        self.tag.as_deref()
+    }
+
+    /// Return the additional authenticated data of JWE JSON serialization.
+    pub fn aad(&self) -> Option<&[u8]> {
+        self.aad.as_deref()
+    }
+
+    fn new(kind: JoseObjectKind, serialization: JoseSerialization, size: usize) -> Self {
+        Self {
+            kind,
+            serialization,
+            size,
+            segments: Vec::new(),
+            protected_header: None,
+            unprotected_header: None,
+            entries: Vec::new(),
+            payload: None,
+            initialization_vector: None,
+            ciphertext: None,
+            tag: None,
+            aad: None,
+        }
+    }
+
+    fn parse_compact(input: &str, size: usize) -> Result<Self, JoseError> {
+        let segments: Vec<&str> = input.split('.').collect();
+        match segments.len() {
+            3 => (|| -> anyhow::Result<Self> {
+                let mut object = Self::new(JoseObjectKind::Jws, JoseSerialization::Compact, size);
+                let protected_header = decode_header(segments[0])?;
+                object.payload = decode_payload(Some(segments[1]), Some(&protected_header))?;
+                object.entries.push(JoseObjectEntry {
+                    protected_header: Some(protected_header),
+                    header: None,
+                    value: decode(segments[2])?,
+                });
+                object.segments = segments.iter().map(|val| val.to_string()).collect();
+                Ok(object)
+            })()
+            .map_err(JoseError::InvalidJwsFormat),
+            5 => (|| -> anyhow::Result<Self> {
+                let mut object = Self::new(JoseObjectKind::Jwe, JoseSerialization::Compact, size);
+                object.protected_header = Some(decode_header(segments[0])?);
+                object.entries.push(JoseObjectEntry {
+                    protected_header: None,
+                    header: None,
+                    value: decode(segments[1])?,
+                });
+                object.initialization_vector = Some(decode(segments[2])?);
+                object.ciphertext = Some(decode(segments[3])?);
+                object.tag = Some(decode(segments[4])?);
+                object.segments = segments.iter().map(|val| val.to_string()).collect();
+                Ok(object)
+            })()
+            .map_err(JoseError::InvalidJweFormat),
+            len => Err(JoseError::InvalidJwtFormat(anyhow::anyhow!(
+                "The compact serialization must have 3 or 5 segments: {}",
+                len
+            ))),
+        }
+    }
+
+    fn parse_json(input: &str, size: usize) -> Result<Self, JoseError> {
+        let map: Map<String, Value> = match serde_json::from_str(input) {
+            Ok(val) => val,
+            Err(err) => return Err(JoseError::InvalidJson(err.into())),
+        };
+
+        if map.contains_key("ciphertext") {
+            Self::parse_jwe_json(&map, size).map_err(JoseError::InvalidJweFormat)
+        } else if map.contains_key("signatures") || map.contains_key("signature") {
+            Self::parse_jws_json(&map, size).map_err(JoseError::InvalidJwsFormat)
+        } else {
+            Err(JoseError::InvalidJwtFormat(anyhow::anyhow!(
+                "The JSON serialization is neither JWS nor JWE."
+            )))
+        }
+    }
+
+    fn parse_jws_json(map: &Map<String, Value>, size: usize) -> anyhow::Result<Self> {
+        let (serialization, signatures) = match map.get("signatures") {
+            Some(Value::Array(vals)) => {
+                let mut signatures = Vec::with_capacity(vals.len());
+                for val in vals {
+                    match val {
+                        Value::Object(val) => signatures.push(val),
+                        _ => bail!("An element of the signatures must be a object."),
+                    }
+                }
+                (JoseSerialization::GeneralJson, signatures)
+            }
+            Some(_) => bail!("The signatures must be a array."),
+            None => (JoseSerialization::FlattenedJson, vec![map]),
+        };
+
+        let mut object = Self::new(JoseObjectKind::Jws, serialization, size);
+        for signature in signatures {
+            let protected_header = match signature.get("protected") {
+                Some(Value::String(val)) => Some(decode_header(val)?),
+                Some(_) => bail!("The protected field must be a string."),
+                None => None,
+            };
+            let value = match signature.get("signature") {
+                Some(Value::String(val)) => decode(val)?,
+                _ => bail!("The signature field is required."),
+            };
+            object.entries.push(JoseObjectEntry {
+                protected_header,
+                header: object_field(signature, "header")?,
+                value,
+            });
+        }
+
+        let payload = match map.get("payload") {
+            Some(Value::String(val)) => Some(val.as_str()),
+            Some(_) => bail!("The payload field must be a string."),
+            None => None,
+        };
+        let first_header = object
+            .entries
+            .first()
+            .and_then(|val| val.protected_header());
+        object.payload = decode_payload(payload, first_header)?;
+
+        Ok(object)
+    }
+
+    fn parse_jwe_json(map: &Map<String, Value>, size: usize) -> anyhow::Result<Self> {
+        let (serialization, recipients) = match map.get("recipients") {
+            Some(Value::Array(vals)) => {
+                let mut recipients = Vec::with_capacity(vals.len());
+                for val in vals {
+                    match val {
+                        Value::Object(val) => recipients.push(val),
+                        _ => bail!("An element of the recipients must be a object."),
+                    }
+                }
+                (JoseSerialization::GeneralJson, recipients)
+            }
+            Some(_) => bail!("The recipients must be a array."),
+            None => (JoseSerialization::FlattenedJson, vec![map]),
+        };
+
+        let mut object = Self::new(JoseObjectKind::Jwe, serialization, size);
+        object.protected_header = match map.get("protected") {
+            Some(Value::String(val)) => Some(decode_header(val)?),
+            Some(_) => bail!("The protected field must be a string."),
+            None => None,
+        };
+        object.unprotected_header = object_field(map, "unprotected")?;
+
+        for recipient in recipients {
+            let value = match recipient.get("encrypted_key") {
+                Some(Value::String(val)) => decode(val)?,
+                Some(_) => bail!("The encrypted_key field must be a string."),
+                None => Vec::new(),
+            };
+            object.entries.push(JoseObjectEntry {
+                protected_header: None,
+                header: object_field(recipient, "header")?,
+                value,
+            });
+        }
+
+        object.initialization_vector = optional_bytes_field(map, "iv")?;
+        object.ciphertext = optional_bytes_field(map, "ciphertext")?;
+        object.tag = optional_bytes_field(map, "tag")?;
+        object.aad = optional_bytes_field(map, "aad")?;
+
+        Ok(object)
+    }
+}
+
+fn decode(input: &str) -> anyhow::Result<Vec<u8>> {
+    Ok(util::decode_base64_urlsafe_no_pad(input)?)
+}
+
+fn decode_header(input: &str) -> anyhow::Result<Map<String, Value>> {
+    let header = decode(input)?;
+    let header: Map<String, Value> = serde_json::from_slice(&header)?;
+    Ok(header)
+}
+
+fn decode_payload(
+    input: Option<&str>,
+    protected_header: Option<&Map<String, Value>>,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let input = match input {
+        Some("") | None => return Ok(None),
+        Some(val) => val,
+    };
+    match protected_header.and_then(|val| val.get("b64")) {
+        Some(Value::Bool(false)) => Ok(Some(input.as_bytes().to_vec())),
+        _ => Ok(Some(decode(input)?)),
+    }
+}
+
+fn object_field(map: &Map<String, Value>, key: &str) -> anyhow::Result<Option<Map<String, Value>>> {
+    match map.get(key) {
+        Some(Value::Object(val)) => Ok(Some(val.clone())),
+        Some(_) => bail!("The {} field must be a object.", key),
+        None => Ok(None),
+    }
+}
+
+fn optional_bytes_field(map: &Map<String, Value>, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+    match map.get(key) {
+        Some(Value::String(val)) => Ok(Some(decode(val)?)),
+        Some(_) => bail!("The {} field must be a string.", key),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::jwe::{self, Dir, JweHeader, JweHeaderSet};
+    use crate::jws::{self, JwsHeader, JwsHeaderSet, HS256};
+    use crate::util;
+    use crate::{JoseObject, JoseObjectKind, JoseSerialization};
+
+    #[test]
+    fn test_jose_object_jws() -> Result<()> {
+        let key = util::random_bytes(32);
+        let signer = HS256.signer_from_bytes(&key)?;
+
+        let mut header = JwsHeader::new();
+        header.set_key_id("key-1");
+        let input = jws::serialize_compact(b"payload", &header, &signer)?;
+        let object = JoseObject::parse(&input)?;
+        assert_eq!(object.kind(), JoseObjectKind::Jws);
+        assert_eq!(object.serialization(), JoseSerialization::Compact);
+        assert_eq!(object.size(), input.len());
+        assert_eq!(object.segments().len(), 3);
+        assert_eq!(object.header_claim("alg"), Some(&json!("HS256")));
+        assert_eq!(object.header_claim("kid"), Some(&json!("key-1")));
+        assert_eq!(object.payload(), Some(&b"payload"[..]));
+        assert_eq!(object.signatures().len(), 1);
+        assert_eq!(object.signatures()[0].value().len(), 32);
+        assert!(object.recipients().is_empty());
+
+        let mut header1 = JwsHeaderSet::new();
+        header1.set_key_id("key-1", false);
+        let mut header2 = JwsHeaderSet::new();
+        header2.set_key_id("key-2", true);
+        let input =
+            jws::serialize_general_json(b"payload", &[(&header1, &signer), (&header2, &signer)])?;
+        let object = JoseObject::parse(&input)?;
+        assert_eq!(object.kind(), JoseObjectKind::Jws);
+        assert_eq!(object.serialization(), JoseSerialization::GeneralJson);
+        assert!(object.segments().is_empty());
+        assert_eq!(object.payload(), Some(&b"payload"[..]));
+        assert_eq!(object.signatures().len(), 2);
+        assert_eq!(
+            object.signatures()[0]
+                .header()
+                .and_then(|val| val.get("kid")),
+            Some(&json!("key-1"))
+        );
+        assert_eq!(
+            object.signatures()[1]
+                .protected_header()
+                .and_then(|val| val.get("kid")),
+            Some(&json!("key-2"))
+        );
+
+        let input = jws::serialize_flattened_json(b"payload", &header1, &signer)?;
+        let object = JoseObject::parse(&input)?;
+        assert_eq!(object.serialization(), JoseSerialization::FlattenedJson);
+        assert_eq!(object.header_claim("kid"), Some(&json!("key-1")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jose_object_jwe() -> Result<()> {
+        let key = util::random_bytes(16);
+        let encrypter = Dir.encrypter_from_bytes(&key)?;
+
+        let mut header = JweHeader::new();
+        header.set_content_encryption("A128GCM");
+        let input = jwe::serialize_compact(b"payload", &header, &encrypter)?;
+        let object = JoseObject::parse(&input)?;
+        assert_eq!(object.kind(), JoseObjectKind::Jwe);
+        assert_eq!(object.serialization(), JoseSerialization::Compact);
+        assert_eq!(object.segments().len(), 5);
+        assert_eq!(object.header_claim("alg"), Some(&json!("dir")));
+        assert_eq!(object.header_claim("enc"), Some(&json!("A128GCM")));
+        assert_eq!(object.payload(), None);
+        assert_eq!(object.recipients().len(), 1);
+        assert!(object.recipients()[0].value().is_empty());
+        assert_eq!(
+            object.initialization_vector().map(|val| val.len()),
+            Some(12)
+        );
+        assert_eq!(object.ciphertext().map(|val| val.len()), Some(7));
+        assert_eq!(object.tag().map(|val| val.len()), Some(16));
+
+        let mut header_set = JweHeaderSet::new();
+        header_set.set_content_encryption("A128GCM", true);
+        let input = jwe::serialize_flattened_json(
+            b"payload",
+            Some(&header_set),
+            None,
+            Some(b"aad"),
+            &encrypter,
+        )?;
+        let object = JoseObject::parse(&input)?;
+        assert_eq!(object.kind(), JoseObjectKind::Jwe);
+        assert_eq!(object.serialization(), JoseSerialization::FlattenedJson);
+        assert_eq!(object.header_claim("enc"), Some(&json!("A128GCM")));
+        assert_eq!(object.aad(), Some(&b"aad"[..]));
+
+        assert!(JoseObject::parse("a.b").is_err());
+        assert!(JoseObject::parse("{}").is_err());
+        assert!(JoseObject::parse("!!!.e30.").is_err());
+
+        Ok(())
+    }
+}
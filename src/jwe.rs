@@ -10,15 +10,21 @@ mod jwe_header;
 mod jwe_header_set;
 pub mod zip;
 
+use std::io::{Read, Seek, Write};
+
 use once_cell::sync::Lazy;
 
 use crate::JoseError;
 
+#[cfg(feature = "async")]
+pub use crate::jwe::jwe_algorithm::AsyncJweDecrypter;
 pub use crate::jwe::jwe_algorithm::JweAlgorithm;
 pub use crate::jwe::jwe_algorithm::JweDecrypter;
 pub use crate::jwe::jwe_algorithm::JweEncrypter;
 pub use crate::jwe::jwe_compression::JweCompression;
 pub use crate::jwe::jwe_content_encryption::JweContentEncryption;
+pub use crate::jwe::jwe_content_encryption::JweContentStreamDecryptor;
+pub use crate::jwe::jwe_content_encryption::JweContentStreamEncryptor;
 pub use crate::jwe::jwe_context::JweContext;
 pub use crate::jwe::jwe_header::JweHeader;
 pub use crate::jwe::jwe_header_set::JweHeaderSet;
@@ -181,6 +187,102 @@ where
     )
 }
 
+/// Write a representation of the data that is formatted by compact serialization.
+///
+/// # Arguments
+///
+/// * `payload` - The reader of the payload data.
+/// * `header` - The JWE heaser claims.
+/// * `encrypter` - The JWE encrypter.
+/// * `output` - The writer of the serialized data.
+pub fn serialize_compact_from_reader(
+    payload: &mut dyn Read,
+    header: &JweHeader,
+    encrypter: &dyn JweEncrypter,
+    output: &mut dyn Write,
+) -> Result<(), JoseError> {
+    DEFAULT_CONTEXT.serialize_compact_from_reader(payload, header, encrypter, output)
+}
+
+/// Write a representation of the data that is formatted by compact serialization.
+///
+/// # Arguments
+///
+/// * `payload` - The reader of the payload data.
+/// * `header` - The JWE heaser claims.
+/// * `selector` - a function for selecting the encrypting algorithm.
+/// * `output` - The writer of the serialized data.
+pub fn serialize_compact_from_reader_with_selector<'a, F>(
+    payload: &mut dyn Read,
+    header: &JweHeader,
+    selector: F,
+    output: &mut dyn Write,
+) -> Result<(), JoseError>
+where
+    F: Fn(&JweHeader) -> Option<&'a dyn JweEncrypter>,
+{
+    DEFAULT_CONTEXT.serialize_compact_from_reader_with_selector(payload, header, selector, output)
+}
+
+/// Write a representation of the data that is formatted by flattened json serialization.
+///
+/// # Arguments
+///
+/// * `payload` - The reader of the payload data.
+/// * `header` - The JWE shared protected and unprotected header claims.
+/// * `recipient_header` - The JWE unprotected header claims per recipient.
+/// * `aad` - The JWE additional authenticated data.
+/// * `encrypter` - The JWE encrypter.
+/// * `output` - The writer of the serialized data.
+pub fn serialize_flattened_json_from_reader(
+    payload: &mut dyn Read,
+    header: Option<&JweHeaderSet>,
+    recipient_header: Option<&JweHeader>,
+    aad: Option<&[u8]>,
+    encrypter: &dyn JweEncrypter,
+    output: &mut dyn Write,
+) -> Result<(), JoseError> {
+    DEFAULT_CONTEXT.serialize_flattened_json_from_reader(
+        payload,
+        header,
+        recipient_header,
+        aad,
+        encrypter,
+        output,
+    )
+}
+
+/// Write a representation of the data that is formatted by flattened json serialization.
+///
+/// # Arguments
+///
+/// * `payload` - The reader of the payload data.
+/// * `header` - The JWE shared protected and unprotected header claims.
+/// * `recipient_header` - The JWE unprotected header claims per recipient.
+/// * `aad` - The JWE additional authenticated data.
+/// * `selector` - a function for selecting the encrypting algorithm.
+/// * `output` - The writer of the serialized data.
+pub fn serialize_flattened_json_from_reader_with_selector<'a, F>(
+    payload: &mut dyn Read,
+    header: Option<&JweHeaderSet>,
+    recipient_header: Option<&JweHeader>,
+    aad: Option<&[u8]>,
+    selector: F,
+    output: &mut dyn Write,
+) -> Result<(), JoseError>
+where
+    F: Fn(&JweHeader) -> Option<&'a dyn JweEncrypter>,
+{
+    DEFAULT_CONTEXT.serialize_flattened_json_from_reader_with_selector(
+        payload,
+        header,
+        recipient_header,
+        aad,
+        selector,
+        output,
+    )
+}
+
 /// Deserialize the input that is formatted by compact serialization.
 ///
 /// # Arguments
@@ -210,6 +312,44 @@ where
     DEFAULT_CONTEXT.deserialize_compact_with_selector(input, selector)
 }
 
+/// Write the payload of the input that is formatted by compact serialization.
+///
+/// The input is read twice, and the payload is written only after
+/// the authentication tag is verified.
+/// There is no streaming decryption for json serialization.
+///
+/// # Arguments
+///
+/// * `input` - The reader of the input data.
+/// * `decrypter` - The JWE decrypter.
+/// * `output` - The writer of the payload data.
+pub fn deserialize_compact_to_writer<R: Read + Seek>(
+    input: &mut R,
+    decrypter: &dyn JweDecrypter,
+    output: &mut dyn Write,
+) -> Result<JweHeader, JoseError> {
+    DEFAULT_CONTEXT.deserialize_compact_to_writer(input, decrypter, output)
+}
+
+/// Write the payload of the input that is formatted by compact serialization.
+///
+/// # Arguments
+///
+/// * `input` - The reader of the input data.
+/// * `selector` - a function for selecting the decrypting algorithm.
+/// * `output` - The writer of the payload data.
+pub fn deserialize_compact_to_writer_with_selector<'a, R, F>(
+    input: &mut R,
+    selector: F,
+    output: &mut dyn Write,
+) -> Result<JweHeader, JoseError>
+where
+    R: Read + Seek,
+    F: Fn(&JweHeader) -> Result<Option<&'a dyn JweDecrypter>, JoseError>,
+{
+    DEFAULT_CONTEXT.deserialize_compact_to_writer_with_selector(input, selector, output)
+}
+
 /// Deserialize the input that is formatted by flattened json serialization.
 ///
 /// # Arguments
@@ -243,6 +383,7 @@ where
 #[cfg(test)]
 mod tests {
     use std::fs;
+    use std::io::{self, Cursor, Read, Seek, SeekFrom};
     use std::path::PathBuf;
 
     use anyhow::Result;
@@ -383,6 +524,216 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_jwe_compact_serialization_from_reader() -> Result<()> {
+        let src_payload: Vec<u8> = (0..100000).map(|i| (i % 251) as u8).collect();
+
+        for enc in [
+            "A128CBC-HS256",
+            "A192CBC-HS384",
+            "A256CBC-HS512",
+            "A128GCM",
+            "A192GCM",
+            "A256GCM",
+        ] {
+            for zip in [None, Some("DEF")] {
+                let mut src_header = JweHeader::new();
+                src_header.set_content_encryption(enc);
+                if let Some(zip) = zip {
+                    src_header.set_compression(zip);
+                }
+
+                let alg = Dir;
+                let key = match enc {
+                    "A128CBC-HS256" => util::random_bytes(32),
+                    "A192CBC-HS384" => util::random_bytes(48),
+                    "A256CBC-HS512" => util::random_bytes(64),
+                    "A128GCM" => util::random_bytes(16),
+                    "A192GCM" => util::random_bytes(24),
+                    "A256GCM" => util::random_bytes(32),
+                    _ => unreachable!(),
+                };
+                let encrypter = alg.encrypter_from_bytes(&key)?;
+                let mut jwe = Vec::new();
+                jwe::serialize_compact_from_reader(
+                    &mut Cursor::new(&src_payload),
+                    &src_header,
+                    &encrypter,
+                    &mut jwe,
+                )?;
+
+                let decrypter = alg.decrypter_from_bytes(&key)?;
+                let (dst_payload, _) =
+                    jwe::deserialize_compact(std::str::from_utf8(&jwe)?, &decrypter)?;
+                assert_eq!(src_payload, dst_payload);
+
+                let mut dst_payload = Vec::new();
+                let dst_header = jwe::deserialize_compact_to_writer(
+                    &mut Cursor::new(&jwe),
+                    &decrypter,
+                    &mut dst_payload,
+                )?;
+                assert_eq!(src_payload, dst_payload);
+                assert_eq!(dst_header.content_encryption(), Some(enc));
+                assert_eq!(dst_header.compression(), zip);
+
+                let jwe = jwe::serialize_compact(&src_payload, &src_header, &encrypter)?;
+                let mut dst_payload = Vec::new();
+                jwe::deserialize_compact_to_writer(
+                    &mut Cursor::new(jwe.as_bytes()),
+                    &decrypter,
+                    &mut dst_payload,
+                )?;
+                assert_eq!(src_payload, dst_payload);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwe_compact_deserialization_to_writer_rejects_tampered_input() -> Result<()> {
+        let src_payload: Vec<u8> = (0..100000).map(|i| (i % 251) as u8).collect();
+
+        for enc in ["A128CBC-HS256", "A128GCM"] {
+            let mut src_header = JweHeader::new();
+            src_header.set_content_encryption(enc);
+
+            let alg = Dir;
+            let key = match enc {
+                "A128CBC-HS256" => util::random_bytes(32),
+                "A128GCM" => util::random_bytes(16),
+                _ => unreachable!(),
+            };
+            let encrypter = alg.encrypter_from_bytes(&key)?;
+            let jwe = jwe::serialize_compact(&src_payload, &src_header, &encrypter)?;
+
+            let mut tampered = jwe.clone().into_bytes();
+            let pos = tampered.len() / 2;
+            tampered[pos] = if tampered[pos] == b'A' { b'B' } else { b'A' };
+
+            let decrypter = alg.decrypter_from_bytes(&key)?;
+            let mut dst_payload = Vec::new();
+            assert!(jwe::deserialize_compact_to_writer(
+                &mut Cursor::new(&tampered),
+                &decrypter,
+                &mut dst_payload,
+            )
+            .is_err());
+            assert!(dst_payload.is_empty());
+
+            for suffix in [".", ".garbage"] {
+                let trailing = format!("{}{}", jwe, suffix);
+                assert!(jwe::deserialize_compact(&trailing, &decrypter).is_err());
+
+                let mut dst_payload = Vec::new();
+                assert!(jwe::deserialize_compact_to_writer(
+                    &mut Cursor::new(trailing.as_bytes()),
+                    &decrypter,
+                    &mut dst_payload,
+                )
+                .is_err());
+                assert!(dst_payload.is_empty());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwe_compact_serialization_with_interrupted_reader() -> Result<()> {
+        let src_payload: Vec<u8> = (0..100000).map(|i| (i % 251) as u8).collect();
+
+        let mut src_header = JweHeader::new();
+        src_header.set_content_encryption("A128GCM");
+        let key = util::random_bytes(16);
+        let encrypter = Dir.encrypter_from_bytes(&key)?;
+        let decrypter = Dir.decrypter_from_bytes(&key)?;
+
+        let mut jwe = Vec::new();
+        jwe::serialize_compact_from_reader(
+            &mut InterruptingReader::new(Cursor::new(&src_payload)),
+            &src_header,
+            &encrypter,
+            &mut jwe,
+        )?;
+
+        let mut dst_payload = Vec::new();
+        jwe::deserialize_compact_to_writer(
+            &mut InterruptingReader::new(Cursor::new(&jwe)),
+            &decrypter,
+            &mut dst_payload,
+        )?;
+        assert_eq!(src_payload, dst_payload);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwe_json_serialization_from_reader() -> Result<()> {
+        let alg = RSA_OAEP;
+
+        let private_key = load_file("pem/RSA_2048bit_private.pem")?;
+        let public_key = load_file("pem/RSA_2048bit_public.pem")?;
+
+        let src_payload: Vec<u8> = (0..100000).map(|i| (i % 251) as u8).collect();
+        let mut src_header = JweHeaderSet::new();
+        src_header.set_key_id("xxx", true);
+        src_header.set_compression("DEF");
+        src_header.set_token_type("JWT", false);
+        let mut src_rheader = JweHeader::new();
+        src_rheader.set_content_encryption("A128GCM");
+
+        let encrypter = alg.encrypter_from_pem(&public_key)?;
+        let mut jwe = Vec::new();
+        jwe::serialize_flattened_json_from_reader(
+            &mut Cursor::new(&src_payload),
+            Some(&src_header),
+            Some(&src_rheader),
+            Some(b"test"),
+            &encrypter,
+            &mut jwe,
+        )?;
+
+        let decrypter = alg.decrypter_from_pem(&private_key)?;
+        let (dst_payload, dst_header) =
+            jwe::deserialize_json(std::str::from_utf8(&jwe)?, &decrypter)?;
+        assert_eq!(src_payload, dst_payload);
+        assert_eq!(dst_header.key_id(), Some("xxx"));
+
+        Ok(())
+    }
+
+    struct InterruptingReader<R> {
+        inner: R,
+        interrupted: bool,
+    }
+
+    impl<R> InterruptingReader<R> {
+        fn new(inner: R) -> Self {
+            Self {
+                inner,
+                interrupted: false,
+            }
+        }
+    }
+
+    impl<R: Read> Read for InterruptingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupted = !self.interrupted;
+            if self.interrupted {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            self.inner.read(buf)
+        }
+    }
+
+    impl<R: Seek> Seek for InterruptingReader<R> {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
     fn load_file(path: &str) -> Result<Vec<u8>> {
         let mut pb = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         pb.push("data");
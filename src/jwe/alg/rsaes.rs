@@ -9,6 +9,8 @@ use openssl::rsa::Padding;
 
 use crate::jwe::{JweAlgorithm, JweContentEncryption, JweDecrypter, JweEncrypter, JweHeader};
 use crate::jwk::{alg::rsa::RsaKeyPair, Jwk};
+#[cfg(feature = "pkcs11")]
+use crate::pkcs11::{Pkcs11JweDecrypter, Pkcs11Key};
 use crate::util;
 use crate::util::der::{DerBuilder, DerType};
 use crate::{JoseError, Value};
@@ -274,6 +276,16 @@ impl RsaesJweAlgorithm {
         })()
         .map_err(|err| JoseError::InvalidKeyFormat(err))
     }
+
+    /// Return a decrypter from a private key on a PKCS#11 token.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A RSA private key object on a PKCS#11 token.
+    #[cfg(feature = "pkcs11")]
+    pub fn decrypter_from_pkcs11(&self, key: Pkcs11Key) -> Result<Pkcs11JweDecrypter, JoseError> {
+        Pkcs11JweDecrypter::new(self.clone(), key)
+    }
 }
 
 impl JweAlgorithm for RsaesJweAlgorithm {
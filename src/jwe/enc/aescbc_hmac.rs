@@ -3,12 +3,18 @@ use std::{fmt::Display, ops::Deref};
 use anyhow::bail;
 use openssl::{
     hash::MessageDigest,
+    md::{Md, MdRef},
+    md_ctx::MdCtx,
+    memcmp,
     pkey::{PKey, Private},
     sign::Signer,
-    symm::{self, Cipher},
+    symm::{self, Cipher, Crypter, Mode},
 };
 
-use crate::{jwe::JweContentEncryption, JoseError};
+use crate::{
+    jwe::{JweContentEncryption, JweContentStreamDecryptor, JweContentStreamEncryptor},
+    JoseError,
+};
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum AescbcHmacJweEncryption {
@@ -66,6 +72,61 @@ impl AescbcHmacJweEncryption {
 
         Ok(signature)
     }
+
+    fn tag_len(&self) -> usize {
+        match self {
+            Self::A128cbcHs256 => 16,
+            Self::A192cbcHs384 => 24,
+            Self::A256cbcHs512 => 32,
+        }
+    }
+
+    fn md(&self) -> &'static MdRef {
+        match self {
+            Self::A128cbcHs256 => Md::sha256(),
+            Self::A192cbcHs384 => Md::sha384(),
+            Self::A256cbcHs512 => Md::sha512(),
+        }
+    }
+
+    fn stream_context(
+        &self,
+        mode: Mode,
+        key: &[u8],
+        iv: Option<&[u8]>,
+        aad: &[u8],
+    ) -> anyhow::Result<AescbcHmacStreamContext> {
+        let expected_len = self.key_len();
+        if key.len() != expected_len {
+            bail!(
+                "The length of content encryption key must be {}: {}",
+                expected_len,
+                key.len()
+            );
+        }
+
+        let mac_key_len = expected_len / 2;
+        let mac_key = &key[0..mac_key_len];
+        let enc_key = &key[mac_key_len..];
+
+        let crypter = Crypter::new(self.cipher(), mode, enc_key, iv)?;
+
+        let pkey = PKey::hmac(mac_key)?;
+        let mut mac = MdCtx::new()?;
+        mac.digest_sign_init(Some(self.md()), &pkey)?;
+        mac.digest_sign_update(aad)?;
+        if let Some(val) = iv {
+            mac.digest_sign_update(val)?;
+        }
+
+        Ok(AescbcHmacStreamContext {
+            crypter,
+            mac,
+            aad_len: aad.len(),
+            tag_len: self.tag_len(),
+            block_size: self.cipher().block_size(),
+        })
+    }
 }
 
 impl JweContentEncryption for AescbcHmacJweEncryption {
@@ -167,6 +228,30 @@ impl JweContentEncryption for AescbcHmacJweEncryption {
         Ok(message)
     }
 
+    fn stream_encryptor(
+        &self,
+        key: &[u8],
+        iv: Option<&[u8]>,
+        aad: &[u8],
+    ) -> Result<Box<dyn JweContentStreamEncryptor + '_>, JoseError> {
+        let context = self
+            .stream_context(Mode::Encrypt, key, iv, aad)
+            .map_err(JoseError::InvalidKeyFormat)?;
+        Ok(Box::new(AescbcHmacJweContentStreamEncryptor { context }))
+    }
+
+    fn stream_decryptor(
+        &self,
+        key: &[u8],
+        iv: Option<&[u8]>,
+        aad: &[u8],
+    ) -> Result<Box<dyn JweContentStreamDecryptor + '_>, JoseError> {
+        let context = self
+            .stream_context(Mode::Decrypt, key, iv, aad)
+            .map_err(JoseError::InvalidKeyFormat)?;
+        Ok(Box::new(AescbcHmacJweContentStreamDecryptor { context }))
+    }
+
     fn box_clone(&self) -> Box<dyn JweContentEncryption> {
         Box::new(self.clone())
     }
@@ -186,6 +271,100 @@ impl Deref for AescbcHmacJweEncryption {
     }
 }
 
+struct AescbcHmacStreamContext {
+    crypter: Crypter,
+    mac: MdCtx,
+    aad_len: usize,
+    tag_len: usize,
+    block_size: usize,
+}
+
+impl AescbcHmacStreamContext {
+    fn calcurate_tag(&mut self) -> anyhow::Result<Vec<u8>> {
+        let aad_bits = ((self.aad_len * 8) as u64).to_be_bytes();
+        self.mac.digest_sign_update(&aad_bits)?;
+        let mut tag = Vec::new();
+        self.mac.digest_sign_final_to_vec(&mut tag)?;
+        tag.truncate(self.tag_len);
+        Ok(tag)
+    }
+}
+
+struct AescbcHmacJweContentStreamEncryptor {
+    context: AescbcHmacStreamContext,
+}
+
+impl JweContentStreamEncryptor for AescbcHmacJweContentStreamEncryptor {
+    fn update(&mut self, message: &[u8]) -> Result<Vec<u8>, JoseError> {
+        (|| -> anyhow::Result<Vec<u8>> {
+            let context = &mut self.context;
+            let mut encrypted_message = vec![0; message.len() + context.block_size];
+            let len = context.crypter.update(message, &mut encrypted_message)?;
+            encrypted_message.truncate(len);
+            context.mac.digest_sign_update(&encrypted_message)?;
+            Ok(encrypted_message)
+        })()
+        .map_err(JoseError::InvalidJweFormat)
+    }
+
+    fn finalize(mut self: Box<Self>) -> Result<(Vec<u8>, Option<Vec<u8>>), JoseError> {
+        (|| -> anyhow::Result<(Vec<u8>, Option<Vec<u8>>)> {
+            let context = &mut self.context;
+            let mut encrypted_message = vec![0; context.block_size];
+            let len = context.crypter.finalize(&mut encrypted_message)?;
+            encrypted_message.truncate(len);
+            context.mac.digest_sign_update(&encrypted_message)?;
+            let tag = context.calcurate_tag()?;
+            Ok((encrypted_message, Some(tag)))
+        })()
+        .map_err(JoseError::InvalidJweFormat)
+    }
+}
+
+struct AescbcHmacJweContentStreamDecryptor {
+    context: AescbcHmacStreamContext,
+}
+
+impl JweContentStreamDecryptor for AescbcHmacJweContentStreamDecryptor {
+    fn update(&mut self, encrypted_message: &[u8]) -> Result<Vec<u8>, JoseError> {
+        (|| -> anyhow::Result<Vec<u8>> {
+            let context = &mut self.context;
+            context.mac.digest_sign_update(encrypted_message)?;
+            let mut message = vec![0; encrypted_message.len() + context.block_size];
+            let len = context.crypter.update(encrypted_message, &mut message)?;
+            message.truncate(len);
+            Ok(message)
+        })()
+        .map_err(JoseError::InvalidJweFormat)
+    }
+
+    fn finalize(mut self: Box<Self>, tag: Option<&[u8]>) -> Result<Vec<u8>, JoseError> {
+        (|| -> anyhow::Result<()> {
+            let tag = match tag {
+                Some(val) => val,
+                None => bail!("A tag value is required."),
+            };
+
+            let calc_tag = self.context.calcurate_tag()?;
+            if calc_tag.len() != tag.len() || !memcmp::eq(&calc_tag, tag) {
+                bail!("The tag doesn't match.");
+            }
+
+            Ok(())
+        })()
+        .map_err(JoseError::InvalidSignature)?;
+
+        (|| -> anyhow::Result<Vec<u8>> {
+            let context = &mut self.context;
+            let mut message = vec![0; context.block_size];
+            let len = context.crypter.finalize(&mut message)?;
+            message.truncate(len);
+            Ok(message)
+        })()
+        .map_err(JoseError::InvalidJweFormat)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -220,4 +399,52 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn stream_encrypt_and_decrypt_aes_cbc_hmac() -> Result<()> {
+        let message: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+        let aad = b"test";
+
+        for enc in [
+            AescbcHmacJweEncryption::A128cbcHs256,
+            AescbcHmacJweEncryption::A192cbcHs384,
+            AescbcHmacJweEncryption::A256cbcHs512,
+        ] {
+            let key = util::random_bytes(enc.key_len());
+            let iv = util::random_bytes(enc.iv_len());
+
+            let mut encryptor = enc.stream_encryptor(&key, Some(&iv), aad)?;
+            let mut encrypted_message = Vec::new();
+            for chunk in message.chunks(33) {
+                encrypted_message.extend_from_slice(&encryptor.update(chunk)?);
+            }
+            let (last, tag) = encryptor.finalize()?;
+            encrypted_message.extend_from_slice(&last);
+
+            let decrypted_message = enc.decrypt(
+                &key,
+                Some(&iv),
+                &encrypted_message,
+                &aad[..],
+                tag.as_deref(),
+            )?;
+            assert_eq!(message, decrypted_message);
+
+            let mut decryptor = enc.stream_decryptor(&key, Some(&iv), aad)?;
+            let mut decrypted_message = Vec::new();
+            for chunk in encrypted_message.chunks(17) {
+                decrypted_message.extend_from_slice(&decryptor.update(chunk)?);
+            }
+            decrypted_message.extend_from_slice(&decryptor.finalize(tag.as_deref())?);
+            assert_eq!(message, decrypted_message);
+
+            let mut tampered = encrypted_message.clone();
+            tampered[0] ^= 1;
+            let mut decryptor = enc.stream_decryptor(&key, Some(&iv), aad)?;
+            decryptor.update(&tampered)?;
+            assert!(decryptor.finalize(tag.as_deref()).is_err());
+        }
+
+        Ok(())
+    }
 }
@@ -2,9 +2,9 @@ use std::fmt::Display;
 use std::ops::Deref;
 
 use anyhow::bail;
-use openssl::symm::{self, Cipher};
+use openssl::symm::{self, Cipher, Crypter, Mode};
 
-use crate::jwe::JweContentEncryption;
+use crate::jwe::{JweContentEncryption, JweContentStreamDecryptor, JweContentStreamEncryptor};
 use crate::JoseError;
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -25,6 +25,27 @@ impl AesgcmJweEncryption {
             Self::A256gcm => Cipher::aes_256_gcm(),
         }
     }
+
+    fn crypter(
+        &self,
+        mode: Mode,
+        key: &[u8],
+        iv: Option<&[u8]>,
+        aad: &[u8],
+    ) -> anyhow::Result<Crypter> {
+        let expected_len = self.key_len();
+        if key.len() != expected_len {
+            bail!(
+                "The length of content encryption key must be {}: {}",
+                expected_len,
+                key.len()
+            );
+        }
+
+        let mut crypter = Crypter::new(self.cipher(), mode, key, iv)?;
+        crypter.aad_update(aad)?;
+        Ok(crypter)
+    }
 }
 
 impl JweContentEncryption for AesgcmJweEncryption {
@@ -103,6 +124,38 @@ impl JweContentEncryption for AesgcmJweEncryption {
         .map_err(|err| JoseError::InvalidJweFormat(err))
     }
 
+    fn stream_encryptor(
+        &self,
+        key: &[u8],
+        iv: Option<&[u8]>,
+        aad: &[u8],
+    ) -> Result<Box<dyn JweContentStreamEncryptor + '_>, JoseError> {
+        (|| -> anyhow::Result<Box<dyn JweContentStreamEncryptor>> {
+            let crypter = self.crypter(Mode::Encrypt, key, iv, aad)?;
+            Ok(Box::new(AesgcmJweContentStreamEncryptor {
+                crypter,
+                block_size: self.cipher().block_size(),
+            }))
+        })()
+        .map_err(JoseError::InvalidKeyFormat)
+    }
+
+    fn stream_decryptor(
+        &self,
+        key: &[u8],
+        iv: Option<&[u8]>,
+        aad: &[u8],
+    ) -> Result<Box<dyn JweContentStreamDecryptor + '_>, JoseError> {
+        (|| -> anyhow::Result<Box<dyn JweContentStreamDecryptor>> {
+            let crypter = self.crypter(Mode::Decrypt, key, iv, aad)?;
+            Ok(Box::new(AesgcmJweContentStreamDecryptor {
+                crypter,
+                block_size: self.cipher().block_size(),
+            }))
+        })()
+        .map_err(JoseError::InvalidKeyFormat)
+    }
+
     fn box_clone(&self) -> Box<dyn JweContentEncryption> {
         Box::new(self.clone())
     }
@@ -122,6 +175,72 @@ impl Deref for AesgcmJweEncryption {
     }
 }
 
+struct AesgcmJweContentStreamEncryptor {
+    crypter: Crypter,
+    block_size: usize,
+}
+
+impl JweContentStreamEncryptor for AesgcmJweContentStreamEncryptor {
+    fn update(&mut self, message: &[u8]) -> Result<Vec<u8>, JoseError> {
+        (|| -> anyhow::Result<Vec<u8>> {
+            let mut encrypted_message = vec![0; message.len() + self.block_size];
+            let len = self.crypter.update(message, &mut encrypted_message)?;
+            encrypted_message.truncate(len);
+            Ok(encrypted_message)
+        })()
+        .map_err(JoseError::InvalidJweFormat)
+    }
+
+    fn finalize(mut self: Box<Self>) -> Result<(Vec<u8>, Option<Vec<u8>>), JoseError> {
+        (|| -> anyhow::Result<(Vec<u8>, Option<Vec<u8>>)> {
+            let mut encrypted_message = vec![0; self.block_size];
+            let len = self.crypter.finalize(&mut encrypted_message)?;
+            encrypted_message.truncate(len);
+
+            let mut tag = [0; 16];
+            self.crypter.get_tag(&mut tag)?;
+            Ok((encrypted_message, Some(tag.to_vec())))
+        })()
+        .map_err(JoseError::InvalidJweFormat)
+    }
+}
+
+struct AesgcmJweContentStreamDecryptor {
+    crypter: Crypter,
+    block_size: usize,
+}
+
+impl JweContentStreamDecryptor for AesgcmJweContentStreamDecryptor {
+    fn update(&mut self, encrypted_message: &[u8]) -> Result<Vec<u8>, JoseError> {
+        (|| -> anyhow::Result<Vec<u8>> {
+            let mut message = vec![0; encrypted_message.len() + self.block_size];
+            let len = self.crypter.update(encrypted_message, &mut message)?;
+            message.truncate(len);
+            Ok(message)
+        })()
+        .map_err(JoseError::InvalidJweFormat)
+    }
+
+    fn finalize(mut self: Box<Self>, tag: Option<&[u8]>) -> Result<Vec<u8>, JoseError> {
+        (|| -> anyhow::Result<Vec<u8>> {
+            let tag = match tag {
+                Some(val) => val,
+                None => bail!("A tag value is required."),
+            };
+
+            self.crypter.set_tag(tag)?;
+            let mut message = vec![0; self.block_size];
+            let len = match self.crypter.finalize(&mut message) {
+                Ok(val) => val,
+                Err(_) => bail!("The tag doesn't match."),
+            };
+            message.truncate(len);
+            Ok(message)
+        })()
+        .map_err(JoseError::InvalidJweFormat)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -156,4 +275,52 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn stream_encrypt_and_decrypt_aes_gcm() -> Result<()> {
+        let message: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+        let aad = b"test";
+
+        for enc in [
+            AesgcmJweEncryption::A128gcm,
+            AesgcmJweEncryption::A192gcm,
+            AesgcmJweEncryption::A256gcm,
+        ] {
+            let key = util::random_bytes(enc.key_len());
+            let iv = util::random_bytes(enc.iv_len());
+
+            let mut encryptor = enc.stream_encryptor(&key, Some(&iv), aad)?;
+            let mut encrypted_message = Vec::new();
+            for chunk in message.chunks(33) {
+                encrypted_message.extend_from_slice(&encryptor.update(chunk)?);
+            }
+            let (last, tag) = encryptor.finalize()?;
+            encrypted_message.extend_from_slice(&last);
+
+            let decrypted_message = enc.decrypt(
+                &key,
+                Some(&iv),
+                &encrypted_message,
+                &aad[..],
+                tag.as_deref(),
+            )?;
+            assert_eq!(message, decrypted_message);
+
+            let mut decryptor = enc.stream_decryptor(&key, Some(&iv), aad)?;
+            let mut decrypted_message = Vec::new();
+            for chunk in encrypted_message.chunks(17) {
+                decrypted_message.extend_from_slice(&decryptor.update(chunk)?);
+            }
+            decrypted_message.extend_from_slice(&decryptor.finalize(tag.as_deref())?);
+            assert_eq!(message, decrypted_message);
+
+            let mut tampered = encrypted_message.clone();
+            tampered[0] ^= 1;
+            let mut decryptor = enc.stream_decryptor(&key, Some(&iv), aad)?;
+            decryptor.update(&tampered)?;
+            assert!(decryptor.finalize(tag.as_deref()).is_err());
+        }
+
+        Ok(())
+    }
 }
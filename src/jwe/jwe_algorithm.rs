@@ -101,3 +101,29 @@ impl Clone for Box<dyn JweDecrypter> {
         self.box_clone()
     }
 }
+
+/// A decrypter that decrypts content encryption keys asynchronously,
+/// e.g. by calling a remote KMS or HSM.
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncJweDecrypter: Debug + Send + Sync {
+    /// Return the source algorithm instance.
+    fn algorithm(&self) -> &dyn JweAlgorithm;
+
+    /// Return the source key ID.
+    fn key_id(&self) -> Option<&str>;
+
+    /// Return a decrypted key.
+    ///
+    /// # Arguments
+    ///
+    /// * `encrypted_key` - The encrypted key.
+    /// * `cencryption` - The content encryption method.
+    /// * `header` - The header
+    async fn decrypt(
+        &self,
+        encrypted_key: Option<&[u8]>,
+        cencryption: &dyn JweContentEncryption,
+        header: &JweHeader,
+    ) -> Result<Vec<u8>, JoseError>;
+}
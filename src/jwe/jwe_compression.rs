@@ -1,6 +1,6 @@
 use std::cmp::Eq;
 use std::fmt::Debug;
-use std::io;
+use std::io::{self, Cursor, Read};
 
 /// Represent a algorithm of JWE zip header claim.
 pub trait JweCompression: Debug + Send + Sync {
@@ -11,6 +11,40 @@ pub trait JweCompression: Debug + Send + Sync {
 
     fn decompress(&self, message: &[u8]) -> Result<Vec<u8>, io::Error>;
 
+    /// Return a reader that compresses the data read from the input.
+    ///
+    /// The default implementation reads the whole input and calls `compress`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The reader of the uncompressed data.
+    fn compress_reader<'a>(
+        &self,
+        input: Box<dyn Read + 'a>,
+    ) -> Result<Box<dyn Read + 'a>, io::Error> {
+        let mut input = input;
+        let mut message = Vec::new();
+        input.read_to_end(&mut message)?;
+        Ok(Box::new(Cursor::new(self.compress(&message)?)))
+    }
+
+    /// Return a reader that decompresses the data read from the input.
+    ///
+    /// The default implementation reads the whole input and calls `decompress`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The reader of the compressed data.
+    fn decompress_reader<'a>(
+        &self,
+        input: Box<dyn Read + 'a>,
+    ) -> Result<Box<dyn Read + 'a>, io::Error> {
+        let mut input = input;
+        let mut message = Vec::new();
+        input.read_to_end(&mut message)?;
+        Ok(Box::new(Cursor::new(self.decompress(&message)?)))
+    }
+
     fn box_clone(&self) -> Box<dyn JweCompression>;
 }
 
@@ -29,6 +29,54 @@ pub trait JweContentEncryption: Debug + Send + Sync {
         tag: Option<&[u8]>,
     ) -> Result<Vec<u8>, JoseError>;
 
+    /// Return a encryptor to encrypt a message incrementally.
+    ///
+    /// The default implementation buffers the whole message and calls `encrypt` when finalized.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The content encryption key.
+    /// * `iv` - The initialization vector.
+    /// * `aad` - The additional authenticated data.
+    fn stream_encryptor(
+        &self,
+        key: &[u8],
+        iv: Option<&[u8]>,
+        aad: &[u8],
+    ) -> Result<Box<dyn JweContentStreamEncryptor + '_>, JoseError> {
+        Ok(Box::new(BufferedJweContentStreamEncryptor {
+            cencryption: self,
+            key: key.to_vec(),
+            iv: iv.map(|val| val.to_vec()),
+            aad: aad.to_vec(),
+            message: Vec::new(),
+        }))
+    }
+
+    /// Return a decryptor to decrypt a encrypted message incrementally.
+    ///
+    /// The default implementation buffers the whole encrypted message and calls `decrypt` when finalized.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The content encryption key.
+    /// * `iv` - The initialization vector.
+    /// * `aad` - The additional authenticated data.
+    fn stream_decryptor(
+        &self,
+        key: &[u8],
+        iv: Option<&[u8]>,
+        aad: &[u8],
+    ) -> Result<Box<dyn JweContentStreamDecryptor + '_>, JoseError> {
+        Ok(Box::new(BufferedJweContentStreamDecryptor {
+            cencryption: self,
+            key: key.to_vec(),
+            iv: iv.map(|val| val.to_vec()),
+            aad: aad.to_vec(),
+            encrypted_message: Vec::new(),
+        }))
+    }
+
     fn box_clone(&self) -> Box<dyn JweContentEncryption>;
 }
 
@@ -45,3 +93,85 @@ impl Clone for Box<dyn JweContentEncryption> {
         self.box_clone()
     }
 }
+
+/// Represent a incremental content encryption.
+pub trait JweContentStreamEncryptor {
+    /// Encrypt a part of the message and return the encrypted part.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - A part of the message.
+    fn update(&mut self, message: &[u8]) -> Result<Vec<u8>, JoseError>;
+
+    /// Return the rest of the encrypted message and the authentication tag.
+    fn finalize(self: Box<Self>) -> Result<(Vec<u8>, Option<Vec<u8>>), JoseError>;
+}
+
+/// Represent a incremental content decryption.
+///
+/// The data returned by `update` is not authenticated yet.
+/// It must not be released before `finalize` succeeds.
+pub trait JweContentStreamDecryptor {
+    /// Decrypt a part of the encrypted message and return the decrypted part.
+    ///
+    /// # Arguments
+    ///
+    /// * `encrypted_message` - A part of the encrypted message.
+    fn update(&mut self, encrypted_message: &[u8]) -> Result<Vec<u8>, JoseError>;
+
+    /// Verify the authentication tag and return the rest of the decrypted message.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The authentication tag.
+    fn finalize(self: Box<Self>, tag: Option<&[u8]>) -> Result<Vec<u8>, JoseError>;
+}
+
+struct BufferedJweContentStreamEncryptor<'a, T: JweContentEncryption + ?Sized> {
+    cencryption: &'a T,
+    key: Vec<u8>,
+    iv: Option<Vec<u8>>,
+    aad: Vec<u8>,
+    message: Vec<u8>,
+}
+
+impl<'a, T: JweContentEncryption + ?Sized> JweContentStreamEncryptor
+    for BufferedJweContentStreamEncryptor<'a, T>
+{
+    fn update(&mut self, message: &[u8]) -> Result<Vec<u8>, JoseError> {
+        self.message.extend_from_slice(message);
+        Ok(Vec::new())
+    }
+
+    fn finalize(self: Box<Self>) -> Result<(Vec<u8>, Option<Vec<u8>>), JoseError> {
+        self.cencryption
+            .encrypt(&self.key, self.iv.as_deref(), &self.message, &self.aad)
+    }
+}
+
+struct BufferedJweContentStreamDecryptor<'a, T: JweContentEncryption + ?Sized> {
+    cencryption: &'a T,
+    key: Vec<u8>,
+    iv: Option<Vec<u8>>,
+    aad: Vec<u8>,
+    encrypted_message: Vec<u8>,
+}
+
+impl<'a, T: JweContentEncryption + ?Sized> JweContentStreamDecryptor
+    for BufferedJweContentStreamDecryptor<'a, T>
+{
+    fn update(&mut self, encrypted_message: &[u8]) -> Result<Vec<u8>, JoseError> {
+        self.encrypted_message.extend_from_slice(encrypted_message);
+        Ok(Vec::new())
+    }
+
+    fn finalize(self: Box<Self>, tag: Option<&[u8]>) -> Result<Vec<u8>, JoseError> {
+        self.cencryption.decrypt(
+            &self.key,
+            self.iv.as_deref(),
+            &self.encrypted_message,
+            &self.aad,
+            tag,
+        )
+    }
+}
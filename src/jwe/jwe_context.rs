@@ -2,17 +2,24 @@ use std::borrow::Cow;
 use std::cmp::Eq;
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Debug;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
 
 use anyhow::bail;
 
 use crate::jwe::enc::{A128CBC_HS256, A128GCM, A192CBC_HS384, A192GCM, A256CBC_HS512, A256GCM};
 use crate::jwe::zip::Def;
+#[cfg(feature = "async")]
+use crate::jwe::AsyncJweDecrypter;
 use crate::jwe::{
-    JweCompression, JweContentEncryption, JweDecrypter, JweEncrypter, JweHeader, JweHeaderSet,
+    JweAlgorithm, JweCompression, JweContentEncryption, JweContentStreamDecryptor, JweDecrypter,
+    JweEncrypter, JweHeader, JweHeaderSet,
 };
 use crate::util;
 use crate::{JoseError, JoseHeader, Map, Value};
 
+const STREAM_BUFFER_SIZE: usize = 8192;
+const MAX_SEGMENT_LEN: u64 = 1024 * 1024;
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct JweContext {
     acceptable_criticals: BTreeSet<String>,
@@ -174,96 +181,34 @@ impl JweContext {
         F: Fn(&JweHeader) -> Option<&'a dyn JweEncrypter>,
     {
         (|| -> anyhow::Result<String> {
-            let encrypter = match selector(header) {
-                Some(val) => val,
-                None => bail!("A encrypter is not found."),
-            };
-
-            let cencryption = match header.content_encryption() {
-                Some(enc) => match self.get_content_encryption(enc) {
-                    Some(val) => val,
-                    None => bail!("A content encryption is not registered: {}", enc),
-                },
-                None => bail!("A enc header claim is required."),
-            };
-
-            let compression = match header.compression() {
-                Some(zip) => match self.get_compression(zip) {
-                    Some(val) => Some(val),
-                    None => bail!("A compression algorithm is not registered: {}", zip),
-                },
-                None => None,
-            };
-
-            let mut out_header = header.clone();
-
-            let key_len = cencryption.key_len();
-            let key = match encrypter.compute_content_encryption_key(
-                cencryption,
-                &header,
-                &mut out_header,
-            )? {
-                Some(val) => val,
-                None => Cow::Owned(util::random_bytes(key_len)),
-            };
-
-            let encrypted_key = encrypter.encrypt(&key, &header, &mut out_header)?;
-            if let None = header.claim("kid") {
-                if let Some(key_id) = encrypter.key_id() {
-                    out_header.set_key_id(key_id);
-                }
-            }
-
-            out_header.set_algorithm(encrypter.algorithm().name());
-
-            let header_bytes = serde_json::to_vec(out_header.claims_set())?;
-            let header_b64 = util::encode_base64_urlsafe_nopad(header_bytes);
+            let prepared = self.prepare_compact_encryption(header, selector)?;
 
             let compressed;
-            let content = if let Some(compression) = compression {
+            let content = if let Some(compression) = prepared.compression {
                 compressed = compression.compress(payload)?;
                 &compressed
             } else {
                 payload
             };
 
-            let iv_vec;
-            let iv = if cencryption.iv_len() > 0 {
-                iv_vec = util::random_bytes(cencryption.iv_len());
-                Some(iv_vec.as_slice())
-            } else {
-                None
-            };
-
-            let (ciphertext, tag) =
-                cencryption.encrypt(&key, iv, content, header_b64.as_bytes())?;
+            let (ciphertext, tag) = prepared.cencryption.encrypt(
+                &prepared.key,
+                prepared.iv.as_deref(),
+                content,
+                prepared.aad.as_bytes(),
+            )?;
 
-            let mut capacity = 4;
-            capacity += header_b64.len();
-            if let Some(val) = &encrypted_key {
-                capacity += util::ceiling(val.len() * 4, 3);
-            }
-            if let Some(val) = iv {
-                capacity += util::ceiling(val.len() * 4, 3);
-            }
+            let mut capacity = 1;
+            capacity += prepared.prefix.len();
             capacity += util::ceiling(ciphertext.len() * 4, 3);
             if let Some(val) = &tag {
                 capacity += util::ceiling(val.len() * 4, 3);
             }
 
             let mut message = String::with_capacity(capacity);
-            message.push_str(&header_b64);
-            message.push_str(".");
-            if let Some(val) = &encrypted_key {
-                util::encode_base64_urlsafe_nopad_buf(val, &mut message);
-            }
-            message.push_str(".");
-            if let Some(val) = iv {
-                util::encode_base64_urlsafe_nopad_buf(val, &mut message);
-            }
-            message.push_str(".");
+            message.push_str(&prepared.prefix);
             util::encode_base64_urlsafe_nopad_buf(ciphertext, &mut message);
-            message.push_str(".");
+            message.push('.');
             if let Some(val) = &tag {
                 util::encode_base64_urlsafe_nopad_buf(val, &mut message);
             }
@@ -276,6 +221,69 @@ impl JweContext {
         })
     }
 
+    /// Write a representation of the data that is formatted by compact serialization.
+    ///
+    /// The payload is read from the reader, and encrypted and written incrementally,
+    /// so it is never loaded into memory at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The reader of the payload data.
+    /// * `header` - The JWE heaser claims.
+    /// * `encrypter` - The JWE encrypter.
+    /// * `output` - The writer of the serialized data.
+    pub fn serialize_compact_from_reader(
+        &self,
+        payload: &mut dyn Read,
+        header: &JweHeader,
+        encrypter: &dyn JweEncrypter,
+        output: &mut dyn Write,
+    ) -> Result<(), JoseError> {
+        self.serialize_compact_from_reader_with_selector(
+            payload,
+            header,
+            |_header| Some(encrypter),
+            output,
+        )
+    }
+
+    /// Write a representation of the data that is formatted by compact serialization.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The reader of the payload data.
+    /// * `header` - The JWE heaser claims.
+    /// * `selector` - a function for selecting the encrypting algorithm.
+    /// * `output` - The writer of the serialized data.
+    pub fn serialize_compact_from_reader_with_selector<'a, F>(
+        &self,
+        payload: &mut dyn Read,
+        header: &JweHeader,
+        selector: F,
+        output: &mut dyn Write,
+    ) -> Result<(), JoseError>
+    where
+        F: Fn(&JweHeader) -> Option<&'a dyn JweEncrypter>,
+    {
+        (|| -> anyhow::Result<()> {
+            let prepared = self.prepare_compact_encryption(header, selector)?;
+
+            output.write_all(prepared.prefix.as_bytes())?;
+            let tag = prepared.encrypt_to_writer(payload, output)?;
+            output.write_all(b".")?;
+            if let Some(val) = &tag {
+                output.write_all(util::encode_base64_urlsafe_nopad(val).as_bytes())?;
+            }
+            output.flush()?;
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJweFormat(err),
+        })
+    }
+
     /// Return a representation of the data that is formatted by general json serialization.
     ///
     /// # Arguments
@@ -628,178 +636,25 @@ impl JweContext {
         F: Fn(&JweHeader) -> Option<&'a dyn JweEncrypter>,
     {
         (|| -> anyhow::Result<String> {
-            let mut compression = None;
-            if let Some(header) = header {
-                match header.claims_set(true).get("zip") {
-                    Some(Value::String(val)) => match self.get_compression(val) {
-                        Some(val) => {
-                            compression = Some(val);
-                        }
-                        None => bail!("A compression algorithm is not registered: {}", val),
-                    },
-                    Some(_) => bail!("A zip header claim must be a string."),
-                    None => {}
-                }
-            };
-
-            let mut merged_map = match header {
-                Some(val) => val.to_map(),
-                None => Map::new(),
-            };
-
-            if let Some(val) = recipient_header {
-                for (key, value) in val.claims_set() {
-                    if merged_map.contains_key(key) {
-                        bail!("Duplicate key exists: {}", key);
-                    }
-                    merged_map.insert(key.clone(), value.clone());
-                }
-            }
-
-            let merged = JweHeader::from_map(merged_map)?;
-
-            let cencryption = match merged.claim("enc") {
-                Some(Value::String(enc)) => match self.get_content_encryption(enc) {
-                    Some(val) => val,
-                    None => bail!("A content encryption is not registered: {}", enc),
-                },
-                Some(_) => bail!("A enc header claim must be a string."),
-                None => bail!("A enc header claim is required."),
-            };
-
-            let encrypter = match selector(&merged) {
-                Some(val) => val,
-                None => bail!("A encrypter is not found."),
-            };
-
-            let mut protected = match header {
-                Some(val) => JweHeader::from_map(val.claims_set(true).clone())?,
-                None => JweHeader::new(),
-            };
-
-            let key = match encrypter.compute_content_encryption_key(
-                cencryption,
-                &merged,
-                &mut protected,
-            )? {
-                Some(val) => val,
-                None => Cow::Owned(util::random_bytes(cencryption.key_len())),
-            };
-
-            let encrypted_key = encrypter.encrypt(&key, &merged, &mut protected)?;
-
-            match merged.algorithm() {
-                Some(val) if val == encrypter.algorithm().name() => {}
-                Some(_) => bail!("A signer is unmatched."),
-                None => {
-                    protected.set_algorithm(encrypter.algorithm().name().to_string());
-                }
-            }
-
-            if let None = merged.key_id() {
-                if let Some(key_id) = encrypter.key_id() {
-                    protected.set_key_id(key_id.to_string());
-                }
-            }
-
-            let iv_vec;
-            let iv = if cencryption.iv_len() > 0 {
-                iv_vec = util::random_bytes(cencryption.iv_len());
-                Some(iv_vec.as_slice())
-            } else {
-                None
-            };
-
-            let protected_b64 = if protected.len() > 0 {
-                let protected_json = serde_json::to_vec(protected.claims_set())?;
-                let protected_b64 = util::encode_base64_urlsafe_nopad(protected_json);
-                Some(protected_b64)
-            } else {
-                None
-            };
-
-            let aad_b64 = match aad {
-                Some(val) => Some(util::encode_base64_urlsafe_nopad(val)),
-                None => None,
-            };
-
-            let mut full_aad = String::with_capacity({
-                let mut full_aad_capacity = 1;
-                if let Some(val) = &protected_b64 {
-                    full_aad_capacity += val.len();
-                }
-                if let Some(val) = &aad_b64 {
-                    full_aad_capacity += val.len();
-                }
-                full_aad_capacity
-            });
-            if let Some(val) = &protected_b64 {
-                full_aad.push_str(&val);
-            }
-            if let Some(val) = &aad_b64 {
-                full_aad.push_str(".");
-                full_aad.push_str(&val);
-            }
+            let prepared =
+                self.prepare_flattened_json_encryption(header, recipient_header, aad, selector)?;
 
             let compressed;
-            let content = if let Some(compression) = compression {
+            let content = if let Some(compression) = prepared.compression {
                 compressed = compression.compress(payload)?;
                 &compressed
             } else {
                 payload
             };
 
-            let (ciphertext, tag) = cencryption.encrypt(&key, iv, content, full_aad.as_bytes())?;
-
-            let mut writed = false;
-            let mut json = String::new();
-            if let Some(val) = protected_b64 {
-                json.push_str("{\"protected\":\"");
-                json.push_str(&val);
-                json.push_str("\"");
-                writed = true;
-            }
-
-            if let Some(val) = header {
-                let unprotected_map = val.claims_set(false);
-                if unprotected_map.len() > 0 {
-                    let unprotected = serde_json::to_string(unprotected_map)?;
-                    json.push_str(if writed { "," } else { "{" });
-                    json.push_str("\"unprotected\":");
-                    json.push_str(&unprotected);
-                    writed = true;
-                }
-            }
-
-            if let Some(val) = recipient_header {
-                let header_map = val.claims_set();
-                if header_map.len() > 0 {
-                    let header = serde_json::to_string(header_map)?;
-                    json.push_str(if writed { "," } else { "{" });
-                    json.push_str("\"header\":");
-                    json.push_str(&header);
-                }
-            }
-
-            if let Some(val) = encrypted_key {
-                json.push_str(",\"encrypted_key\":\"");
-                util::encode_base64_urlsafe_nopad_buf(&val, &mut json);
-                json.push_str("\"");
-            }
-
-            if let Some(val) = aad_b64 {
-                json.push_str(",\"aad\":\"");
-                json.push_str(&val);
-                json.push_str("\"");
-            }
-
-            json.push_str(",\"iv\":\"");
-            if let Some(val) = iv {
-                util::encode_base64_urlsafe_nopad_buf(&val, &mut json);
-            }
-            json.push_str("\"");
+            let (ciphertext, tag) = prepared.cencryption.encrypt(
+                &prepared.key,
+                prepared.iv.as_deref(),
+                content,
+                prepared.aad.as_bytes(),
+            )?;
 
-            json.push_str(",\"ciphertext\":\"");
+            let mut json = prepared.prefix;
             util::encode_base64_urlsafe_nopad_buf(&ciphertext, &mut json);
             json.push_str("\"");
 
@@ -817,6 +672,81 @@ impl JweContext {
         })
     }
 
+    /// Write a representation of the data that is formatted by flattened json serialization.
+    ///
+    /// The payload is read from the reader, and encrypted and written incrementally,
+    /// so it is never loaded into memory at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The reader of the payload data.
+    /// * `header` - The JWE shared protected and unprotected header claims.
+    /// * `recipient_header` - The JWE unprotected header claims per recipient.
+    /// * `aad` - The JWE additional authenticated data.
+    /// * `encrypter` - The JWE encrypter.
+    /// * `output` - The writer of the serialized data.
+    pub fn serialize_flattened_json_from_reader(
+        &self,
+        payload: &mut dyn Read,
+        header: Option<&JweHeaderSet>,
+        recipient_header: Option<&JweHeader>,
+        aad: Option<&[u8]>,
+        encrypter: &dyn JweEncrypter,
+        output: &mut dyn Write,
+    ) -> Result<(), JoseError> {
+        self.serialize_flattened_json_from_reader_with_selector(
+            payload,
+            header,
+            recipient_header,
+            aad,
+            |_header| Some(encrypter),
+            output,
+        )
+    }
+
+    /// Write a representation of the data that is formatted by flattened json serialization.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The reader of the payload data.
+    /// * `header` - The JWE shared protected and unprotected header claims.
+    /// * `recipient_header` - The JWE unprotected header claims per recipient.
+    /// * `aad` - The JWE additional authenticated data.
+    /// * `selector` - a function for selecting the encrypting algorithm.
+    /// * `output` - The writer of the serialized data.
+    pub fn serialize_flattened_json_from_reader_with_selector<'a, F>(
+        &self,
+        payload: &mut dyn Read,
+        header: Option<&JweHeaderSet>,
+        recipient_header: Option<&JweHeader>,
+        aad: Option<&[u8]>,
+        selector: F,
+        output: &mut dyn Write,
+    ) -> Result<(), JoseError>
+    where
+        F: Fn(&JweHeader) -> Option<&'a dyn JweEncrypter>,
+    {
+        (|| -> anyhow::Result<()> {
+            let prepared =
+                self.prepare_flattened_json_encryption(header, recipient_header, aad, selector)?;
+
+            output.write_all(prepared.prefix.as_bytes())?;
+            let tag = prepared.encrypt_to_writer(payload, output)?;
+            output.write_all(b"\",\"tag\":\"")?;
+            if let Some(val) = &tag {
+                output.write_all(util::encode_base64_urlsafe_nopad(val).as_bytes())?;
+            }
+            output.write_all(b"\"}")?;
+            output.flush()?;
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJweFormat(err),
+        })
+    }
+
     /// Deserialize the input that is formatted by compact serialization.
     ///
     /// # Arguments
@@ -846,114 +776,143 @@ impl JweContext {
         F: Fn(&JweHeader) -> Result<Option<&'a dyn JweDecrypter>, JoseError>,
     {
         (|| -> anyhow::Result<(Vec<u8>, JweHeader)> {
-            let input = input.as_ref();
-            let indexies: Vec<usize> = input
-                .iter()
-                .enumerate()
-                .filter(|(_, b)| **b == b'.' as u8)
-                .map(|(pos, _)| pos)
-                .collect();
-            if indexies.len() != 4 {
-                bail!(
-                    "The compact serialization form of JWE must be five parts separated by colon."
-                );
-            }
+            let parts = CompactParts::parse(input.as_ref())?;
 
-            let header_b64 = &input[0..indexies[0]];
+            let prepared = self.prepare_compact_decryption(
+                parts.header_b64,
+                parts.encrypted_key.as_deref(),
+                selector,
+            )?;
+            let content =
+                parts.decrypt(prepared.cencryption, prepared.compression, &prepared.key)?;
 
-            let encrypted_key_b64 = &input[(indexies[0] + 1)..(indexies[1])];
-            let encrypted_key_vec;
-            let encrypted_key = if encrypted_key_b64.len() > 0 {
-                encrypted_key_vec = util::decode_base64_urlsafe_no_pad(encrypted_key_b64)?;
-                Some(encrypted_key_vec.as_slice())
-            } else {
-                None
-            };
+            Ok((content, prepared.header))
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJweFormat(err),
+        })
+    }
 
-            let iv_b64 = &input[(indexies[1] + 1)..(indexies[2])];
-            let iv_vec;
-            let iv = if iv_b64.len() > 0 {
-                iv_vec = util::decode_base64_urlsafe_no_pad(iv_b64)?;
-                Some(iv_vec.as_slice())
-            } else {
-                None
-            };
+    /// Write the payload of the input that is formatted by compact serialization.
+    ///
+    /// The input is read twice. The first pass verifies the authentication tag,
+    /// and the second pass decrypts and writes the payload incrementally.
+    /// So the output never receives unauthenticated data,
+    /// and neither the input nor the payload is loaded into memory at once.
+    /// The input must not be modified between the passes.
+    ///
+    /// There is no streaming decryption for json serialization,
+    /// since its members may come in any order. Use deserialize_json instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The reader of the input data.
+    /// * `decrypter` - The JWE decrypter.
+    /// * `output` - The writer of the payload data.
+    pub fn deserialize_compact_to_writer<R: Read + Seek>(
+        &self,
+        input: &mut R,
+        decrypter: &dyn JweDecrypter,
+        output: &mut dyn Write,
+    ) -> Result<JweHeader, JoseError> {
+        self.deserialize_compact_to_writer_with_selector(
+            input,
+            |_header| Ok(Some(decrypter)),
+            output,
+        )
+    }
+
+    /// Write the payload of the input that is formatted by compact serialization.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The reader of the input data.
+    /// * `selector` - a function for selecting the decrypting algorithm.
+    /// * `output` - The writer of the payload data.
+    pub fn deserialize_compact_to_writer_with_selector<'a, R, F>(
+        &self,
+        input: &mut R,
+        selector: F,
+        output: &mut dyn Write,
+    ) -> Result<JweHeader, JoseError>
+    where
+        R: Read + Seek,
+        F: Fn(&JweHeader) -> Result<Option<&'a dyn JweDecrypter>, JoseError>,
+    {
+        (|| -> anyhow::Result<JweHeader> {
+            let start = input.stream_position()?;
 
-            let ciphertext_b64 = &input[(indexies[2] + 1)..(indexies[3])];
-            let ciphertext = util::decode_base64_urlsafe_no_pad(ciphertext_b64)?;
+            let mut reader = BufReader::new(&mut *input);
+            let header_b64 = read_segment(&mut reader)?;
+            let encrypted_key_b64 = read_segment(&mut reader)?;
+            let iv_b64 = read_segment(&mut reader)?;
+            let offset = (header_b64.len() + encrypted_key_b64.len() + iv_b64.len() + 3) as u64;
 
-            let tag_b64 = &input[(indexies[3] + 1)..];
-            let tag_vec;
-            let tag = if tag_b64.len() > 0 {
-                tag_vec = util::decode_base64_urlsafe_no_pad(tag_b64)?;
-                Some(tag_vec.as_slice())
+            let encrypted_key_vec;
+            let encrypted_key = if !encrypted_key_b64.is_empty() {
+                encrypted_key_vec = util::decode_base64_urlsafe_no_pad(&encrypted_key_b64)?;
+                Some(encrypted_key_vec.as_slice())
             } else {
                 None
             };
 
-            let header = util::decode_base64_urlsafe_no_pad(header_b64)?;
-            let merged: Map<String, Value> = serde_json::from_slice(&header)?;
-            let merged = JweHeader::from_map(merged)?;
-
-            let decrypter = match selector(&merged)? {
-                Some(val) => val,
-                None => bail!("A decrypter is not found."),
-            };
-
-            let cencryption = match merged.claim("enc") {
-                Some(Value::String(val)) => match self.get_content_encryption(val) {
-                    Some(val2) => val2,
-                    None => bail!("A content encryption is not registered: {}", val),
-                },
-                Some(_) => bail!("A enc header claim must be a string."),
-                None => bail!("A enc header claim is required."),
-            };
-
-            let compression = match merged.claim("zip") {
-                Some(Value::String(val)) => match self.get_compression(val) {
-                    Some(val2) => Some(val2),
-                    None => bail!("A compression algorithm is not registered: {}", val),
-                },
-                Some(_) => bail!("A enc header claim must be a string."),
-                None => None,
+            let iv_vec;
+            let iv = if !iv_b64.is_empty() {
+                iv_vec = util::decode_base64_urlsafe_no_pad(&iv_b64)?;
+                Some(iv_vec.as_slice())
+            } else {
+                None
             };
 
-            match merged.claim("alg") {
-                Some(Value::String(val)) => {
-                    let expected_alg = decrypter.algorithm().name();
-                    if val != expected_alg {
-                        bail!("The JWE alg header claim is not {}: {}", expected_alg, val);
-                    }
+            let prepared = self.prepare_compact_decryption(&header_b64, encrypted_key, selector)?;
+
+            let mut decryptor =
+                prepared
+                    .cencryption
+                    .stream_decryptor(&prepared.key, iv, &header_b64)?;
+            let mut ciphertext = SegmentReader::new(&mut reader);
+            let mut ciphertext = util::decode_base64_urlsafe_nopad_reader(&mut ciphertext);
+            let mut buf = vec![0; STREAM_BUFFER_SIZE];
+            loop {
+                let len = util::read_retrying(&mut ciphertext, &mut buf)?;
+                if len == 0 {
+                    break;
                 }
-                Some(_) => bail!("A alg header claim must be a string."),
-                None => bail!("The JWE alg header claim is required."),
-            }
-
-            match decrypter.key_id() {
-                Some(expected) => match merged.key_id() {
-                    Some(actual) if expected == actual => {}
-                    Some(actual) => bail!("The JWE kid header claim is mismatched: {}", actual),
-                    None => bail!("The JWE kid header claim is required."),
-                },
-                None => {}
-            }
-
-            let key = decrypter.decrypt(encrypted_key, cencryption, &merged)?;
-            if key.len() != cencryption.key_len() {
-                bail!(
-                    "The key size is expected to be {}: {}",
-                    cencryption.key_len(),
-                    key.len()
-                );
+                decryptor.update(&buf[..len])?;
             }
 
-            let content = cencryption.decrypt(&key, iv, &ciphertext, header_b64, tag)?;
-            let content = match compression {
-                Some(val) => val.decompress(&content)?,
+            let tag_b64 = read_final_segment(&mut reader)?;
+            let tag = if !tag_b64.is_empty() {
+                Some(util::decode_base64_urlsafe_no_pad(&tag_b64)?)
+            } else {
+                None
+            };
+            decryptor.finalize(tag.as_deref())?;
+
+            input.seek(SeekFrom::Start(start + offset))?;
+            let mut reader = BufReader::new(&mut *input);
+            let mut ciphertext = SegmentReader::new(&mut reader);
+            let ciphertext = util::decode_base64_urlsafe_nopad_reader(&mut ciphertext);
+            let decryptor =
+                prepared
+                    .cencryption
+                    .stream_decryptor(&prepared.key, iv, &header_b64)?;
+            let content: Box<dyn Read> = Box::new(DecryptingReader {
+                input: Box::new(ciphertext),
+                decryptor: Some(decryptor),
+                tag: tag.as_deref(),
+                buf: Vec::new(),
+                pos: 0,
+            });
+            let mut content = match prepared.compression {
+                Some(val) => val.decompress_reader(content)?,
                 None => content,
             };
+            io::copy(&mut content, output)?;
+            output.flush()?;
 
-            Ok((content, merged))
+            Ok(prepared.header)
         })()
         .map_err(|err| match err.downcast::<JoseError>() {
             Ok(err) => err,
@@ -973,25 +932,11 @@ impl JweContext {
         decrypter: &'a dyn JweDecrypter,
     ) -> Result<(Vec<u8>, JweHeader), JoseError> {
         self.deserialize_json_with_selector(input, |header| {
-            match header.algorithm() {
-                Some(val) => {
-                    let expected_alg = decrypter.algorithm().name();
-                    if val != expected_alg {
-                        return Ok(None);
-                    }
-                }
-                _ => return Ok(None),
-            }
-
-            match decrypter.key_id() {
-                Some(expected) => match header.key_id() {
-                    Some(actual) if expected == actual => {}
-                    _ => return Ok(None),
-                },
-                None => {}
+            if is_recipient_of(header, decrypter.algorithm(), decrypter.key_id()) {
+                Ok(Some(decrypter))
+            } else {
+                Ok(None)
             }
-
-            Ok(Some(decrypter))
         })
     }
 
@@ -1010,221 +955,927 @@ impl JweContext {
         F: Fn(&JweHeader) -> Result<Option<&'a dyn JweDecrypter>, JoseError>,
     {
         (|| -> anyhow::Result<(Vec<u8>, JweHeader)> {
-            let input = input.as_ref();
-            let mut map: Map<String, Value> = serde_json::from_slice(input)?;
+            let parts = JsonParts::parse(input.as_ref())?;
 
-            let (protected, protected_b64) = match map.remove("protected") {
-                Some(Value::String(val)) => {
-                    if val.len() == 0 {
-                        bail!("The protected field must be empty.");
-                    }
-                    let vec = util::decode_base64_urlsafe_no_pad(&val)?;
-                    let json: Map<String, Value> = serde_json::from_slice(&vec)?;
-                    (Some(json), Some(val))
+            for recipient in &parts.recipients {
+                let (encrypted_key, merged) = parts.recipient_header(recipient)?;
+
+                let decrypter = match selector(&merged)? {
+                    Some(val) => val,
+                    None => continue,
+                };
+
+                let (cencryption, compression) =
+                    self.resolve_decryption(&merged, decrypter.algorithm(), decrypter.key_id())?;
+
+                let key = decrypter.decrypt(encrypted_key.as_deref(), cencryption, &merged)?;
+                check_key_len(cencryption, &key)?;
+                let content = parts.decrypt(cencryption, compression, &key)?;
+
+                return Ok((content, merged));
+            }
+
+            bail!("A recipient that matched the header claims is not found.");
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJweFormat(err),
+        })
+    }
+
+    /// Deserialize the input that is formatted by compact serialization
+    /// with a asynchronous decrypter.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input data.
+    /// * `decrypter` - The asynchronous JWE decrypter.
+    #[cfg(feature = "async")]
+    pub async fn deserialize_compact_async(
+        &self,
+        input: impl AsRef<[u8]>,
+        decrypter: &dyn AsyncJweDecrypter,
+    ) -> Result<(Vec<u8>, JweHeader), JoseError> {
+        async {
+            let parts = CompactParts::parse(input.as_ref())?;
+            let merged = decode_compact_header(parts.header_b64)?;
+
+            let (cencryption, compression) =
+                self.resolve_decryption(&merged, decrypter.algorithm(), decrypter.key_id())?;
+
+            let key = decrypter
+                .decrypt(parts.encrypted_key.as_deref(), cencryption, &merged)
+                .await?;
+            check_key_len(cencryption, &key)?;
+            let content = parts.decrypt(cencryption, compression, &key)?;
+
+            Ok((content, merged))
+        }
+        .await
+        .map_err(|err: anyhow::Error| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJweFormat(err),
+        })
+    }
+
+    /// Deserialize the input that is formatted by json serialization
+    /// with a asynchronous decrypter.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input data.
+    /// * `decrypter` - The asynchronous JWE decrypter.
+    #[cfg(feature = "async")]
+    pub async fn deserialize_json_async(
+        &self,
+        input: impl AsRef<[u8]>,
+        decrypter: &dyn AsyncJweDecrypter,
+    ) -> Result<(Vec<u8>, JweHeader), JoseError> {
+        async {
+            let parts = JsonParts::parse(input.as_ref())?;
+
+            for recipient in &parts.recipients {
+                let (encrypted_key, merged) = parts.recipient_header(recipient)?;
+                if !is_recipient_of(&merged, decrypter.algorithm(), decrypter.key_id()) {
+                    continue;
                 }
-                Some(_) => bail!("The protected field must be a string."),
-                None => (None, None),
+
+                let (cencryption, compression) =
+                    self.resolve_decryption(&merged, decrypter.algorithm(), decrypter.key_id())?;
+
+                let key = decrypter
+                    .decrypt(encrypted_key.as_deref(), cencryption, &merged)
+                    .await?;
+                check_key_len(cencryption, &key)?;
+                let content = parts.decrypt(cencryption, compression, &key)?;
+
+                return Ok((content, merged));
+            }
+
+            bail!("A recipient that matched the header claims is not found.");
+        }
+        .await
+        .map_err(|err: anyhow::Error| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJweFormat(err),
+        })
+    }
+
+    fn prepare_compact_encryption<'a, 'b, F>(
+        &'a self,
+        header: &JweHeader,
+        selector: F,
+    ) -> anyhow::Result<PreparedEncryption<'a>>
+    where
+        'b: 'a,
+        F: Fn(&JweHeader) -> Option<&'b dyn JweEncrypter>,
+    {
+        let encrypter = match selector(header) {
+            Some(val) => val,
+            None => bail!("A encrypter is not found."),
+        };
+
+        let cencryption = match header.content_encryption() {
+            Some(enc) => match self.get_content_encryption(enc) {
+                Some(val) => val,
+                None => bail!("A content encryption is not registered: {}", enc),
+            },
+            None => bail!("A enc header claim is required."),
+        };
+
+        let compression = match header.compression() {
+            Some(zip) => match self.get_compression(zip) {
+                Some(val) => Some(val),
+                None => bail!("A compression algorithm is not registered: {}", zip),
+            },
+            None => None,
+        };
+
+        let mut out_header = header.clone();
+
+        let key_len = cencryption.key_len();
+        let key =
+            match encrypter.compute_content_encryption_key(cencryption, header, &mut out_header)? {
+                Some(val) => val,
+                None => Cow::Owned(util::random_bytes(key_len)),
             };
-            let unprotected = match map.remove("unprotected") {
-                Some(Value::Object(val)) => {
-                    if val.len() == 0 {
-                        bail!("The unprotected field must be empty.");
+
+        let encrypted_key = encrypter.encrypt(&key, header, &mut out_header)?;
+        if header.claim("kid").is_none() {
+            if let Some(key_id) = encrypter.key_id() {
+                out_header.set_key_id(key_id);
+            }
+        }
+
+        out_header.set_algorithm(encrypter.algorithm().name());
+
+        let header_bytes = serde_json::to_vec(out_header.claims_set())?;
+        let header_b64 = util::encode_base64_urlsafe_nopad(header_bytes);
+
+        let iv = if cencryption.iv_len() > 0 {
+            Some(util::random_bytes(cencryption.iv_len()))
+        } else {
+            None
+        };
+
+        let mut capacity = 3;
+        capacity += header_b64.len();
+        if let Some(val) = &encrypted_key {
+            capacity += util::ceiling(val.len() * 4, 3);
+        }
+        if let Some(val) = &iv {
+            capacity += util::ceiling(val.len() * 4, 3);
+        }
+
+        let mut prefix = String::with_capacity(capacity);
+        prefix.push_str(&header_b64);
+        prefix.push('.');
+        if let Some(val) = &encrypted_key {
+            util::encode_base64_urlsafe_nopad_buf(val, &mut prefix);
+        }
+        prefix.push('.');
+        if let Some(val) = &iv {
+            util::encode_base64_urlsafe_nopad_buf(val, &mut prefix);
+        }
+        prefix.push('.');
+
+        Ok(PreparedEncryption {
+            cencryption,
+            compression,
+            key,
+            iv,
+            aad: header_b64,
+            prefix,
+        })
+    }
+
+    fn prepare_flattened_json_encryption<'a, 'b, F>(
+        &'a self,
+        header: Option<&JweHeaderSet>,
+        recipient_header: Option<&JweHeader>,
+        aad: Option<&[u8]>,
+        selector: F,
+    ) -> anyhow::Result<PreparedEncryption<'a>>
+    where
+        'b: 'a,
+        F: Fn(&JweHeader) -> Option<&'b dyn JweEncrypter>,
+    {
+        let mut compression = None;
+        if let Some(header) = header {
+            match header.claims_set(true).get("zip") {
+                Some(Value::String(val)) => match self.get_compression(val) {
+                    Some(val) => {
+                        compression = Some(val);
                     }
-                    Some(val)
+                    None => bail!("A compression algorithm is not registered: {}", val),
+                },
+                Some(_) => bail!("A zip header claim must be a string."),
+                None => {}
+            }
+        };
+
+        let mut merged_map = match header {
+            Some(val) => val.to_map(),
+            None => Map::new(),
+        };
+
+        if let Some(val) = recipient_header {
+            for (key, value) in val.claims_set() {
+                if merged_map.contains_key(key) {
+                    bail!("Duplicate key exists: {}", key);
                 }
-                Some(_) => bail!("The JWE unprotected field must be string."),
-                None => None,
+                merged_map.insert(key.clone(), value.clone());
+            }
+        }
+
+        let merged = JweHeader::from_map(merged_map)?;
+
+        let cencryption = match merged.claim("enc") {
+            Some(Value::String(enc)) => match self.get_content_encryption(enc) {
+                Some(val) => val,
+                None => bail!("A content encryption is not registered: {}", enc),
+            },
+            Some(_) => bail!("A enc header claim must be a string."),
+            None => bail!("A enc header claim is required."),
+        };
+
+        let encrypter = match selector(&merged) {
+            Some(val) => val,
+            None => bail!("A encrypter is not found."),
+        };
+
+        let mut protected = match header {
+            Some(val) => JweHeader::from_map(val.claims_set(true).clone())?,
+            None => JweHeader::new(),
+        };
+
+        let key =
+            match encrypter.compute_content_encryption_key(cencryption, &merged, &mut protected)? {
+                Some(val) => val,
+                None => Cow::Owned(util::random_bytes(cencryption.key_len())),
             };
-            let aad_b64 = match map.remove("aad") {
-                Some(Value::String(val)) => {
-                    if val.len() == 0 {
-                        bail!("The JWE aad field must be empty.");
-                    } else if !util::is_base64_urlsafe_nopad(&val) {
-                        bail!("The JWE aad field must be a base64 string.");
-                    }
-                    Some(val)
+
+        let encrypted_key = encrypter.encrypt(&key, &merged, &mut protected)?;
+
+        match merged.algorithm() {
+            Some(val) if val == encrypter.algorithm().name() => {}
+            Some(_) => bail!("A signer is unmatched."),
+            None => {
+                protected.set_algorithm(encrypter.algorithm().name().to_string());
+            }
+        }
+
+        if let None = merged.key_id() {
+            if let Some(key_id) = encrypter.key_id() {
+                protected.set_key_id(key_id.to_string());
+            }
+        }
+
+        let iv = if cencryption.iv_len() > 0 {
+            Some(util::random_bytes(cencryption.iv_len()))
+        } else {
+            None
+        };
+
+        let protected_b64 = if protected.len() > 0 {
+            let protected_json = serde_json::to_vec(protected.claims_set())?;
+            let protected_b64 = util::encode_base64_urlsafe_nopad(protected_json);
+            Some(protected_b64)
+        } else {
+            None
+        };
+
+        let aad_b64 = match aad {
+            Some(val) => Some(util::encode_base64_urlsafe_nopad(val)),
+            None => None,
+        };
+
+        let mut full_aad = String::with_capacity({
+            let mut full_aad_capacity = 1;
+            if let Some(val) = &protected_b64 {
+                full_aad_capacity += val.len();
+            }
+            if let Some(val) = &aad_b64 {
+                full_aad_capacity += val.len();
+            }
+            full_aad_capacity
+        });
+        if let Some(val) = &protected_b64 {
+            full_aad.push_str(&val);
+        }
+        if let Some(val) = &aad_b64 {
+            full_aad.push_str(".");
+            full_aad.push_str(&val);
+        }
+
+        let mut writed = false;
+        let mut json = String::new();
+        if let Some(val) = protected_b64 {
+            json.push_str("{\"protected\":\"");
+            json.push_str(&val);
+            json.push_str("\"");
+            writed = true;
+        }
+
+        if let Some(val) = header {
+            let unprotected_map = val.claims_set(false);
+            if unprotected_map.len() > 0 {
+                let unprotected = serde_json::to_string(unprotected_map)?;
+                json.push_str(if writed { "," } else { "{" });
+                json.push_str("\"unprotected\":");
+                json.push_str(&unprotected);
+                writed = true;
+            }
+        }
+
+        if let Some(val) = recipient_header {
+            let header_map = val.claims_set();
+            if header_map.len() > 0 {
+                let header = serde_json::to_string(header_map)?;
+                json.push_str(if writed { "," } else { "{" });
+                json.push_str("\"header\":");
+                json.push_str(&header);
+            }
+        }
+
+        if let Some(val) = encrypted_key {
+            json.push_str(",\"encrypted_key\":\"");
+            util::encode_base64_urlsafe_nopad_buf(&val, &mut json);
+            json.push_str("\"");
+        }
+
+        if let Some(val) = aad_b64 {
+            json.push_str(",\"aad\":\"");
+            json.push_str(&val);
+            json.push_str("\"");
+        }
+
+        json.push_str(",\"iv\":\"");
+        if let Some(val) = &iv {
+            util::encode_base64_urlsafe_nopad_buf(&val, &mut json);
+        }
+        json.push_str("\"");
+
+        json.push_str(",\"ciphertext\":\"");
+
+        Ok(PreparedEncryption {
+            cencryption,
+            compression,
+            key,
+            iv,
+            aad: full_aad,
+            prefix: json,
+        })
+    }
+
+    /// Return the content encryption and the compression of the header claims
+    /// after checking that they are acceptable for the decrypter.
+    fn resolve_decryption(
+        &self,
+        header: &JweHeader,
+        algorithm: &dyn JweAlgorithm,
+        key_id: Option<&str>,
+    ) -> anyhow::Result<(&dyn JweContentEncryption, Option<&dyn JweCompression>)> {
+        let cencryption = match header.claim("enc") {
+            Some(Value::String(val)) => match self.get_content_encryption(val) {
+                Some(val2) => val2,
+                None => bail!("A content encryption is not registered: {}", val),
+            },
+            Some(_) => bail!("A enc header claim must be a string."),
+            None => bail!("A enc header claim is required."),
+        };
+
+        let compression = match header.claim("zip") {
+            Some(Value::String(val)) => match self.get_compression(val) {
+                Some(val2) => Some(val2),
+                None => bail!("A compression algorithm is not registered: {}", val),
+            },
+            Some(_) => bail!("A enc header claim must be a string."),
+            None => None,
+        };
+
+        match header.claim("alg") {
+            Some(Value::String(val)) => {
+                let expected_alg = algorithm.name();
+                if val != expected_alg {
+                    bail!(JoseError::AlgorithmMismatch {
+                        expected: expected_alg.to_string(),
+                        actual: val.to_string(),
+                    });
                 }
-                Some(_) => bail!("The JWE aad field must be string."),
-                None => None,
-            };
-            let iv_vec;
-            let iv = match map.remove("iv") {
-                Some(Value::String(val)) => {
-                    if val.len() == 0 {
-                        bail!("The iv field must be empty.");
-                    }
-                    iv_vec = util::decode_base64_urlsafe_no_pad(&val)?;
-                    Some(iv_vec.as_slice())
+            }
+            Some(_) => bail!("A alg header claim must be a string."),
+            None => bail!("The JWE alg header claim is required."),
+        }
+
+        match key_id {
+            Some(expected) => match header.key_id() {
+                Some(actual) if expected == actual => {}
+                Some(actual) => bail!("The JWE kid header claim is mismatched: {}", actual),
+                None => bail!("The JWE kid header claim is required."),
+            },
+            None => {}
+        }
+
+        Ok((cencryption, compression))
+    }
+
+    fn prepare_compact_decryption<'a, 'b, F>(
+        &'a self,
+        header_b64: &[u8],
+        encrypted_key: Option<&[u8]>,
+        selector: F,
+    ) -> anyhow::Result<PreparedDecryption<'a>>
+    where
+        'b: 'a,
+        F: Fn(&JweHeader) -> Result<Option<&'b dyn JweDecrypter>, JoseError>,
+    {
+        let merged = decode_compact_header(header_b64)?;
+
+        let decrypter = match selector(&merged)? {
+            Some(val) => val,
+            None => bail!(JoseError::KeyNotFound(
+                merged.key_id().map(|val| val.to_string())
+            )),
+        };
+
+        let (cencryption, compression) =
+            self.resolve_decryption(&merged, decrypter.algorithm(), decrypter.key_id())?;
+
+        let key = decrypter.decrypt(encrypted_key, cencryption, &merged)?;
+        check_key_len(cencryption, &key)?;
+
+        Ok(PreparedDecryption {
+            header: merged,
+            cencryption,
+            compression,
+            key,
+        })
+    }
+}
+
+struct PreparedEncryption<'a> {
+    cencryption: &'a dyn JweContentEncryption,
+    compression: Option<&'a dyn JweCompression>,
+    key: Cow<'a, [u8]>,
+    iv: Option<Vec<u8>>,
+    aad: String,
+    prefix: String,
+}
+
+impl PreparedEncryption<'_> {
+    fn encrypt_to_writer(
+        &self,
+        payload: &mut dyn Read,
+        output: &mut dyn Write,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut encryptor = self.cencryption.stream_encryptor(
+            &self.key,
+            self.iv.as_deref(),
+            self.aad.as_bytes(),
+        )?;
+
+        let mut content = match self.compression {
+            Some(val) => val.compress_reader(Box::new(payload))?,
+            None => Box::new(payload),
+        };
+
+        let mut ciphertext = util::encode_base64_urlsafe_nopad_writer(output);
+        let mut buf = vec![0; STREAM_BUFFER_SIZE];
+        loop {
+            let len = util::read_retrying(&mut content, &mut buf)?;
+            if len == 0 {
+                break;
+            }
+            ciphertext.write_all(&encryptor.update(&buf[..len])?)?;
+        }
+        let (last, tag) = encryptor.finalize()?;
+        ciphertext.write_all(&last)?;
+        ciphertext.finish()?;
+
+        Ok(tag)
+    }
+}
+
+struct PreparedDecryption<'a> {
+    header: JweHeader,
+    cencryption: &'a dyn JweContentEncryption,
+    compression: Option<&'a dyn JweCompression>,
+    key: Cow<'a, [u8]>,
+}
+
+/// The decoded parts of the compact serialization.
+struct CompactParts<'a> {
+    header_b64: &'a [u8],
+    encrypted_key: Option<Vec<u8>>,
+    iv: Option<Vec<u8>>,
+    ciphertext: Vec<u8>,
+    tag: Option<Vec<u8>>,
+}
+
+impl<'a> CompactParts<'a> {
+    fn parse(input: &'a [u8]) -> anyhow::Result<Self> {
+        let indexies: Vec<usize> = input
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b == b'.' as u8)
+            .map(|(pos, _)| pos)
+            .collect();
+        if indexies.len() != 4 {
+            bail!("The compact serialization form of JWE must be five parts separated by colon.");
+        }
+
+        let header_b64 = &input[0..indexies[0]];
+
+        let encrypted_key_b64 = &input[(indexies[0] + 1)..(indexies[1])];
+        let encrypted_key = if encrypted_key_b64.len() > 0 {
+            Some(util::decode_base64_urlsafe_no_pad(encrypted_key_b64)?)
+        } else {
+            None
+        };
+
+        let iv_b64 = &input[(indexies[1] + 1)..(indexies[2])];
+        let iv = if iv_b64.len() > 0 {
+            Some(util::decode_base64_urlsafe_no_pad(iv_b64)?)
+        } else {
+            None
+        };
+
+        let ciphertext_b64 = &input[(indexies[2] + 1)..(indexies[3])];
+        let ciphertext = util::decode_base64_urlsafe_no_pad(ciphertext_b64)?;
+
+        let tag_b64 = &input[(indexies[3] + 1)..];
+        let tag = if tag_b64.len() > 0 {
+            Some(util::decode_base64_urlsafe_no_pad(tag_b64)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            header_b64,
+            encrypted_key,
+            iv,
+            ciphertext,
+            tag,
+        })
+    }
+
+    fn decrypt(
+        &self,
+        cencryption: &dyn JweContentEncryption,
+        compression: Option<&dyn JweCompression>,
+        key: &[u8],
+    ) -> anyhow::Result<Vec<u8>> {
+        decrypt_content(
+            cencryption,
+            compression,
+            key,
+            self.iv.as_deref(),
+            &self.ciphertext,
+            self.header_b64,
+            self.tag.as_deref(),
+        )
+    }
+}
+
+/// The decoded parts of the json serialization.
+struct JsonParts {
+    protected: Option<Map<String, Value>>,
+    unprotected: Option<Map<String, Value>>,
+    aad: String,
+    iv: Option<Vec<u8>>,
+    ciphertext: Vec<u8>,
+    tag: Option<Vec<u8>>,
+    recipients: Vec<Map<String, Value>>,
+}
+
+impl JsonParts {
+    fn parse(input: &[u8]) -> anyhow::Result<Self> {
+        let mut map: Map<String, Value> = serde_json::from_slice(input)?;
+
+        let (protected, protected_b64) = match map.remove("protected") {
+            Some(Value::String(val)) => {
+                if val.len() == 0 {
+                    bail!("The protected field must be empty.");
                 }
-                Some(_) => bail!("The iv field must be string."),
-                None => None,
-            };
-            let ciphertext = match map.remove("ciphertext") {
-                Some(Value::String(val)) => {
-                    if val.len() == 0 {
-                        bail!("The ciphertext field must be empty.");
-                    }
-                    util::decode_base64_urlsafe_no_pad(&val)?
+                let vec = util::decode_base64_urlsafe_no_pad(&val)?;
+                let json: Map<String, Value> = serde_json::from_slice(&vec)?;
+                (Some(json), Some(val))
+            }
+            Some(_) => bail!("The protected field must be a string."),
+            None => (None, None),
+        };
+        let unprotected = match map.remove("unprotected") {
+            Some(Value::Object(val)) => {
+                if val.len() == 0 {
+                    bail!("The unprotected field must be empty.");
                 }
-                Some(_) => bail!("The ciphertext field must be string."),
-                None => bail!("The ciphertext field is required."),
-            };
-            let tag_vec;
-            let tag = match map.remove("tag") {
-                Some(Value::String(val)) => {
-                    if val.len() == 0 {
-                        bail!("The tag field must be empty.");
-                    }
-                    tag_vec = util::decode_base64_urlsafe_no_pad(&val)?;
-                    Some(tag_vec.as_slice())
+                Some(val)
+            }
+            Some(_) => bail!("The JWE unprotected field must be string."),
+            None => None,
+        };
+        let aad_b64 = match map.remove("aad") {
+            Some(Value::String(val)) => {
+                if val.len() == 0 {
+                    bail!("The JWE aad field must be empty.");
+                } else if !util::is_base64_urlsafe_nopad(&val) {
+                    bail!("The JWE aad field must be a base64 string.");
                 }
-                Some(_) => bail!("The tag field must be string."),
-                None => None,
-            };
-
-            let recipients = match map.remove("recipients") {
-                Some(Value::Array(vals)) => {
-                    if vals.len() == 0 {
-                        bail!("The recipients field must be empty.");
-                    }
-                    let mut vec = Vec::with_capacity(vals.len());
-                    for val in vals {
-                        if let Value::Object(val) = val {
-                            vec.push(val);
-                        } else {
-                            bail!("The recipients field must be a array of object.");
-                        }
-                    }
-                    vec
+                Some(val)
+            }
+            Some(_) => bail!("The JWE aad field must be string."),
+            None => None,
+        };
+        let iv = match map.remove("iv") {
+            Some(Value::String(val)) => {
+                if val.len() == 0 {
+                    bail!("The iv field must be empty.");
                 }
-                Some(_) => bail!("The recipients field must be a array."),
-                None => {
-                    let mut vec = Vec::with_capacity(1);
-                    vec.push(map);
-                    vec
+                Some(util::decode_base64_urlsafe_no_pad(&val)?)
+            }
+            Some(_) => bail!("The iv field must be string."),
+            None => None,
+        };
+        let ciphertext = match map.remove("ciphertext") {
+            Some(Value::String(val)) => {
+                if val.len() == 0 {
+                    bail!("The ciphertext field must be empty.");
                 }
-            };
-
-            for mut recipient in recipients {
-                let header = recipient.remove("header");
+                util::decode_base64_urlsafe_no_pad(&val)?
+            }
+            Some(_) => bail!("The ciphertext field must be string."),
+            None => bail!("The ciphertext field is required."),
+        };
+        let tag = match map.remove("tag") {
+            Some(Value::String(val)) => {
+                if val.len() == 0 {
+                    bail!("The tag field must be empty.");
+                }
+                Some(util::decode_base64_urlsafe_no_pad(&val)?)
+            }
+            Some(_) => bail!("The tag field must be string."),
+            None => None,
+        };
 
-                let encrypted_key_vec;
-                let encrypted_key = match recipient.get("encrypted_key") {
-                    Some(Value::String(val)) => {
-                        if val.len() == 0 {
-                            bail!("The encrypted_key field must be empty.");
-                        }
-                        encrypted_key_vec = util::decode_base64_urlsafe_no_pad(&val)?;
-                        Some(encrypted_key_vec.as_slice())
+        let recipients = match map.remove("recipients") {
+            Some(Value::Array(vals)) => {
+                if vals.len() == 0 {
+                    bail!("The recipients field must be empty.");
+                }
+                let mut vec = Vec::with_capacity(vals.len());
+                for val in vals {
+                    if let Value::Object(val) = val {
+                        vec.push(val);
+                    } else {
+                        bail!("The recipients field must be a array of object.");
                     }
-                    Some(_) => bail!("The encrypted_key field must be a string."),
-                    None => None,
-                };
+                }
+                vec
+            }
+            Some(_) => bail!("The recipients field must be a array."),
+            None => {
+                let mut vec = Vec::with_capacity(1);
+                vec.push(map);
+                vec
+            }
+        };
+
+        let mut aad = match protected_b64 {
+            Some(val) => val,
+            None => String::new(),
+        };
+        if let Some(val) = aad_b64 {
+            aad.push_str(".");
+            aad.push_str(&val);
+        }
 
-                let mut merged = match header {
-                    Some(Value::Object(val)) => val,
-                    Some(_) => bail!("The protected field must be a object."),
-                    None => Map::new(),
-                };
+        Ok(Self {
+            protected,
+            unprotected,
+            aad,
+            iv,
+            ciphertext,
+            tag,
+            recipients,
+        })
+    }
 
-                if let Some(val) = &unprotected {
-                    for (key, value) in val {
-                        if merged.contains_key(key) {
-                            bail!("A duplicate key exists: {}", key);
-                        } else {
-                            merged.insert(key.clone(), value.clone());
-                        }
-                    }
+    /// Return the encrypted key and the merged header claims of the recipient.
+    fn recipient_header(
+        &self,
+        recipient: &Map<String, Value>,
+    ) -> anyhow::Result<(Option<Vec<u8>>, JweHeader)> {
+        let encrypted_key = match recipient.get("encrypted_key") {
+            Some(Value::String(val)) => {
+                if val.len() == 0 {
+                    bail!("The encrypted_key field must be empty.");
                 }
+                Some(util::decode_base64_urlsafe_no_pad(&val)?)
+            }
+            Some(_) => bail!("The encrypted_key field must be a string."),
+            None => None,
+        };
+
+        let mut merged = match recipient.get("header") {
+            Some(Value::Object(val)) => val.clone(),
+            Some(_) => bail!("The protected field must be a object."),
+            None => Map::new(),
+        };
+
+        if let Some(val) = &self.unprotected {
+            for (key, value) in val {
+                if merged.contains_key(key) {
+                    bail!("A duplicate key exists: {}", key);
+                } else {
+                    merged.insert(key.clone(), value.clone());
+                }
+            }
+        }
 
-                if let Some(val) = &protected {
-                    for (key, value) in val {
-                        if merged.contains_key(key) {
-                            bail!("A duplicate key exists: {}", key);
-                        } else {
-                            merged.insert(key.clone(), value.clone());
-                        }
-                    }
+        if let Some(val) = &self.protected {
+            for (key, value) in val {
+                if merged.contains_key(key) {
+                    bail!("A duplicate key exists: {}", key);
+                } else {
+                    merged.insert(key.clone(), value.clone());
                 }
+            }
+        }
 
-                let merged = JweHeader::from_map(merged)?;
+        let merged = JweHeader::from_map(merged)?;
+        Ok((encrypted_key, merged))
+    }
 
-                let decrypter = match selector(&merged)? {
-                    Some(val) => val,
-                    None => continue,
-                };
+    fn decrypt(
+        &self,
+        cencryption: &dyn JweContentEncryption,
+        compression: Option<&dyn JweCompression>,
+        key: &[u8],
+    ) -> anyhow::Result<Vec<u8>> {
+        decrypt_content(
+            cencryption,
+            compression,
+            key,
+            self.iv.as_deref(),
+            &self.ciphertext,
+            self.aad.as_bytes(),
+            self.tag.as_deref(),
+        )
+    }
+}
 
-                let cencryption = match merged.claim("enc") {
-                    Some(Value::String(val)) => match self.get_content_encryption(val) {
-                        Some(val2) => val2,
-                        None => bail!("A content encryption is not registered: {}", val),
-                    },
-                    Some(_) => bail!("A enc header claim must be string."),
-                    None => bail!("A enc header claim is required."),
-                };
+fn decode_compact_header(header_b64: &[u8]) -> anyhow::Result<JweHeader> {
+    let header = util::decode_base64_urlsafe_no_pad(header_b64)?;
+    let merged: Map<String, Value> = serde_json::from_slice(&header)?;
+    Ok(JweHeader::from_map(merged)?)
+}
 
-                let compression = match merged.claim("zip") {
-                    Some(Value::String(val)) => match self.get_compression(val) {
-                        Some(val2) => Some(val2),
-                        None => bail!("A compression algorithm is not registered: {}", val),
-                    },
-                    Some(_) => bail!("A enc header claim must be string."),
-                    None => None,
-                };
+/// Test the header claims are addressed to the decrypter.
+fn is_recipient_of(header: &JweHeader, algorithm: &dyn JweAlgorithm, key_id: Option<&str>) -> bool {
+    match header.algorithm() {
+        Some(val) if val == algorithm.name() => {}
+        _ => return false,
+    }
 
-                match merged.algorithm() {
-                    Some(val) => {
-                        let expected_alg = decrypter.algorithm().name();
-                        if val != expected_alg {
-                            bail!("The JWE alg header claim is not {}: {}", expected_alg, val);
-                        }
-                    }
-                    None => bail!("The JWE alg header claim is required."),
-                }
+    match key_id {
+        Some(expected) => header.key_id() == Some(expected),
+        None => true,
+    }
+}
 
-                match decrypter.key_id() {
-                    Some(expected) => match merged.key_id() {
-                        Some(actual) if expected == actual => {}
-                        Some(actual) => bail!("The JWE kid header claim is mismatched: {}", actual),
-                        None => bail!("The JWE kid header claim is required."),
-                    },
-                    None => {}
-                }
+fn check_key_len(cencryption: &dyn JweContentEncryption, key: &[u8]) -> anyhow::Result<()> {
+    if key.len() != cencryption.key_len() {
+        bail!(
+            "The key size is expected to be {}: {}",
+            cencryption.key_len(),
+            key.len()
+        );
+    }
+    Ok(())
+}
 
-                let mut full_aad = match protected_b64 {
-                    Some(val) => val,
-                    None => String::new(),
-                };
-                if let Some(val) = aad_b64 {
-                    full_aad.push_str(".");
-                    full_aad.push_str(&val);
-                }
+fn decrypt_content(
+    cencryption: &dyn JweContentEncryption,
+    compression: Option<&dyn JweCompression>,
+    key: &[u8],
+    iv: Option<&[u8]>,
+    ciphertext: &[u8],
+    aad: &[u8],
+    tag: Option<&[u8]>,
+) -> anyhow::Result<Vec<u8>> {
+    let content = cencryption.decrypt(key, iv, ciphertext, aad, tag)?;
+    let content = match compression {
+        Some(val) => val.decompress(&content)?,
+        None => content,
+    };
+    Ok(content)
+}
 
-                let key = decrypter.decrypt(encrypted_key, cencryption, &merged)?;
-                if key.len() != cencryption.key_len() {
-                    bail!(
-                        "The key size is expected to be {}: {}",
-                        cencryption.key_len(),
-                        key.len()
-                    );
-                }
+/// Read a part of the compact serialization until the next dot.
+fn read_segment(reader: &mut impl BufRead) -> anyhow::Result<Vec<u8>> {
+    let mut segment = Vec::new();
+    reader
+        .take(MAX_SEGMENT_LEN)
+        .read_until(b'.', &mut segment)?;
+    if segment.last() == Some(&b'.') {
+        segment.pop();
+    } else if segment.len() as u64 == MAX_SEGMENT_LEN {
+        bail!("A part of the compact serialization is too large.");
+    }
+    Ok(segment)
+}
 
-                let content =
-                    cencryption.decrypt(&key, iv, &ciphertext, full_aad.as_bytes(), tag)?;
-                let content = match compression {
-                    Some(val) => val.decompress(&content)?,
-                    None => content,
-                };
+/// Read the last part of the compact serialization, which must end at the end of input.
+fn read_final_segment(reader: &mut impl Read) -> anyhow::Result<Vec<u8>> {
+    let mut segment = Vec::new();
+    reader.take(MAX_SEGMENT_LEN + 1).read_to_end(&mut segment)?;
+    if segment.len() as u64 > MAX_SEGMENT_LEN {
+        bail!("A part of the compact serialization is too large.");
+    } else if segment.contains(&b'.') {
+        bail!("The compact serialization form of JWE must be five parts separated by colon.");
+    }
+    Ok(segment)
+}
 
-                return Ok((content, merged));
-            }
+/// A reader that reads a part of the compact serialization until the next dot.
+struct SegmentReader<'a, B: BufRead> {
+    inner: &'a mut B,
+    done: bool,
+}
 
-            bail!("A recipient that matched the header claims is not found.");
-        })()
-        .map_err(|err| match err.downcast::<JoseError>() {
-            Ok(err) => err,
-            Err(err) => JoseError::InvalidJweFormat(err),
-        })
+impl<'a, B: BufRead> SegmentReader<'a, B> {
+    fn new(inner: &'a mut B) -> Self {
+        Self { inner, done: false }
+    }
+}
+
+impl<B: BufRead> Read for SegmentReader<'_, B> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.done || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let available = self.inner.fill_buf()?;
+        if available.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The compact serialization form of JWE must be five parts separated by colon.",
+            ));
+        }
+
+        let (len, found) = match available.iter().position(|b| *b == b'.') {
+            Some(pos) => (pos, true),
+            None => (available.len(), false),
+        };
+        let read_len = len.min(buf.len());
+        buf[..read_len].copy_from_slice(&available[..read_len]);
+        if found && read_len == len {
+            self.inner.consume(read_len + 1);
+            self.done = true;
+        } else {
+            self.inner.consume(read_len);
+        }
+        Ok(read_len)
+    }
+}
+
+/// A reader that decrypts the encrypted content read from the input.
+struct DecryptingReader<'a> {
+    input: Box<dyn Read + 'a>,
+    decryptor: Option<Box<dyn JweContentStreamDecryptor + 'a>>,
+    tag: Option<&'a [u8]>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl Read for DecryptingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let to_io_error = |err: JoseError| io::Error::new(io::ErrorKind::InvalidData, err);
+
+        while self.pos >= self.buf.len() {
+            let decryptor = match &mut self.decryptor {
+                Some(val) => val,
+                None => return Ok(0),
+            };
+
+            let mut encrypted = vec![0; STREAM_BUFFER_SIZE];
+            let len = util::read_retrying(&mut self.input, &mut encrypted)?;
+            self.buf = if len > 0 {
+                decryptor.update(&encrypted[..len]).map_err(to_io_error)?
+            } else {
+                match self.decryptor.take() {
+                    Some(val) => val.finalize(self.tag).map_err(to_io_error)?,
+                    None => Vec::new(),
+                }
+            };
+            self.pos = 0;
+        }
+
+        let len = (self.buf.len() - self.pos).min(buf.len());
+        buf[..len].copy_from_slice(&self.buf[self.pos..(self.pos + len)]);
+        self.pos += len;
+        Ok(len)
     }
 }
 
@@ -1234,6 +1885,10 @@ mod tests {
         alg::direct::DirectJweAlgorithm, deserialize_compact, deserialize_json, serialize_compact,
         serialize_flattened_json, serialize_general_json, JweHeader, JweHeaderSet,
     };
+    #[cfg(feature = "async")]
+    use crate::jwe::{JweContext, RSA_OAEP};
+    #[cfg(feature = "async")]
+    use crate::util::mock_kms::{assert_send, MockKmsDecrypter};
     use anyhow::Result;
 
     const CONTENT_CIPHERS: [(&str, usize); 6] = [
@@ -1301,4 +1956,62 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_decrypter() -> Result<()> {
+        let payload = b"hello world";
+        let private_key = load_file("pem/RSA_2048bit_private.pem")?;
+        let public_key = load_file("pem/RSA_2048bit_public.pem")?;
+        let other_key = RSA_OAEP.generate_key_pair(2048)?;
+
+        let mut encrypter = RSA_OAEP.encrypter_from_pem(&public_key)?;
+        encrypter.set_key_id("kms-key-1");
+        let mut decrypter = RSA_OAEP.decrypter_from_pem(&private_key)?;
+        decrypter.set_key_id("kms-key-1");
+        let kms_decrypter = MockKmsDecrypter {
+            key: Box::new(decrypter),
+        };
+        let mut other_encrypter = RSA_OAEP.encrypter_from_der(other_key.to_der_public_key())?;
+        other_encrypter.set_key_id("kms-key-2");
+
+        let context = JweContext::new();
+
+        let mut header = JweHeader::new();
+        header.set_content_encryption("A128GCM");
+        let jwe = serialize_compact(payload, &header, &encrypter)?;
+        let future = context.deserialize_compact_async(&jwe, &kms_decrypter);
+        assert_send(&future);
+        let (data, header) = pollster::block_on(future)?;
+        assert_eq!(data, payload);
+        assert_eq!(header.key_id(), Some("kms-key-1"));
+
+        let mut hs = JweHeaderSet::new();
+        hs.set_content_encryption("A256GCM", true);
+        let jwe = serialize_general_json(
+            payload,
+            Some(&hs),
+            &[(None, &other_encrypter), (None, &encrypter)],
+            None,
+        )?;
+        let (data, header) =
+            pollster::block_on(context.deserialize_json_async(&jwe, &kms_decrypter))?;
+        assert_eq!(data, payload);
+        assert_eq!(header.key_id(), Some("kms-key-1"));
+
+        let jwe = serialize_general_json(payload, Some(&hs), &[(None, &other_encrypter)], None)?;
+        assert!(pollster::block_on(context.deserialize_json_async(&jwe, &kms_decrypter)).is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    fn load_file(path: &str) -> Result<Vec<u8>> {
+        let mut pb = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        pb.push("data");
+        pb.push(path);
+
+        let data = std::fs::read(&pb)?;
+        Ok(data)
+    }
 }
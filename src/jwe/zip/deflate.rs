@@ -2,7 +2,7 @@ use std::fmt::Display;
 use std::io::{self, Read, Write};
 use std::ops::Deref;
 
-use flate2::read::DeflateDecoder;
+use flate2::read::{self as deflate_read, DeflateDecoder};
 use flate2::write::DeflateEncoder;
 use flate2::Compression;
 
@@ -35,6 +35,23 @@ impl JweCompression for DeflateJweCompression {
         Ok(vec)
     }
 
+    fn compress_reader<'a>(
+        &self,
+        input: Box<dyn Read + 'a>,
+    ) -> Result<Box<dyn Read + 'a>, io::Error> {
+        Ok(Box::new(deflate_read::DeflateEncoder::new(
+            input,
+            Compression::default(),
+        )))
+    }
+
+    fn decompress_reader<'a>(
+        &self,
+        input: Box<dyn Read + 'a>,
+    ) -> Result<Box<dyn Read + 'a>, io::Error> {
+        Ok(Box::new(DeflateDecoder::new(input)))
+    }
+
     fn box_clone(&self) -> Box<dyn JweCompression> {
         Box::new(self.clone())
     }
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::io::Read;
 use std::string::ToString;
@@ -10,7 +11,7 @@ use crate::jwk::alg::ec::{EcCurve, EcKeyPair};
 use crate::jwk::alg::ecx::{EcxCurve, EcxKeyPair};
 use crate::jwk::alg::ed::{EdCurve, EdKeyPair};
 use crate::jwk::alg::rsa::RsaKeyPair;
-use crate::util;
+use crate::util::{self, HashAlgorithm};
 use crate::{JoseError, Map, Value};
 
 /// Represents JWK object.
@@ -213,6 +214,43 @@ impl Jwk {
         .map_err(|err| JoseError::InvalidJwkFormat(err))
     }
 
+    /// Return the JWK thumbprint (RFC 7638) as a base64url encoded string.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - a hash algorithm like SHA-256.
+    pub fn thumbprint(&self, hash: HashAlgorithm) -> Result<String, JoseError> {
+        (|| -> anyhow::Result<String> {
+            let keys: &[&str] = match self.key_type() {
+                "oct" => &["k", "kty"],
+                "RSA" => &["e", "kty", "n"],
+                "EC" => &["crv", "kty", "x", "y"],
+                "OKP" => &["crv", "kty", "x"],
+                val => bail!("Unknown key type: {}", val),
+            };
+
+            let mut members = BTreeMap::new();
+            for key in keys {
+                match self.map.get(*key) {
+                    Some(Value::String(val)) => {
+                        members.insert(*key, val.as_str());
+                    }
+                    Some(_) => bail!("The parameter '{}' must be a string.", key),
+                    None => bail!(
+                        "The key type '{}' must have parameter '{}'.",
+                        self.key_type(),
+                        key
+                    ),
+                }
+            }
+
+            let json = serde_json::to_vec(&members)?;
+            let digest = openssl::hash::hash(hash.message_digest(), &json)?;
+            Ok(util::encode_base64_urlsafe_nopad(digest))
+        })()
+        .map_err(JoseError::InvalidJwkFormat)
+    }
+
     /// Set a value for a key type parameter (kty).
     ///
     /// # Arguments
@@ -591,8 +629,31 @@ mod tests {
     use anyhow::Result;
 
     use crate::jwk::Jwk;
+    use crate::util::SHA_256;
     use crate::Value;
 
+    #[test]
+    fn test_jwk_thumbprint() -> Result<()> {
+        // RFC 7638 section 3.1
+        let jwk = Jwk::from_bytes(
+            r#"{
+                "kty": "RSA",
+                "n": "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw",
+                "e": "AQAB",
+                "alg": "RS256",
+                "kid": "2011-04-29"
+            }"#,
+        )?;
+        assert_eq!(
+            jwk.thumbprint(SHA_256)?,
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+
+        assert!(Jwk::new("EC").thumbprint(SHA_256).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_new_jws_header() -> Result<()> {
         let mut jwk = Jwk::new("oct");
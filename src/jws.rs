@@ -6,12 +6,19 @@ mod jws_context;
 mod jws_header;
 mod jws_header_set;
 
+use std::io::Read;
+
 use once_cell::sync::Lazy;
 
+use crate::jwk::Jwk;
 use crate::JoseError;
 
+#[cfg(feature = "async")]
+pub use crate::jws::jws_algorithm::AsyncJwsSigner;
 pub use crate::jws::jws_algorithm::JwsAlgorithm;
 pub use crate::jws::jws_algorithm::JwsSigner;
+pub use crate::jws::jws_algorithm::JwsStreamSigner;
+pub use crate::jws::jws_algorithm::JwsStreamVerifier;
 pub use crate::jws::jws_algorithm::JwsVerifier;
 pub use crate::jws::jws_context::JwsContext;
 pub use crate::jws::jws_header::JwsHeader;
@@ -142,6 +149,44 @@ where
     DEFAULT_CONTEXT.serialize_flattened_json_with_selector(payload, header, selector)
 }
 
+/// Return a representation of the data that is formatted by compact serialization
+/// with a detached and unencoded payload (RFC 7797).
+///
+/// A signer without incremental support like EdDSA
+/// buffers the whole payload.
+///
+/// # Arguments
+///
+/// * `payload` - The reader of the payload data.
+/// * `header` - The JWS heaser claims.
+/// * `signer` - The JWS signer.
+pub fn serialize_compact_detached_from_reader(
+    payload: &mut dyn Read,
+    header: &JwsHeader,
+    signer: &dyn JwsSigner,
+) -> Result<String, JoseError> {
+    DEFAULT_CONTEXT.serialize_compact_detached_from_reader(payload, header, signer)
+}
+
+/// Return a representation of the data that is formatted by compact serialization
+/// with a detached and unencoded payload (RFC 7797).
+///
+/// # Arguments
+///
+/// * `payload` - The reader of the payload data.
+/// * `header` - The JWS heaser claims.
+/// * `selector` - a function for selecting the signing algorithm.
+pub fn serialize_compact_detached_from_reader_with_selector<'a, F>(
+    payload: &mut dyn Read,
+    header: &JwsHeader,
+    selector: F,
+) -> Result<String, JoseError>
+where
+    F: Fn(&JwsHeader) -> Option<&'a dyn JwsSigner>,
+{
+    DEFAULT_CONTEXT.serialize_compact_detached_from_reader_with_selector(payload, header, selector)
+}
+
 /// Deserialize the input that is formatted by compact serialization.
 ///
 /// # Arguments
@@ -204,15 +249,92 @@ where
     DEFAULT_CONTEXT.deserialize_json_with_selector(input, selector)
 }
 
+/// Verify the input that is formatted by compact serialization
+/// with a detached and unencoded payload (RFC 7797).
+///
+/// A verifier without incremental support like EdDSA
+/// buffers the whole payload.
+///
+/// # Arguments
+///
+/// * `input` - The input data.
+/// * `payload` - The reader of the detached payload data.
+/// * `verifier` - The JWS verifier.
+pub fn deserialize_compact_detached_from_reader(
+    input: impl AsRef<[u8]>,
+    payload: &mut dyn Read,
+    verifier: &dyn JwsVerifier,
+) -> Result<JwsHeader, JoseError> {
+    DEFAULT_CONTEXT.deserialize_compact_detached_from_reader(input, payload, verifier)
+}
+
+/// Verify the input that is formatted by compact serialization
+/// with a detached and unencoded payload (RFC 7797).
+///
+/// # Arguments
+///
+/// * `input` - The input data.
+/// * `payload` - The reader of the detached payload data.
+/// * `selector` - a function for selecting the verifying algorithm.
+pub fn deserialize_compact_detached_from_reader_with_selector<'a, F>(
+    input: impl AsRef<[u8]>,
+    payload: &mut dyn Read,
+    selector: F,
+) -> Result<JwsHeader, JoseError>
+where
+    F: Fn(&JwsHeader) -> Result<Option<&'a dyn JwsVerifier>, JoseError>,
+{
+    DEFAULT_CONTEXT.deserialize_compact_detached_from_reader_with_selector(input, payload, selector)
+}
+
+/// Return a verifier of a specified algorithm from a JWK.
+///
+/// # Arguments
+///
+/// * `alg` - a JWS algorithm name like "ES256".
+/// * `jwk` - a JWK of the verifying key.
+pub fn verifier_from_jwk(alg: &str, jwk: &Jwk) -> Result<Box<dyn JwsVerifier>, JoseError> {
+    let verifier: Box<dyn JwsVerifier> = match alg {
+        "HS256" => Box::new(HS256.verifier_from_jwk(jwk)?),
+        "HS384" => Box::new(HS384.verifier_from_jwk(jwk)?),
+        "HS512" => Box::new(HS512.verifier_from_jwk(jwk)?),
+        "RS256" => Box::new(RS256.verifier_from_jwk(jwk)?),
+        "RS384" => Box::new(RS384.verifier_from_jwk(jwk)?),
+        "RS512" => Box::new(RS512.verifier_from_jwk(jwk)?),
+        "PS256" => Box::new(PS256.verifier_from_jwk(jwk)?),
+        "PS384" => Box::new(PS384.verifier_from_jwk(jwk)?),
+        "PS512" => Box::new(PS512.verifier_from_jwk(jwk)?),
+        "ES256" => Box::new(ES256.verifier_from_jwk(jwk)?),
+        "ES256K" => Box::new(ES256K.verifier_from_jwk(jwk)?),
+        "ES384" => Box::new(ES384.verifier_from_jwk(jwk)?),
+        "ES512" => Box::new(ES512.verifier_from_jwk(jwk)?),
+        "EdDSA" => Box::new(EdDSA.verifier_from_jwk(jwk)?),
+        _ => {
+            return Err(JoseError::UnsupportedSignatureAlgorithm(anyhow::anyhow!(
+                "Unknown algorithm: {}",
+                alg
+            )))
+        }
+    };
+    Ok(verifier)
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
+    use std::io::Cursor;
     use std::path::PathBuf;
 
     use anyhow::Result;
     use once_cell::sync::OnceCell;
 
-    use crate::jws::{self, EdDSA, JwsHeader, JwsHeaderSet, JwsVerifier, ES256, RS256};
+    #[cfg(feature = "async")]
+    use crate::jws::JwsContext;
+    use crate::jws::{
+        self, EdDSA, JwsHeader, JwsHeaderSet, JwsSigner, JwsVerifier, ES256, HS256, PS256, RS256,
+    };
+    #[cfg(feature = "async")]
+    use crate::util::mock_kms::{assert_send, MockKmsSigner};
     use crate::Value;
 
     #[test]
@@ -336,6 +458,164 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_jws_compact_detached_serialization_from_reader() -> Result<()> {
+        let hmac_key = b"0123456789ABCDEF0123456789ABCDEF";
+        let rsa_private_key = load_file("pem/RSA_2048bit_private.pem")?;
+        let rsa_public_key = load_file("pem/RSA_2048bit_public.pem")?;
+        let pss_private_key = load_file("pem/RSA-PSS_2048bit_SHA-256_private.pem")?;
+        let pss_public_key = load_file("pem/RSA-PSS_2048bit_SHA-256_public.pem")?;
+        let ec_private_key = load_file("pem/EC_P-256_private.pem")?;
+        let ec_public_key = load_file("pem/EC_P-256_public.pem")?;
+        let ed_private_key = load_file("pem/ED25519_private.pem")?;
+        let ed_public_key = load_file("pem/ED25519_public.pem")?;
+
+        let pairs: Vec<(Box<dyn JwsSigner>, Box<dyn JwsVerifier>)> = vec![
+            (
+                Box::new(HS256.signer_from_bytes(hmac_key)?),
+                Box::new(HS256.verifier_from_bytes(hmac_key)?),
+            ),
+            (
+                Box::new(RS256.signer_from_pem(&rsa_private_key)?),
+                Box::new(RS256.verifier_from_pem(&rsa_public_key)?),
+            ),
+            (
+                Box::new(PS256.signer_from_pem(&pss_private_key)?),
+                Box::new(PS256.verifier_from_pem(&pss_public_key)?),
+            ),
+            (
+                Box::new(ES256.signer_from_pem(&ec_private_key)?),
+                Box::new(ES256.verifier_from_pem(&ec_public_key)?),
+            ),
+            (
+                Box::new(EdDSA.signer_from_pem(&ed_private_key)?),
+                Box::new(EdDSA.verifier_from_pem(&ed_public_key)?),
+            ),
+        ];
+
+        let src_payload: Vec<u8> = (0..20000).map(|i| (i % 251) as u8).collect();
+        for (signer, verifier) in pairs {
+            let mut src_header = JwsHeader::new();
+            src_header.set_content_type("application/octet-stream");
+            let jws = jws::serialize_compact_detached_from_reader(
+                &mut Cursor::new(&src_payload),
+                &src_header,
+                &*signer,
+            )?;
+            assert!(jws.contains(".."));
+
+            let dst_header = jws::deserialize_compact_detached_from_reader(
+                &jws,
+                &mut Cursor::new(&src_payload),
+                &*verifier,
+            )?;
+            assert_eq!(dst_header.base64url_encode_payload(), Some(false));
+            assert_eq!(dst_header.critical(), Some(vec!["b64"]));
+            assert_eq!(dst_header.content_type(), Some("application/octet-stream"));
+
+            let mut tampered = src_payload.clone();
+            tampered[10000] ^= 1;
+            assert!(jws::deserialize_compact_detached_from_reader(
+                &jws,
+                &mut Cursor::new(&tampered),
+                &*verifier,
+            )
+            .is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jws_compact_detached_serialization_requires_unencoded_payload() -> Result<()> {
+        let signer = HS256.signer_from_bytes(b"0123456789ABCDEF0123456789ABCDEF")?;
+        let verifier = HS256.verifier_from_bytes(b"0123456789ABCDEF0123456789ABCDEF")?;
+
+        let jws = jws::serialize_compact(b"", &JwsHeader::new(), &signer)?;
+        assert!(jws::deserialize_compact_detached_from_reader(
+            &jws,
+            &mut Cursor::new(b"test payload!"),
+            &verifier,
+        )
+        .is_err());
+
+        let jws = jws::serialize_compact(b"test payload!", &JwsHeader::new(), &signer)?;
+        assert!(jws::deserialize_compact_detached_from_reader(
+            &jws,
+            &mut Cursor::new(b"test payload!"),
+            &verifier,
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_jws_async_serialization() -> Result<()> {
+        let private_key = load_file("pem/RSA_2048bit_private.pem")?;
+        let public_key = load_file("pem/RSA_2048bit_public.pem")?;
+
+        let mut signer = RS256.signer_from_pem(&private_key)?;
+        signer.set_key_id("kms-key-1");
+        let kms_signer = MockKmsSigner {
+            key: Box::new(signer.clone()),
+        };
+        let verifier = RS256.verifier_from_pem(&public_key)?;
+        let context = JwsContext::new();
+        let src_payload = b"test payload!";
+
+        let mut src_header = JwsHeader::new();
+        src_header.set_token_type("JWT");
+        let future = context.serialize_compact_async(src_payload, &src_header, &kms_signer);
+        assert_send(&future);
+        let jws = pollster::block_on(future)?;
+        assert_eq!(
+            jws,
+            context.serialize_compact(src_payload, &src_header, &signer)?
+        );
+
+        let (dst_payload, dst_header) = context.deserialize_compact(&jws, &verifier)?;
+        assert_eq!(dst_header.algorithm(), Some("RS256"));
+        assert_eq!(dst_header.key_id(), Some("kms-key-1"));
+        assert_eq!(dst_payload, src_payload);
+
+        let mut src_header = JwsHeaderSet::new();
+        src_header.set_token_type("JWT", true);
+        let jws = pollster::block_on(context.serialize_flattened_json_async(
+            src_payload,
+            &src_header,
+            &kms_signer,
+        ))?;
+        assert_eq!(
+            jws,
+            context.serialize_flattened_json(src_payload, &src_header, &signer)?
+        );
+
+        let jws = pollster::block_on(
+            context.serialize_general_json_async(src_payload, &[(&src_header, &kms_signer)]),
+        )?;
+        assert_eq!(
+            jws,
+            context.serialize_general_json(src_payload, &[(&src_header, &signer)])?
+        );
+
+        let (dst_payload, dst_header) = context.deserialize_json(&jws, &verifier)?;
+        assert_eq!(dst_header.key_id(), Some("kms-key-1"));
+        assert_eq!(dst_payload, src_payload);
+
+        let mut src_header = JwsHeaderSet::new();
+        src_header.set_algorithm("ES256", true);
+        assert!(pollster::block_on(context.serialize_flattened_json_async(
+            src_payload,
+            &src_header,
+            &kms_signer,
+        ))
+        .is_err());
+
+        Ok(())
+    }
+
     fn load_file(path: &str) -> Result<Vec<u8>> {
         let mut pb = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         pb.push("data");
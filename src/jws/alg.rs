@@ -1,5 +1,6 @@
 pub mod ecdsa;
 pub mod eddsa;
+pub mod external;
 pub mod hmac;
 pub mod rsassa;
 pub mod rsassa_pss;
@@ -9,7 +9,10 @@ use crate::jwk::{
     alg::ec::{EcCurve, EcKeyPair},
     Jwk,
 };
-use crate::jws::{JwsAlgorithm, JwsSigner, JwsVerifier};
+use crate::jws::alg::external::{ExternalJwsSigner, ExternalSignatureEncoding};
+use crate::jws::{JwsAlgorithm, JwsSigner, JwsStreamSigner, JwsStreamVerifier, JwsVerifier};
+#[cfg(feature = "pkcs11")]
+use crate::pkcs11::{Pkcs11JwsSigner, Pkcs11Key, Pkcs11SignatureScheme};
 use crate::util::der::{DerBuilder, DerReader, DerType};
 use crate::util::{self, HashAlgorithm};
 use crate::{JoseError, Value};
@@ -130,6 +133,57 @@ impl EcdsaJwsAlgorithm {
         .map_err(|err| JoseError::InvalidKeyFormat(err))
     }
 
+    /// Return a signer from a private key on a PKCS#11 token.
+    ///
+    /// # Arguments
+    /// * `key` - A EC private key object on a PKCS#11 token.
+    #[cfg(feature = "pkcs11")]
+    pub fn signer_from_pkcs11(&self, key: Pkcs11Key) -> Result<Pkcs11JwsSigner, JoseError> {
+        Pkcs11JwsSigner::new(
+            Box::new(self.clone()),
+            Pkcs11SignatureScheme::Ecdsa(self.hash_algorithm(), self.curve()),
+            key,
+        )
+    }
+
+    /// Return a signer that signs the digest of a message with a external function.
+    ///
+    /// The function may return either R || S or a DER encoded ECDSA-Sig-Value.
+    ///
+    /// # Arguments
+    /// * `sign` - A function that returns a signature of the digest.
+    pub fn signer_from_digest_fn(
+        &self,
+        sign: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> ExternalJwsSigner {
+        ExternalJwsSigner::new(
+            Box::new(self.clone()),
+            Some(self.hash_algorithm()),
+            ExternalSignatureEncoding::Ecdsa,
+            self.signature_len(),
+            sign,
+        )
+    }
+
+    /// Return a signer that signs a message with a external function.
+    ///
+    /// The function may return either R || S or a DER encoded ECDSA-Sig-Value.
+    ///
+    /// # Arguments
+    /// * `sign` - A function that returns a signature of the message.
+    pub fn signer_from_message_fn(
+        &self,
+        sign: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> ExternalJwsSigner {
+        ExternalJwsSigner::new(
+            Box::new(self.clone()),
+            None,
+            ExternalSignatureEncoding::Ecdsa,
+            self.signature_len(),
+            sign,
+        )
+    }
+
     /// Return a verifier from a public key that is a DER encoded SubjectPublicKeyInfo.
     ///
     /// # Arguments
@@ -352,33 +406,25 @@ impl JwsSigner for EcdsaJwsSigner {
             signer.update(message)?;
             let der_signature = signer.sign_to_vec()?;
 
-            let signature_len = self.signature_len();
-            let sep = signature_len / 2;
-
-            let mut signature = Vec::with_capacity(signature_len);
-            let mut reader = DerReader::from_bytes(&der_signature);
-            match reader.next()? {
-                Some(DerType::Sequence) => {}
-                _ => unreachable!("A generated signature is invalid."),
-            }
-            match reader.next()? {
-                Some(DerType::Integer) => {
-                    signature.extend_from_slice(&reader.to_be_bytes(false, sep));
-                }
-                _ => unreachable!("A generated signature is invalid."),
-            }
-            match reader.next()? {
-                Some(DerType::Integer) => {
-                    signature.extend_from_slice(&reader.to_be_bytes(false, sep));
-                }
-                _ => unreachable!("A generated signature is invalid."),
-            }
-
+            let signature = der_to_raw_signature(&der_signature, self.signature_len())?;
             Ok(signature)
         })()
         .map_err(|err| JoseError::InvalidSignature(err))
     }
 
+    fn stream_signer(&self) -> Result<Box<dyn JwsStreamSigner + '_>, JoseError> {
+        (|| -> anyhow::Result<Box<dyn JwsStreamSigner + '_>> {
+            let md = self.algorithm.hash_algorithm().message_digest();
+
+            let signer = Signer::new(md, &self.private_key)?;
+            Ok(Box::new(EcdsaJwsStreamSigner {
+                signer,
+                signature_len: self.signature_len(),
+            }))
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+
     fn box_clone(&self) -> Box<dyn JwsSigner> {
         Box::new(self.clone())
     }
@@ -423,27 +469,7 @@ impl JwsVerifier for EcdsaJwsVerifier {
 
     fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), JoseError> {
         (|| -> anyhow::Result<()> {
-            let signature_len = self.algorithm.signature_len();
-            if signature.len() != signature_len {
-                bail!(
-                    "A signature size must be {}: {}",
-                    signature_len,
-                    signature.len()
-                );
-            }
-
-            let mut der_builder = DerBuilder::new();
-            der_builder.begin(DerType::Sequence);
-            {
-                let sep = signature_len / 2;
-
-                let zeros = signature[..sep].iter().take_while(|b| **b == 0).count();
-                der_builder.append_integer_from_be_slice(&signature[zeros..sep], true);
-                let zeros = signature[sep..].iter().take_while(|b| **b == 0).count();
-                der_builder.append_integer_from_be_slice(&signature[(sep + zeros)..], true);
-            }
-            der_builder.end();
-            let der_signature = der_builder.build();
+            let der_signature = raw_to_der_signature(signature, self.algorithm.signature_len())?;
 
             let md = self.algorithm.hash_algorithm().message_digest();
 
@@ -457,6 +483,19 @@ impl JwsVerifier for EcdsaJwsVerifier {
         .map_err(|err| JoseError::InvalidSignature(err))
     }
 
+    fn stream_verifier(&self) -> Result<Box<dyn JwsStreamVerifier + '_>, JoseError> {
+        (|| -> anyhow::Result<Box<dyn JwsStreamVerifier + '_>> {
+            let md = self.algorithm.hash_algorithm().message_digest();
+
+            let verifier = Verifier::new(md, &self.public_key)?;
+            Ok(Box::new(EcdsaJwsStreamVerifier {
+                verifier,
+                signature_len: self.algorithm.signature_len(),
+            }))
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+
     fn box_clone(&self) -> Box<dyn JwsVerifier> {
         Box::new(self.clone())
     }
@@ -470,6 +509,124 @@ impl Deref for EcdsaJwsVerifier {
     }
 }
 
+struct EcdsaJwsStreamSigner<'a> {
+    signer: Signer<'a>,
+    signature_len: usize,
+}
+
+impl JwsStreamSigner for EcdsaJwsStreamSigner<'_> {
+    fn update(&mut self, data: &[u8]) -> Result<(), JoseError> {
+        self.signer
+            .update(data)
+            .map_err(|err| JoseError::InvalidSignature(err.into()))
+    }
+
+    fn finalize(self: Box<Self>) -> Result<Vec<u8>, JoseError> {
+        (|| -> anyhow::Result<Vec<u8>> {
+            let der_signature = self.signer.sign_to_vec()?;
+            let signature = der_to_raw_signature(&der_signature, self.signature_len)?;
+            Ok(signature)
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+}
+
+struct EcdsaJwsStreamVerifier<'a> {
+    verifier: Verifier<'a>,
+    signature_len: usize,
+}
+
+impl JwsStreamVerifier for EcdsaJwsStreamVerifier<'_> {
+    fn update(&mut self, data: &[u8]) -> Result<(), JoseError> {
+        self.verifier
+            .update(data)
+            .map_err(|err| JoseError::InvalidSignature(err.into()))
+    }
+
+    fn finalize(self: Box<Self>, signature: &[u8]) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            let der_signature = raw_to_der_signature(signature, self.signature_len)?;
+            if !self.verifier.verify(&der_signature)? {
+                bail!("The signature does not match.");
+            }
+            Ok(())
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+}
+
+/// Convert a DER encoded ECDSA-Sig-Value into the JWS signature (R || S).
+pub(crate) fn der_to_raw_signature(
+    der_signature: &[u8],
+    signature_len: usize,
+) -> anyhow::Result<Vec<u8>> {
+    let sep = signature_len / 2;
+
+    let mut signature = Vec::with_capacity(signature_len);
+    let mut reader = DerReader::from_bytes(&der_signature);
+    match reader.next()? {
+        Some(DerType::Sequence) => {}
+        _ => bail!("A DER encoded signature must be a sequence."),
+    }
+    for _ in 0..2 {
+        match reader.next()? {
+            Some(DerType::Integer) => {
+                let value = reader.to_be_bytes(false, sep);
+                if value.len() != sep {
+                    bail!("An integer of the signature is too large.");
+                }
+                signature.extend_from_slice(&value);
+            }
+            _ => bail!("A DER encoded signature must contain two integers."),
+        }
+    }
+
+    Ok(signature)
+}
+
+/// Convert a signature that is either R || S or a DER encoded ECDSA-Sig-Value
+/// into the JWS signature (R || S).
+///
+/// The encoding is told by the length alone: a signature of signature_len bytes
+/// is taken as R || S, and any other one is parsed as DER.
+pub(crate) fn to_raw_signature(
+    signature: Vec<u8>,
+    signature_len: usize,
+) -> anyhow::Result<Vec<u8>> {
+    if signature.len() == signature_len {
+        Ok(signature)
+    } else {
+        der_to_raw_signature(&signature, signature_len)
+    }
+}
+
+/// Convert the JWS signature (R || S) into a DER encoded ECDSA-Sig-Value.
+pub(crate) fn raw_to_der_signature(
+    signature: &[u8],
+    signature_len: usize,
+) -> anyhow::Result<Vec<u8>> {
+    if signature.len() != signature_len {
+        bail!(
+            "A signature size must be {}: {}",
+            signature_len,
+            signature.len()
+        );
+    }
+
+    let mut der_builder = DerBuilder::new();
+    der_builder.begin(DerType::Sequence);
+    {
+        let sep = signature_len / 2;
+
+        let zeros = signature[..sep].iter().take_while(|b| **b == 0).count();
+        der_builder.append_integer_from_be_slice(&signature[zeros..sep], true);
+        let zeros = signature[sep..].iter().take_while(|b| **b == 0).count();
+        der_builder.append_integer_from_be_slice(&signature[(sep + zeros)..], true);
+    }
+    der_builder.end();
+    Ok(der_builder.build())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -714,6 +871,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn convert_ecdsa_signature() -> Result<()> {
+        for alg in &[
+            EcdsaJwsAlgorithm::Es256,
+            EcdsaJwsAlgorithm::Es384,
+            EcdsaJwsAlgorithm::Es512,
+        ] {
+            let key_pair = alg.generate_key_pair()?;
+            let signer = alg.signer_from_der(&key_pair.to_der_private_key())?;
+            let raw = signer.sign(b"abcde12345")?;
+            let der = raw_to_der_signature(&raw, alg.signature_len())?;
+
+            assert_eq!(to_raw_signature(raw.clone(), alg.signature_len())?, raw);
+            assert_eq!(to_raw_signature(der, alg.signature_len())?, raw);
+            assert!(to_raw_signature(vec![0; 10], alg.signature_len()).is_err());
+        }
+
+        Ok(())
+    }
+
     fn load_file(path: &str) -> Result<Vec<u8>> {
         let mut pb = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         pb.push("data");
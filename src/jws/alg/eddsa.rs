@@ -9,7 +9,10 @@ use crate::jwk::{
     alg::ed::{EdCurve, EdKeyPair},
     Jwk,
 };
+use crate::jws::alg::external::{ExternalJwsSigner, ExternalSignatureEncoding};
 use crate::jws::{JwsAlgorithm, JwsSigner, JwsVerifier};
+#[cfg(feature = "pkcs11")]
+use crate::pkcs11::{Pkcs11JwsSigner, Pkcs11Key, Pkcs11SignatureScheme};
 use crate::util;
 use crate::{JoseError, Value};
 
@@ -125,6 +128,41 @@ impl EddsaJwsAlgorithm {
         .map_err(|err| JoseError::InvalidKeyFormat(err))
     }
 
+    /// Return a signer from a private key on a PKCS#11 token.
+    ///
+    /// # Arguments
+    /// * `key` - A Edwards curve private key object on a PKCS#11 token.
+    #[cfg(feature = "pkcs11")]
+    pub fn signer_from_pkcs11(&self, key: Pkcs11Key) -> Result<Pkcs11JwsSigner, JoseError> {
+        Pkcs11JwsSigner::new(Box::new(self.clone()), Pkcs11SignatureScheme::Eddsa, key)
+    }
+
+    /// Return a signer that signs a message with a external function.
+    ///
+    /// EdDSA signs the whole message, so there is no digest variant.
+    ///
+    /// # Arguments
+    /// * `curve` - The curve of the Edwards curve key.
+    /// * `sign` - A function that returns a signature of the message.
+    pub fn signer_from_message_fn(
+        &self,
+        curve: EdCurve,
+        sign: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> ExternalJwsSigner {
+        let signature_len = match curve {
+            EdCurve::Ed25519 => 64,
+            EdCurve::Ed448 => 114,
+        };
+
+        ExternalJwsSigner::new(
+            Box::new(self.clone()),
+            None,
+            ExternalSignatureEncoding::Raw,
+            signature_len,
+            sign,
+        )
+    }
+
     /// Return a verifier from a public key that is a DER encoded SubjectPublicKeyInfo.
     ///
     /// # Arguments
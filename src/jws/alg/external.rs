@@ -0,0 +1,382 @@
+//! Signers that delegate signing to an external function, e.g. a cloud KMS or
+//! the transit engine of Vault.
+//!
+//! The function receives either the whole signing input or its digest and may return
+//! a signature in the encoding that the service produces. The signer converts it into
+//! the JWS signature, e.g. a DER encoded ECDSA signature into R || S.
+
+use std::fmt::{self, Debug};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use anyhow::bail;
+use openssl::hash;
+
+use crate::jws::alg::ecdsa;
+use crate::jws::{JwsAlgorithm, JwsSigner};
+use crate::util::HashAlgorithm;
+use crate::JoseError;
+
+type SignFn = dyn Fn(&[u8]) -> Vec<u8> + Send + Sync;
+
+/// The encoding of signatures which an external signing function returns.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum ExternalSignatureEncoding {
+    /// A RSA signature which may be shorter than the modulus by the leading zeros.
+    Rsa,
+    /// A ECDSA signature that is either R || S or a DER encoded ECDSA-Sig-Value.
+    Ecdsa,
+    /// A signature that is already the JWS signature.
+    Raw,
+}
+
+/// A JWS signer that calls an external signing function.
+///
+/// A RSA signature shorter than the modulus is left-padded with zeros.
+/// A ECDSA signature is taken as R || S if its length is the JWS signature length,
+/// and otherwise as a DER encoded ECDSA-Sig-Value. The encoding is told by the length
+/// alone, so a rare DER signature of exactly that length is taken as R || S
+/// and fails the verification.
+#[derive(Clone)]
+pub struct ExternalJwsSigner {
+    algorithm: Box<dyn JwsAlgorithm>,
+    prehash: Option<HashAlgorithm>,
+    encoding: ExternalSignatureEncoding,
+    signature_len: usize,
+    sign: Arc<SignFn>,
+    key_id: Option<String>,
+}
+
+impl ExternalJwsSigner {
+    pub(crate) fn new(
+        algorithm: Box<dyn JwsAlgorithm>,
+        prehash: Option<HashAlgorithm>,
+        encoding: ExternalSignatureEncoding,
+        signature_len: usize,
+        sign: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            algorithm,
+            prehash,
+            encoding,
+            signature_len,
+            sign: Arc::new(sign),
+            key_id: None,
+        }
+    }
+
+    /// Return the hash algorithm of the digest that the signing function receives,
+    /// or None if it receives the whole message.
+    pub fn prehash(&self) -> Option<HashAlgorithm> {
+        self.prehash
+    }
+
+    /// Set a key ID that is used as the kid header claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID of the external key.
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.key_id = Some(value.into());
+    }
+
+    /// Remove the key ID.
+    pub fn remove_key_id(&mut self) {
+        self.key_id = None;
+    }
+}
+
+impl JwsSigner for ExternalJwsSigner {
+    fn algorithm(&self) -> &dyn JwsAlgorithm {
+        self.algorithm.as_ref()
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        match &self.key_id {
+            Some(val) => Some(val.as_ref()),
+            None => None,
+        }
+    }
+
+    fn signature_len(&self) -> usize {
+        self.signature_len
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, JoseError> {
+        (|| -> anyhow::Result<Vec<u8>> {
+            let signature = match self.prehash {
+                Some(hash) => {
+                    let digest = hash::hash(hash.message_digest(), message)?;
+                    (self.sign)(&digest)
+                }
+                None => (self.sign)(message),
+            };
+
+            let signature = match self.encoding {
+                ExternalSignatureEncoding::Rsa if signature.len() < self.signature_len => {
+                    let mut padded = vec![0; self.signature_len - signature.len()];
+                    padded.extend_from_slice(&signature);
+                    padded
+                }
+                ExternalSignatureEncoding::Ecdsa => {
+                    ecdsa::to_raw_signature(signature, self.signature_len)?
+                }
+                _ => signature,
+            };
+
+            if signature.len() != self.signature_len {
+                bail!(
+                    "A signature size must be {}: {}",
+                    self.signature_len,
+                    signature.len()
+                );
+            }
+            Ok(signature)
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+
+    fn box_clone(&self) -> Box<dyn JwsSigner> {
+        Box::new(self.clone())
+    }
+}
+
+impl Deref for ExternalJwsSigner {
+    type Target = dyn JwsSigner;
+
+    fn deref(&self) -> &Self::Target {
+        self
+    }
+}
+
+impl Debug for ExternalJwsSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExternalJwsSigner")
+            .field("algorithm", &self.algorithm)
+            .field("prehash", &self.prehash)
+            .field("encoding", &self.encoding)
+            .field("signature_len", &self.signature_len)
+            .field("key_id", &self.key_id)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::Result;
+    use openssl::ecdsa::EcdsaSig;
+    use openssl::md::{Md, MdRef};
+    use openssl::pkey::PKey;
+    use openssl::pkey_ctx::PkeyCtx;
+    use openssl::rsa::Padding;
+    use openssl::sign::{RsaPssSaltlen, Signer};
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::jwk::alg::ed::EdCurve;
+    use crate::jws::alg::ecdsa::EcdsaJwsAlgorithm;
+    use crate::jws::alg::eddsa::EddsaJwsAlgorithm;
+    use crate::jws::alg::rsassa::RsassaJwsAlgorithm;
+    use crate::jws::alg::rsassa_pss::RsassaPssJwsAlgorithm;
+
+    #[test]
+    fn sign_and_verify_ecdsa_external_der() -> Result<()> {
+        let input = b"abcde12345";
+
+        for &(alg, hash, key) in &[
+            (EcdsaJwsAlgorithm::Es256, HashAlgorithm::Sha256, "EC_P-256"),
+            (EcdsaJwsAlgorithm::Es384, HashAlgorithm::Sha384, "EC_P-384"),
+            (EcdsaJwsAlgorithm::Es512, HashAlgorithm::Sha512, "EC_P-521"),
+            (
+                EcdsaJwsAlgorithm::Es256k,
+                HashAlgorithm::Sha256,
+                "EC_secp256k1",
+            ),
+        ] {
+            let private_key = load_file(&format!("pem/{}_private.pem", key))?;
+            let public_key = load_file(&format!("pem/{}_public.pem", key))?;
+            let verifier = alg.verifier_from_pem(&public_key)?;
+
+            let ec_key = PKey::private_key_from_pem(&private_key)?.ec_key()?;
+            let signer = alg.signer_from_digest_fn(move |digest| {
+                EcdsaSig::sign(digest, &ec_key).unwrap().to_der().unwrap()
+            });
+            assert_eq!(signer.prehash(), Some(hash));
+            let signature = signer.sign(input)?;
+            assert_eq!(signature.len(), signer.signature_len());
+            verifier.verify(input, &signature)?;
+
+            let pkey = PKey::private_key_from_pem(&private_key)?;
+            let md = hash.message_digest();
+            let signer = alg.signer_from_message_fn(move |message| {
+                let mut signer = Signer::new(md, &pkey).unwrap();
+                signer.update(message).unwrap();
+                signer.sign_to_vec().unwrap()
+            });
+            assert_eq!(signer.prehash(), None);
+            let signature = signer.sign(input)?;
+            verifier.verify(input, &signature)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sign_and_verify_ecdsa_external_raw() -> Result<()> {
+        let input = b"abcde12345";
+
+        let alg = EcdsaJwsAlgorithm::Es256;
+        let private_key = load_file("pem/EC_P-256_private.pem")?;
+        let public_key = load_file("pem/EC_P-256_public.pem")?;
+
+        let key_signer = alg.signer_from_pem(&private_key)?;
+        let signer = alg.signer_from_message_fn(move |message| key_signer.sign(message).unwrap());
+        let signature = signer.sign(input)?;
+
+        let verifier = alg.verifier_from_pem(&public_key)?;
+        verifier.verify(input, &signature)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn sign_and_verify_rsassa_external() -> Result<()> {
+        let input = b"abcde12345";
+
+        let private_key = load_file("pem/RSA_2048bit_private.pem")?;
+        let public_key = load_file("pem/RSA_2048bit_public.pem")?;
+
+        for &(alg, hash) in &[
+            (RsassaJwsAlgorithm::Rs256, HashAlgorithm::Sha256),
+            (RsassaJwsAlgorithm::Rs384, HashAlgorithm::Sha384),
+            (RsassaJwsAlgorithm::Rs512, HashAlgorithm::Sha512),
+        ] {
+            let verifier = alg.verifier_from_pem(&public_key)?;
+
+            let pkey = PKey::private_key_from_pem(&private_key)?;
+            let md = hash.message_digest();
+            let signer = alg.signer_from_digest_fn(2048, move |digest| {
+                let mut ctx = PkeyCtx::new(&pkey).unwrap();
+                ctx.sign_init().unwrap();
+                ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+                ctx.set_signature_md(md_of(hash)).unwrap();
+                let mut signature = Vec::new();
+                ctx.sign_to_vec(digest, &mut signature).unwrap();
+                signature
+            })?;
+            let signature = signer.sign(input)?;
+            assert_eq!(signature.len(), 256);
+            verifier.verify(input, &signature)?;
+
+            let pkey = PKey::private_key_from_pem(&private_key)?;
+            let signer = alg.signer_from_message_fn(2048, move |message| {
+                let mut signer = Signer::new(md, &pkey).unwrap();
+                signer.update(message).unwrap();
+                signer.sign_to_vec().unwrap()
+            })?;
+            let signature = signer.sign(input)?;
+            verifier.verify(input, &signature)?;
+        }
+
+        assert!(RsassaJwsAlgorithm::Rs256
+            .signer_from_message_fn(1024, |_| Vec::new())
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sign_and_verify_rsassa_pss_external() -> Result<()> {
+        let input = b"abcde12345";
+
+        let private_key = load_file("pem/RSA_2048bit_private.pem")?;
+        let public_key = load_file("pem/RSA_2048bit_traditional_public.pem")?;
+
+        for &(alg, hash) in &[
+            (RsassaPssJwsAlgorithm::Ps256, HashAlgorithm::Sha256),
+            (RsassaPssJwsAlgorithm::Ps384, HashAlgorithm::Sha384),
+            (RsassaPssJwsAlgorithm::Ps512, HashAlgorithm::Sha512),
+        ] {
+            let verifier = alg.verifier_from_pem(&public_key)?;
+
+            let pkey = PKey::private_key_from_pem(&private_key)?;
+            let signer = alg.signer_from_digest_fn(2048, move |digest| {
+                let mut ctx = PkeyCtx::new(&pkey).unwrap();
+                ctx.sign_init().unwrap();
+                ctx.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+                ctx.set_signature_md(md_of(hash)).unwrap();
+                ctx.set_rsa_mgf1_md(md_of(hash)).unwrap();
+                ctx.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)
+                    .unwrap();
+                let mut signature = Vec::new();
+                ctx.sign_to_vec(digest, &mut signature).unwrap();
+                signature
+            })?;
+            let signature = signer.sign(input)?;
+            verifier.verify(input, &signature)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sign_and_verify_eddsa_external() -> Result<()> {
+        let input = b"abcde12345";
+
+        for (curve, key) in &[(EdCurve::Ed25519, "ED25519"), (EdCurve::Ed448, "ED448")] {
+            let alg = EddsaJwsAlgorithm::Eddsa;
+            let private_key = load_file(&format!("pem/{}_private.pem", key))?;
+            let public_key = load_file(&format!("pem/{}_public.pem", key))?;
+
+            let pkey = PKey::private_key_from_pem(&private_key)?;
+            let signer = alg.signer_from_message_fn(*curve, move |message| {
+                let mut signer = Signer::new_without_digest(&pkey).unwrap();
+                signer.sign_oneshot_to_vec(message).unwrap()
+            });
+            let signature = signer.sign(input)?;
+
+            let verifier = alg.verifier_from_pem(&public_key)?;
+            verifier.verify(input, &signature)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_external_signature() -> Result<()> {
+        let signer = EcdsaJwsAlgorithm::Es256.signer_from_digest_fn(|_| vec![0; 10]);
+        assert!(signer.sign(b"abcde12345").is_err());
+
+        let signer =
+            EddsaJwsAlgorithm::Eddsa.signer_from_message_fn(EdCurve::Ed25519, |_| Vec::new());
+        assert!(signer.sign(b"abcde12345").is_err());
+
+        let signer = RsassaJwsAlgorithm::Rs256.signer_from_message_fn(2048, |_| vec![1; 257])?;
+        assert!(signer.sign(b"abcde12345").is_err());
+
+        let signer = RsassaJwsAlgorithm::Rs256.signer_from_message_fn(2048, |_| vec![1; 255])?;
+        assert_eq!(signer.sign(b"abcde12345")?[..2], [0, 1]);
+
+        Ok(())
+    }
+
+    fn md_of(hash: HashAlgorithm) -> &'static MdRef {
+        match hash {
+            HashAlgorithm::Sha256 => Md::sha256(),
+            HashAlgorithm::Sha384 => Md::sha384(),
+            HashAlgorithm::Sha512 => Md::sha512(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn load_file(path: &str) -> Result<Vec<u8>> {
+        let mut pb = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        pb.push("data");
+        pb.push(path);
+
+        let data = fs::read(&pb)?;
+        Ok(data)
+    }
+}
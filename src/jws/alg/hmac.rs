@@ -2,11 +2,12 @@ use std::fmt::Display;
 use std::ops::Deref;
 
 use anyhow::bail;
+use openssl::memcmp;
 use openssl::pkey::{PKey, Private};
 use openssl::sign::Signer;
 
 use crate::jwk::Jwk;
-use crate::jws::{JwsAlgorithm, JwsSigner, JwsVerifier};
+use crate::jws::{JwsAlgorithm, JwsSigner, JwsStreamSigner, JwsStreamVerifier, JwsVerifier};
 use crate::util::{self, HashAlgorithm};
 use crate::{JoseError, Value};
 
@@ -279,6 +280,16 @@ impl JwsSigner for HmacJwsSigner {
         .map_err(|err| JoseError::InvalidSignature(err))
     }
 
+    fn stream_signer(&self) -> Result<Box<dyn JwsStreamSigner + '_>, JoseError> {
+        (|| -> anyhow::Result<Box<dyn JwsStreamSigner + '_>> {
+            let md = self.algorithm.hash_algorithm().message_digest();
+
+            let signer = Signer::new(md, &self.private_key)?;
+            Ok(Box::new(HmacJwsStreamSigner { signer }))
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+
     fn box_clone(&self) -> Box<dyn JwsSigner> {
         Box::new(self.clone())
     }
@@ -336,6 +347,16 @@ impl JwsVerifier for HmacJwsVerifier {
         .map_err(|err| JoseError::InvalidSignature(err))
     }
 
+    fn stream_verifier(&self) -> Result<Box<dyn JwsStreamVerifier + '_>, JoseError> {
+        (|| -> anyhow::Result<Box<dyn JwsStreamVerifier + '_>> {
+            let md = self.algorithm.hash_algorithm().message_digest();
+
+            let signer = Signer::new(md, &self.private_key)?;
+            Ok(Box::new(HmacJwsStreamVerifier { signer }))
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+
     fn box_clone(&self) -> Box<dyn JwsVerifier> {
         Box::new(self.clone())
     }
@@ -349,6 +370,47 @@ impl Deref for HmacJwsVerifier {
     }
 }
 
+struct HmacJwsStreamSigner<'a> {
+    signer: Signer<'a>,
+}
+
+impl JwsStreamSigner for HmacJwsStreamSigner<'_> {
+    fn update(&mut self, data: &[u8]) -> Result<(), JoseError> {
+        self.signer
+            .update(data)
+            .map_err(|err| JoseError::InvalidSignature(err.into()))
+    }
+
+    fn finalize(self: Box<Self>) -> Result<Vec<u8>, JoseError> {
+        self.signer
+            .sign_to_vec()
+            .map_err(|err| JoseError::InvalidSignature(err.into()))
+    }
+}
+
+struct HmacJwsStreamVerifier<'a> {
+    signer: Signer<'a>,
+}
+
+impl JwsStreamVerifier for HmacJwsStreamVerifier<'_> {
+    fn update(&mut self, data: &[u8]) -> Result<(), JoseError> {
+        self.signer
+            .update(data)
+            .map_err(|err| JoseError::InvalidSignature(err.into()))
+    }
+
+    fn finalize(self: Box<Self>, signature: &[u8]) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            let new_signature = self.signer.sign_to_vec()?;
+            if new_signature.len() != signature.len() || !memcmp::eq(&new_signature, signature) {
+                bail!("Failed to verify.");
+            }
+            Ok(())
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -6,7 +6,10 @@ use openssl::pkey::{PKey, Private, Public};
 use openssl::sign::{Signer, Verifier};
 
 use crate::jwk::{alg::rsa::RsaKeyPair, Jwk};
-use crate::jws::{JwsAlgorithm, JwsSigner, JwsVerifier};
+use crate::jws::alg::external::{ExternalJwsSigner, ExternalSignatureEncoding};
+use crate::jws::{JwsAlgorithm, JwsSigner, JwsStreamSigner, JwsStreamVerifier, JwsVerifier};
+#[cfg(feature = "pkcs11")]
+use crate::pkcs11::{Pkcs11JwsSigner, Pkcs11Key, Pkcs11SignatureScheme};
 use crate::util::der::{DerBuilder, DerType};
 use crate::util::{self, HashAlgorithm};
 use crate::{JoseError, Value};
@@ -161,6 +164,75 @@ impl RsassaJwsAlgorithm {
         .map_err(|err| JoseError::InvalidKeyFormat(err))
     }
 
+    /// Return a signer from a private key on a PKCS#11 token.
+    ///
+    /// # Arguments
+    /// * `key` - A RSA private key object on a PKCS#11 token.
+    #[cfg(feature = "pkcs11")]
+    pub fn signer_from_pkcs11(&self, key: Pkcs11Key) -> Result<Pkcs11JwsSigner, JoseError> {
+        Pkcs11JwsSigner::new(
+            Box::new(self.clone()),
+            Pkcs11SignatureScheme::RsaPkcs1(self.hash_algorithm()),
+            key,
+        )
+    }
+
+    /// Return a signer that signs the digest of a message with a external function.
+    ///
+    /// The function may omit the leading zeros of the signature.
+    ///
+    /// # Arguments
+    /// * `bits` - The key length of the RSA key in bits.
+    /// * `sign` - A function that returns a signature of the digest.
+    pub fn signer_from_digest_fn(
+        &self,
+        bits: u32,
+        sign: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> Result<ExternalJwsSigner, JoseError> {
+        (|| -> anyhow::Result<ExternalJwsSigner> {
+            if bits < 2048 {
+                bail!("key length must be 2048 or more.");
+            }
+
+            Ok(ExternalJwsSigner::new(
+                Box::new(self.clone()),
+                Some(self.hash_algorithm()),
+                ExternalSignatureEncoding::Rsa,
+                util::ceiling(bits as usize, 8),
+                sign,
+            ))
+        })()
+        .map_err(JoseError::InvalidKeyFormat)
+    }
+
+    /// Return a signer that signs a message with a external function.
+    ///
+    /// The function may omit the leading zeros of the signature.
+    ///
+    /// # Arguments
+    /// * `bits` - The key length of the RSA key in bits.
+    /// * `sign` - A function that returns a signature of the message.
+    pub fn signer_from_message_fn(
+        &self,
+        bits: u32,
+        sign: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> Result<ExternalJwsSigner, JoseError> {
+        (|| -> anyhow::Result<ExternalJwsSigner> {
+            if bits < 2048 {
+                bail!("key length must be 2048 or more.");
+            }
+
+            Ok(ExternalJwsSigner::new(
+                Box::new(self.clone()),
+                None,
+                ExternalSignatureEncoding::Rsa,
+                util::ceiling(bits as usize, 8),
+                sign,
+            ))
+        })()
+        .map_err(JoseError::InvalidKeyFormat)
+    }
+
     /// Return the verifier from a public key that is a DER encoded SubjectPublicKeyInfo or PKCS#1 RSAPublicKey.
     ///
     /// # Arguments
@@ -384,6 +456,16 @@ impl JwsSigner for RsassaJwsSigner {
         .map_err(|err| JoseError::InvalidSignature(err))
     }
 
+    fn stream_signer(&self) -> Result<Box<dyn JwsStreamSigner + '_>, JoseError> {
+        (|| -> anyhow::Result<Box<dyn JwsStreamSigner + '_>> {
+            let md = self.algorithm.hash_algorithm().message_digest();
+
+            let signer = Signer::new(md, &self.private_key)?;
+            Ok(Box::new(RsassaJwsStreamSigner { signer }))
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+
     fn box_clone(&self) -> Box<dyn JwsSigner> {
         Box::new(self.clone())
     }
@@ -440,6 +522,16 @@ impl JwsVerifier for RsassaJwsVerifier {
         .map_err(|err| JoseError::InvalidSignature(err))
     }
 
+    fn stream_verifier(&self) -> Result<Box<dyn JwsStreamVerifier + '_>, JoseError> {
+        (|| -> anyhow::Result<Box<dyn JwsStreamVerifier + '_>> {
+            let md = self.algorithm.hash_algorithm().message_digest();
+
+            let verifier = Verifier::new(md, &self.public_key)?;
+            Ok(Box::new(RsassaJwsStreamVerifier { verifier }))
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+
     fn box_clone(&self) -> Box<dyn JwsVerifier> {
         Box::new(self.clone())
     }
@@ -453,6 +545,46 @@ impl Deref for RsassaJwsVerifier {
     }
 }
 
+struct RsassaJwsStreamSigner<'a> {
+    signer: Signer<'a>,
+}
+
+impl JwsStreamSigner for RsassaJwsStreamSigner<'_> {
+    fn update(&mut self, data: &[u8]) -> Result<(), JoseError> {
+        self.signer
+            .update(data)
+            .map_err(|err| JoseError::InvalidSignature(err.into()))
+    }
+
+    fn finalize(self: Box<Self>) -> Result<Vec<u8>, JoseError> {
+        self.signer
+            .sign_to_vec()
+            .map_err(|err| JoseError::InvalidSignature(err.into()))
+    }
+}
+
+struct RsassaJwsStreamVerifier<'a> {
+    verifier: Verifier<'a>,
+}
+
+impl JwsStreamVerifier for RsassaJwsStreamVerifier<'_> {
+    fn update(&mut self, data: &[u8]) -> Result<(), JoseError> {
+        self.verifier
+            .update(data)
+            .map_err(|err| JoseError::InvalidSignature(err.into()))
+    }
+
+    fn finalize(self: Box<Self>, signature: &[u8]) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            if !self.verifier.verify(signature)? {
+                bail!("The signature does not match.");
+            }
+            Ok(())
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
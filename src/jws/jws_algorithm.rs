@@ -41,6 +41,18 @@ pub trait JwsSigner: Debug + Send + Sync {
     /// * `message` - The message data to sign.
     fn sign(&self, message: &[u8]) -> Result<Vec<u8>, JoseError>;
 
+    /// Return a signer that computes a signature incrementally.
+    ///
+    /// The default implementation buffers the whole message and calls `sign` at the end,
+    /// so the memory use grows with the message size. EdDSA signers use this default,
+    /// since Ed25519 and Ed448 can't sign a message incrementally.
+    fn stream_signer(&self) -> Result<Box<dyn JwsStreamSigner + '_>, JoseError> {
+        Ok(Box::new(BufferedJwsStreamSigner {
+            signer: self,
+            message: Vec::new(),
+        }))
+    }
+
     fn box_clone(&self) -> Box<dyn JwsSigner>;
 }
 
@@ -50,6 +62,28 @@ impl Clone for Box<dyn JwsSigner> {
     }
 }
 
+/// A signer that computes signatures asynchronously,
+/// e.g. by calling a remote KMS or HSM.
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncJwsSigner: Debug + Send + Sync {
+    /// Return the source algorithm instance.
+    fn algorithm(&self) -> &dyn JwsAlgorithm;
+
+    /// Return the source key ID.
+    fn key_id(&self) -> Option<&str>;
+
+    /// Return the signature length of JWS.
+    fn signature_len(&self) -> usize;
+
+    /// Return a signature of the data.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message data to sign.
+    async fn sign(&self, message: &[u8]) -> Result<Vec<u8>, JoseError>;
+}
+
 pub trait JwsVerifier: Debug + Send + Sync {
     /// Return the source algrithm instance.
     fn algorithm(&self) -> &dyn JwsAlgorithm;
@@ -66,6 +100,18 @@ pub trait JwsVerifier: Debug + Send + Sync {
     /// * `signature` - a signature data.
     fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), JoseError>;
 
+    /// Return a verifier that processes a message incrementally.
+    ///
+    /// The default implementation buffers the whole message and calls `verify` at the end,
+    /// so the memory use grows with the message size. EdDSA verifiers use this default,
+    /// since Ed25519 and Ed448 can't verify a message incrementally.
+    fn stream_verifier(&self) -> Result<Box<dyn JwsStreamVerifier + '_>, JoseError> {
+        Ok(Box::new(BufferedJwsStreamVerifier {
+            verifier: self,
+            message: Vec::new(),
+        }))
+    }
+
     fn box_clone(&self) -> Box<dyn JwsVerifier>;
 }
 
@@ -74,3 +120,63 @@ impl Clone for Box<dyn JwsVerifier> {
         self.box_clone()
     }
 }
+
+pub trait JwsStreamSigner {
+    /// Append a part of the message to sign.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The next part of the message data.
+    fn update(&mut self, data: &[u8]) -> Result<(), JoseError>;
+
+    /// Return a signature of the whole appended message.
+    fn finalize(self: Box<Self>) -> Result<Vec<u8>, JoseError>;
+}
+
+pub trait JwsStreamVerifier {
+    /// Append a part of the message to verify.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The next part of the message data.
+    fn update(&mut self, data: &[u8]) -> Result<(), JoseError>;
+
+    /// Verify the whole appended message by the signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature` - a signature data.
+    fn finalize(self: Box<Self>, signature: &[u8]) -> Result<(), JoseError>;
+}
+
+struct BufferedJwsStreamSigner<'a, T: JwsSigner + ?Sized> {
+    signer: &'a T,
+    message: Vec<u8>,
+}
+
+impl<'a, T: JwsSigner + ?Sized> JwsStreamSigner for BufferedJwsStreamSigner<'a, T> {
+    fn update(&mut self, data: &[u8]) -> Result<(), JoseError> {
+        self.message.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn finalize(self: Box<Self>) -> Result<Vec<u8>, JoseError> {
+        self.signer.sign(&self.message)
+    }
+}
+
+struct BufferedJwsStreamVerifier<'a, T: JwsVerifier + ?Sized> {
+    verifier: &'a T,
+    message: Vec<u8>,
+}
+
+impl<'a, T: JwsVerifier + ?Sized> JwsStreamVerifier for BufferedJwsStreamVerifier<'a, T> {
+    fn update(&mut self, data: &[u8]) -> Result<(), JoseError> {
+        self.message.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn finalize(self: Box<Self>, signature: &[u8]) -> Result<(), JoseError> {
+        self.verifier.verify(&self.message, signature)
+    }
+}
@@ -1,12 +1,17 @@
 use std::collections::BTreeSet;
 use std::fmt::Debug;
+use std::io::Read;
 
 use anyhow::bail;
 
-use crate::jws::{JwsHeader, JwsHeaderSet, JwsSigner, JwsVerifier};
+#[cfg(feature = "async")]
+use crate::jws::AsyncJwsSigner;
+use crate::jws::{JwsAlgorithm, JwsHeader, JwsHeaderSet, JwsSigner, JwsVerifier};
 use crate::util;
 use crate::{JoseError, Map, Value};
 
+const STREAM_BUFFER_SIZE: usize = 8192;
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct JwsContext {
     acceptable_criticals: BTreeSet<String>,
@@ -79,51 +84,18 @@ impl JwsContext {
         F: Fn(&JwsHeader) -> Option<&'a dyn JwsSigner>,
     {
         (|| -> anyhow::Result<String> {
-            let mut b64 = true;
-            if let Some(vals) = header.critical() {
-                if vals.contains(&"b64") {
-                    if let Some(val) = header.base64url_encode_payload() {
-                        b64 = val;
-                    }
-                }
-            }
-
             let signer = match selector(header) {
                 Some(val) => val,
                 None => bail!("A signer is not found."),
             };
 
-            let mut header = header.claims_set().clone();
-            header.insert(
-                "alg".to_string(),
-                Value::String(signer.algorithm().name().to_string()),
-            );
-            if let Some(key_id) = signer.key_id() {
-                header.insert("kid".to_string(), Value::String(key_id.to_string()));
-            }
-            let header_bytes = serde_json::to_vec(&header)?;
-
-            let mut capacity = 2;
-            capacity += util::ceiling(header_bytes.len() * 4, 3);
-            capacity += if b64 {
-                util::ceiling(payload.len() * 4, 3)
-            } else {
-                payload.len()
-            };
-            capacity += util::ceiling(signer.signature_len() * 4, 3);
-
-            let mut message = String::with_capacity(capacity);
-            util::encode_base64_urlsafe_nopad_buf(header_bytes, &mut message);
-            message.push_str(".");
-            if b64 {
-                util::encode_base64_urlsafe_nopad_buf(payload, &mut message);
-            } else {
-                let payload = std::str::from_utf8(payload)?;
-                if payload.contains(".") {
-                    bail!("A JWS payload cannot contain dot.");
-                }
-                message.push_str(payload);
-            }
+            let mut message = prepare_compact_signing(
+                payload,
+                header,
+                signer.algorithm(),
+                signer.key_id(),
+                signer.signature_len(),
+            )?;
 
             let signature = signer.sign(message.as_bytes())?;
 
@@ -192,50 +164,17 @@ impl JwsContext {
                     None => bail!("A signer is not found."),
                 };
 
-                let mut protected_map = header.claims_set(true).clone();
-
-                match merged.algorithm() {
-                    Some(val) if val == signer.algorithm().name() => {}
-                    Some(_) => bail!("A signer is unmatched."),
-                    None => {
-                        protected_map.insert(
-                            "alg".to_string(),
-                            Value::String(signer.algorithm().name().to_string()),
-                        );
-                    }
-                }
-
-                if let None = merged.key_id() {
-                    if let Some(key_id) = signer.key_id() {
-                        protected_map.insert("kid".to_string(), Value::String(key_id.to_string()));
-                    }
-                }
-
-                if i > 0 {
-                    result.push_str(",");
-                }
-
-                let protected_bytes = serde_json::to_vec(&protected_map)?;
-                let protected_b64 = util::encode_base64_urlsafe_nopad(&protected_bytes);
-
-                let unprotected_map = header.claims_set(false);
+                let protected_b64 =
+                    encode_protected_header(header, &merged, signer.algorithm(), signer.key_id())?;
 
                 let message = format!("{}.{}", &protected_b64, &payload_b64);
                 let signature = signer.sign(message.as_bytes())?;
 
-                result.push_str("{\"protected\":\"");
-                result.push_str(&protected_b64);
-                result.push_str("\"");
-
-                if unprotected_map.len() > 0 {
-                    let unprotected = serde_json::to_string(&unprotected_map)?;
-                    result.push_str(",\"header\":");
-                    result.push_str(&unprotected);
+                if i > 0 {
+                    result.push_str(",");
                 }
-
-                result.push_str(",\"signature\":\"");
-                util::encode_base64_urlsafe_nopad_buf(&signature, &mut result);
-                result.push_str("\"}");
+                push_json_headers(&mut result, &protected_b64, header)?;
+                push_json_signature(&mut result, &signature);
             }
 
             result.push_str("],\"payload\":\"");
@@ -283,23 +222,6 @@ impl JwsContext {
         F: Fn(&JwsHeader) -> Option<&'a dyn JwsSigner>,
     {
         (|| -> anyhow::Result<String> {
-            let protected_map = header.claims_set(true);
-            let mut b64 = true;
-            match protected_map.get("crit") {
-                Some(Value::Array(vals)) => {
-                    if vals.iter().any(|val| match val {
-                        Value::String(val2) => val2 == "b64",
-                        _ => false,
-                    }) {
-                        b64 = match protected_map.get("b64") {
-                            Some(Value::Bool(val3)) => *val3,
-                            _ => false,
-                        };
-                    }
-                }
-                _ => {}
-            }
-
             let merged_map = header.to_map();
             let merged = JwsHeader::from_map(merged_map)?;
             let signer = match selector(&merged) {
@@ -307,60 +229,242 @@ impl JwsContext {
                 None => bail!("A signer is not found."),
             };
 
-            let mut protected_map = protected_map.clone();
+            let protected_b64 =
+                encode_protected_header(header, &merged, signer.algorithm(), signer.key_id())?;
+            let payload = encode_flattened_json_payload(payload, header)?;
 
-            match merged.algorithm() {
-                Some(val) if val == signer.algorithm().name() => {}
-                Some(_) => bail!("A signer is unmatched."),
-                None => {
-                    protected_map.insert(
-                        "alg".to_string(),
-                        Value::String(signer.algorithm().name().to_string()),
-                    );
-                }
-            }
+            let message = format!("{}.{}", &protected_b64, &payload);
+            let signature = signer.sign(message.as_bytes())?;
+
+            let mut json = String::new();
+            push_json_headers(&mut json, &protected_b64, header)?;
+            json.push_str(",\"payload\":\"");
+            json.push_str(&payload);
+            json.push_str("\"");
+            push_json_signature(&mut json, &signature);
+
+            Ok(json)
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwsFormat(err),
+        })
+    }
+
+    /// Return a representation of the data that is formatted by compact serialization
+    /// with a asynchronous signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload data.
+    /// * `header` - The JWS heaser claims.
+    /// * `signer` - The asynchronous JWS signer.
+    #[cfg(feature = "async")]
+    pub async fn serialize_compact_async(
+        &self,
+        payload: &[u8],
+        header: &JwsHeader,
+        signer: &dyn AsyncJwsSigner,
+    ) -> Result<String, JoseError> {
+        async {
+            let mut message = prepare_compact_signing(
+                payload,
+                header,
+                signer.algorithm(),
+                signer.key_id(),
+                signer.signature_len(),
+            )?;
+
+            let signature = signer.sign(message.as_bytes()).await?;
+
+            message.push_str(".");
+            util::encode_base64_urlsafe_nopad_buf(signature, &mut message);
+
+            Ok(message)
+        }
+        .await
+        .map_err(|err: anyhow::Error| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwsFormat(err),
+        })
+    }
+
+    /// Return a representation of the data that is formatted by general json serialization
+    /// with asynchronous signers.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload data.
+    /// * `signers` - The asynchronous JWS signers.
+    #[cfg(feature = "async")]
+    pub async fn serialize_general_json_async(
+        &self,
+        payload: &[u8],
+        signers: &[(&JwsHeaderSet, &dyn AsyncJwsSigner)],
+    ) -> Result<String, JoseError> {
+        async {
+            let payload_b64 = util::encode_base64_urlsafe_nopad(payload);
+
+            let mut result = String::new();
+            result.push_str("{\"signatures\":[");
+
+            for (i, (header, signer)) in signers.iter().enumerate() {
+                let merged = JwsHeader::from_map(header.to_map())?;
+                let protected_b64 =
+                    encode_protected_header(header, &merged, signer.algorithm(), signer.key_id())?;
 
-            if let None = merged.key_id() {
-                if let Some(key_id) = signer.key_id() {
-                    protected_map.insert("kid".to_string(), Value::String(key_id.to_string()));
+                let message = format!("{}.{}", &protected_b64, &payload_b64);
+                let signature = signer.sign(message.as_bytes()).await?;
+
+                if i > 0 {
+                    result.push_str(",");
                 }
+                push_json_headers(&mut result, &protected_b64, header)?;
+                push_json_signature(&mut result, &signature);
             }
 
-            let protected_json = serde_json::to_string(&protected_map)?;
-            let protected_b64 = util::encode_base64_urlsafe_nopad(protected_json);
+            result.push_str("],\"payload\":\"");
+            result.push_str(&payload_b64);
+            result.push_str("\"}");
 
-            let payload_b64;
-            let payload = if b64 {
-                payload_b64 = util::encode_base64_urlsafe_nopad(payload);
-                &payload_b64
-            } else {
-                std::str::from_utf8(payload)?
-            };
+            Ok(result)
+        }
+        .await
+        .map_err(|err: anyhow::Error| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwsFormat(err),
+        })
+    }
 
-            let message = format!("{}.{}", &protected_b64, payload);
-            let signature = signer.sign(message.as_bytes())?;
+    /// Return a representation of the data that is formatted by flattened json serialization
+    /// with a asynchronous signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload data.
+    /// * `header` - The JWS protected and unprotected header claims.
+    /// * `signer` - The asynchronous JWS signer.
+    #[cfg(feature = "async")]
+    pub async fn serialize_flattened_json_async(
+        &self,
+        payload: &[u8],
+        header: &JwsHeaderSet,
+        signer: &dyn AsyncJwsSigner,
+    ) -> Result<String, JoseError> {
+        async {
+            let merged = JwsHeader::from_map(header.to_map())?;
+            let protected_b64 =
+                encode_protected_header(header, &merged, signer.algorithm(), signer.key_id())?;
+            let payload = encode_flattened_json_payload(payload, header)?;
+
+            let message = format!("{}.{}", &protected_b64, &payload);
+            let signature = signer.sign(message.as_bytes()).await?;
 
             let mut json = String::new();
-            json.push_str("{\"protected\":\"");
-            json.push_str(&protected_b64);
+            push_json_headers(&mut json, &protected_b64, header)?;
+            json.push_str(",\"payload\":\"");
+            json.push_str(&payload);
             json.push_str("\"");
+            push_json_signature(&mut json, &signature);
+
+            Ok(json)
+        }
+        .await
+        .map_err(|err: anyhow::Error| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwsFormat(err),
+        })
+    }
+
+    /// Return a representation of the data that is formatted by compact serialization
+    /// with a detached and unencoded payload (RFC 7797).
+    ///
+    /// The payload is read from the reader and signed incrementally,
+    /// so it is never loaded into memory at once.
+    /// However a signer without incremental support like EdDSA
+    /// buffers the whole payload (see `JwsSigner::stream_signer`).
+    /// The b64 header claim is set to false and listed in the crit header claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The reader of the payload data.
+    /// * `header` - The JWS heaser claims.
+    /// * `signer` - The JWS signer.
+    pub fn serialize_compact_detached_from_reader(
+        &self,
+        payload: &mut dyn Read,
+        header: &JwsHeader,
+        signer: &dyn JwsSigner,
+    ) -> Result<String, JoseError> {
+        self.serialize_compact_detached_from_reader_with_selector(payload, header, |_header| {
+            Some(signer)
+        })
+    }
+
+    /// Return a representation of the data that is formatted by compact serialization
+    /// with a detached and unencoded payload (RFC 7797).
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The reader of the payload data.
+    /// * `header` - The JWS heaser claims.
+    /// * `selector` - a function for selecting the signing algorithm.
+    pub fn serialize_compact_detached_from_reader_with_selector<'a, F>(
+        &self,
+        payload: &mut dyn Read,
+        header: &JwsHeader,
+        selector: F,
+    ) -> Result<String, JoseError>
+    where
+        F: Fn(&JwsHeader) -> Option<&'a dyn JwsSigner>,
+    {
+        (|| -> anyhow::Result<String> {
+            let signer = match selector(header) {
+                Some(val) => val,
+                None => bail!("A signer is not found."),
+            };
 
-            let unprotected = header.claims_set(false);
-            if unprotected.len() > 0 {
-                let unprotcted_json = serde_json::to_string(unprotected)?;
-                json.push_str(",\"header\":");
-                json.push_str(&unprotcted_json);
+            let mut header = header.clone();
+            header.set_algorithm(signer.algorithm().name());
+            if let Some(key_id) = signer.key_id() {
+                header.set_key_id(key_id);
+            }
+            header.set_base64url_encode_payload(false);
+            let mut critical: Vec<String> = match header.critical() {
+                Some(vals) => vals.iter().map(|val| val.to_string()).collect(),
+                None => Vec::new(),
+            };
+            if !critical.iter().any(|val| val == "b64") {
+                critical.push("b64".to_string());
             }
+            header.set_critical(&critical);
 
-            json.push_str(",\"payload\":\"");
-            json.push_str(&payload);
-            json.push_str("\"");
+            let header_bytes = serde_json::to_vec(header.claims_set())?;
 
-            json.push_str(",\"signature\":\"");
-            util::encode_base64_urlsafe_nopad_buf(&signature, &mut json);
-            json.push_str("\"}");
+            let mut message = String::with_capacity(
+                util::ceiling(header_bytes.len() * 4, 3)
+                    + util::ceiling(signer.signature_len() * 4, 3)
+                    + 2,
+            );
+            util::encode_base64_urlsafe_nopad_buf(header_bytes, &mut message);
+            message.push('.');
+
+            let mut stream = signer.stream_signer()?;
+            stream.update(message.as_bytes())?;
+            let mut buf = vec![0; STREAM_BUFFER_SIZE];
+            loop {
+                let len = util::read_retrying(payload, &mut buf)?;
+                if len == 0 {
+                    break;
+                }
+                stream.update(&buf[..len])?;
+            }
+            let signature = stream.finalize()?;
 
-            Ok(json)
+            message.push('.');
+            util::encode_base64_urlsafe_nopad_buf(signature, &mut message);
+
+            Ok(message)
         })()
         .map_err(|err| match err.downcast::<JoseError>() {
             Ok(err) => err,
@@ -422,14 +526,19 @@ impl JwsContext {
 
             let verifier = match selector(&header)? {
                 Some(val) => val,
-                None => bail!("A verifier is not found."),
+                None => bail!(JoseError::KeyNotFound(
+                    header.key_id().map(|val| val.to_string())
+                )),
             };
 
             match header.claim("alg") {
                 Some(Value::String(val)) => {
                     let expected_alg = verifier.algorithm().name();
                     if val != expected_alg {
-                        bail!("The JWS alg header claim is not {}: {}", expected_alg, val);
+                        bail!(JoseError::AlgorithmMismatch {
+                            expected: expected_alg.to_string(),
+                            actual: val.to_string(),
+                        });
                     }
                 }
                 Some(_) => bail!("The JWS alg header claim must be a string."),
@@ -450,7 +559,7 @@ impl JwsContext {
                 for val in vals {
                     if let Value::String(val2) = val {
                         if !self.is_acceptable_critical(val2) {
-                            bail!("The critical name '{}' is not supported.", val2);
+                            bail!(JoseError::CriticalHeaderUnsupported(val2.to_string()));
                         }
 
                         if val2 == "b64" {
@@ -578,7 +687,7 @@ impl JwsContext {
                         match val {
                             Value::String(name) => {
                                 if !self.is_acceptable_critical(name) {
-                                    bail!("The critical name '{}' is not supported.", name);
+                                    bail!(JoseError::CriticalHeaderUnsupported(name.to_string()));
                                 }
 
                                 if name == "b64" {
@@ -631,7 +740,10 @@ impl JwsContext {
                     Some(Value::String(val)) => {
                         let expected_alg = verifier.algorithm().name();
                         if val != expected_alg {
-                            bail!("The JWS alg header claim is not {}: {}", expected_alg, val);
+                            bail!(JoseError::AlgorithmMismatch {
+                                expected: expected_alg.to_string(),
+                                actual: val.to_string(),
+                            });
                         }
                     }
                     Some(_) => bail!("The JWS alg header claim must be a string."),
@@ -666,4 +778,274 @@ impl JwsContext {
             Err(err) => JoseError::InvalidJwsFormat(err),
         })
     }
+
+    /// Verify the input that is formatted by compact serialization
+    /// with a detached and unencoded payload (RFC 7797).
+    ///
+    /// The payload is read from the reader and verified incrementally.
+    /// However a verifier without incremental support like EdDSA
+    /// buffers the whole payload (see `JwsVerifier::stream_verifier`).
+    /// The b64 header claim is understood by this method,
+    /// so it doesn't need to be registered as an acceptable critical header claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input data.
+    /// * `payload` - The reader of the detached payload data.
+    /// * `verifier` - The JWS verifier.
+    pub fn deserialize_compact_detached_from_reader(
+        &self,
+        input: impl AsRef<[u8]>,
+        payload: &mut dyn Read,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<JwsHeader, JoseError> {
+        self.deserialize_compact_detached_from_reader_with_selector(input, payload, |_header| {
+            Ok(Some(verifier))
+        })
+    }
+
+    /// Verify the input that is formatted by compact serialization
+    /// with a detached and unencoded payload (RFC 7797).
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input data.
+    /// * `payload` - The reader of the detached payload data.
+    /// * `selector` - a function for selecting the verifying algorithm.
+    pub fn deserialize_compact_detached_from_reader_with_selector<'a, F>(
+        &self,
+        input: impl AsRef<[u8]>,
+        payload: &mut dyn Read,
+        selector: F,
+    ) -> Result<JwsHeader, JoseError>
+    where
+        F: Fn(&JwsHeader) -> Result<Option<&'a dyn JwsVerifier>, JoseError>,
+    {
+        (|| -> anyhow::Result<JwsHeader> {
+            let input = input.as_ref();
+            let indexies: Vec<usize> = input
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| **b == b'.')
+                .map(|(pos, _)| pos)
+                .collect();
+            if indexies.len() != 2 {
+                bail!(
+                    "The compact serialization form of JWS must be three parts separated by colon."
+                );
+            }
+            if indexies[1] != indexies[0] + 1 {
+                bail!("The payload part of the detached JWS must be empty.");
+            }
+
+            let header_b64 = &input[0..indexies[0]];
+            let signature = &input[(indexies[1] + 1)..];
+
+            let header = util::decode_base64_urlsafe_no_pad(header_b64)?;
+            let header: Map<String, Value> = serde_json::from_slice(&header)?;
+            let header = JwsHeader::from_map(header)?;
+
+            let verifier = match selector(&header)? {
+                Some(val) => val,
+                None => bail!(JoseError::KeyNotFound(
+                    header.key_id().map(|val| val.to_string())
+                )),
+            };
+
+            match header.claim("alg") {
+                Some(Value::String(val)) => {
+                    let expected_alg = verifier.algorithm().name();
+                    if val != expected_alg {
+                        bail!(JoseError::AlgorithmMismatch {
+                            expected: expected_alg.to_string(),
+                            actual: val.to_string(),
+                        });
+                    }
+                }
+                Some(_) => bail!("The JWS alg header claim must be a string."),
+                None => bail!("The JWS alg header claim is required."),
+            }
+
+            if let Some(expected) = verifier.key_id() {
+                match header.key_id() {
+                    Some(actual) if expected == actual => {}
+                    Some(actual) => bail!("The JWS kid header claim is mismatched: {}", actual),
+                    None => bail!("The JWS kid header claim is required."),
+                }
+            }
+
+            let mut b64 = true;
+            if let Some(Value::Array(vals)) = header.claim("crit") {
+                for val in vals {
+                    if let Value::String(val2) = val {
+                        if val2 == "b64" {
+                            if let Some(val) = header.base64url_encode_payload() {
+                                b64 = val;
+                            }
+                        } else if !self.is_acceptable_critical(val2) {
+                            bail!(JoseError::CriticalHeaderUnsupported(val2.to_string()));
+                        }
+                    }
+                }
+            }
+            if b64 {
+                bail!("The detached JWS payload must be unencoded by the b64 header claim.");
+            }
+
+            let signature = util::decode_base64_urlsafe_no_pad(signature)?;
+
+            let mut stream = verifier.stream_verifier()?;
+            stream.update(&input[..(indexies[0] + 1)])?;
+            let mut buf = vec![0; STREAM_BUFFER_SIZE];
+            loop {
+                let len = util::read_retrying(payload, &mut buf)?;
+                if len == 0 {
+                    break;
+                }
+                stream.update(&buf[..len])?;
+            }
+            stream.finalize(&signature)?;
+
+            Ok(header)
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwsFormat(err),
+        })
+    }
+}
+
+/// Return the signing input of compact serialization: the encoded header
+/// with the alg and kid header claims of the signer, a dot and the payload.
+fn prepare_compact_signing(
+    payload: &[u8],
+    header: &JwsHeader,
+    algorithm: &dyn JwsAlgorithm,
+    key_id: Option<&str>,
+    signature_len: usize,
+) -> anyhow::Result<String> {
+    let mut b64 = true;
+    if let Some(vals) = header.critical() {
+        if vals.contains(&"b64") {
+            if let Some(val) = header.base64url_encode_payload() {
+                b64 = val;
+            }
+        }
+    }
+
+    let mut header = header.claims_set().clone();
+    header.insert(
+        "alg".to_string(),
+        Value::String(algorithm.name().to_string()),
+    );
+    if let Some(key_id) = key_id {
+        header.insert("kid".to_string(), Value::String(key_id.to_string()));
+    }
+    let header_bytes = serde_json::to_vec(&header)?;
+
+    let mut capacity = 2;
+    capacity += util::ceiling(header_bytes.len() * 4, 3);
+    capacity += if b64 {
+        util::ceiling(payload.len() * 4, 3)
+    } else {
+        payload.len()
+    };
+    capacity += util::ceiling(signature_len * 4, 3);
+
+    let mut message = String::with_capacity(capacity);
+    util::encode_base64_urlsafe_nopad_buf(header_bytes, &mut message);
+    message.push_str(".");
+    if b64 {
+        util::encode_base64_urlsafe_nopad_buf(payload, &mut message);
+    } else {
+        let payload = std::str::from_utf8(payload)?;
+        if payload.contains(".") {
+            bail!("A JWS payload cannot contain dot.");
+        }
+        message.push_str(payload);
+    }
+
+    Ok(message)
+}
+
+/// Return the encoded protected header of json serialization
+/// with the alg and kid header claims of the signer.
+fn encode_protected_header(
+    header: &JwsHeaderSet,
+    merged: &JwsHeader,
+    algorithm: &dyn JwsAlgorithm,
+    key_id: Option<&str>,
+) -> anyhow::Result<String> {
+    let mut protected_map = header.claims_set(true).clone();
+
+    match merged.algorithm() {
+        Some(val) if val == algorithm.name() => {}
+        Some(_) => bail!("A signer is unmatched."),
+        None => {
+            protected_map.insert(
+                "alg".to_string(),
+                Value::String(algorithm.name().to_string()),
+            );
+        }
+    }
+
+    if let None = merged.key_id() {
+        if let Some(key_id) = key_id {
+            protected_map.insert("kid".to_string(), Value::String(key_id.to_string()));
+        }
+    }
+
+    let protected_bytes = serde_json::to_vec(&protected_map)?;
+    Ok(util::encode_base64_urlsafe_nopad(&protected_bytes))
+}
+
+/// Return the payload of flattened json serialization
+/// that is encoded unless the b64 header claim is false.
+fn encode_flattened_json_payload(payload: &[u8], header: &JwsHeaderSet) -> anyhow::Result<String> {
+    let protected_map = header.claims_set(true);
+    let mut b64 = true;
+    match protected_map.get("crit") {
+        Some(Value::Array(vals)) => {
+            if vals.iter().any(|val| match val {
+                Value::String(val2) => val2 == "b64",
+                _ => false,
+            }) {
+                b64 = match protected_map.get("b64") {
+                    Some(Value::Bool(val3)) => *val3,
+                    _ => false,
+                };
+            }
+        }
+        _ => {}
+    }
+
+    if b64 {
+        Ok(util::encode_base64_urlsafe_nopad(payload))
+    } else {
+        Ok(std::str::from_utf8(payload)?.to_string())
+    }
+}
+
+fn push_json_headers(
+    json: &mut String,
+    protected_b64: &str,
+    header: &JwsHeaderSet,
+) -> anyhow::Result<()> {
+    json.push_str("{\"protected\":\"");
+    json.push_str(protected_b64);
+    json.push_str("\"");
+
+    let unprotected = header.claims_set(false);
+    if unprotected.len() > 0 {
+        let unprotected_json = serde_json::to_string(unprotected)?;
+        json.push_str(",\"header\":");
+        json.push_str(&unprotected_json);
+    }
+    Ok(())
+}
+
+fn push_json_signature(json: &mut String, signature: &[u8]) {
+    json.push_str(",\"signature\":\"");
+    util::encode_base64_urlsafe_nopad_buf(signature, json);
+    json.push_str("\"}");
 }
@@ -1,17 +1,29 @@
 //! JSON Web Token (JWT) support.
 
 pub mod alg;
+mod confirmation;
+mod jwt_builder;
+mod jwt_claim_type;
 mod jwt_context;
+mod jwt_header_validator;
 mod jwt_payload;
 mod jwt_payload_validator;
+mod replay_guard;
 
+pub use crate::jwt::confirmation::Confirmation;
+pub use crate::jwt::jwt_builder::JwtBuilder;
+pub use crate::jwt::jwt_claim_type::JwtClaimType;
 pub use crate::jwt::jwt_context::JwtContext;
+pub use crate::jwt::jwt_header_validator::JwtHeaderValidator;
 pub use crate::jwt::jwt_payload::JwtPayload;
 pub use crate::jwt::jwt_payload_validator::JwtPayloadValidator;
+pub use crate::jwt::replay_guard::{MemoryReplayGuard, ReplayGuard};
 
 pub use crate::jwt::alg::unsecured::UnsecuredJwsAlgorithm::None;
 
 use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 use crate::jwe::{JweDecrypter, JweEncrypter, JweHeader};
 use crate::jwk::{Jwk, JwkSet};
@@ -45,6 +57,22 @@ pub fn encode_with_signer(
     DEFAULT_CONTEXT.encode_with_signer(payload, header, signer)
 }
 
+/// Return the string repsentation of the JWT with the siginig algorithm
+/// from a serializable claims object.
+///
+/// # Arguments
+///
+/// * `claims` - a claims object.
+/// * `header` - The JWS heaser claims.
+/// * `signer` - a signer object.
+pub fn encode_with_signer_typed<T: Serialize + ?Sized>(
+    claims: &T,
+    header: &JwsHeader,
+    signer: &dyn JwsSigner,
+) -> Result<String, JoseError> {
+    DEFAULT_CONTEXT.encode_with_signer_typed(claims, header, signer)
+}
+
 /// Return the string repsentation of the JWT with the encrypting algorithm.
 ///
 /// # Arguments
@@ -60,6 +88,25 @@ pub fn encode_with_encrypter(
     DEFAULT_CONTEXT.encode_with_encrypter(payload, header, encrypter)
 }
 
+/// Return the string repsentation of the nested JWT that is signed and then encrypted.
+///
+/// # Arguments
+///
+/// * `payload` - The payload data.
+/// * `jws_header` - The JWS heaser claims of the inner JWT.
+/// * `signer` - a signer object.
+/// * `jwe_header` - The JWE heaser claims of the outer JWT.
+/// * `encrypter` - a encrypter object.
+pub fn encode_nested(
+    payload: &JwtPayload,
+    jws_header: &JwsHeader,
+    signer: &dyn JwsSigner,
+    jwe_header: &JweHeader,
+    encrypter: &dyn JweEncrypter,
+) -> Result<String, JoseError> {
+    DEFAULT_CONTEXT.encode_nested(payload, jws_header, signer, jwe_header, encrypter)
+}
+
 /// Return the Jose header decoded from JWT.
 ///
 /// # Arguments
@@ -91,6 +138,19 @@ pub fn decode_with_verifier(
     DEFAULT_CONTEXT.decode_with_verifier(input, verifier)
 }
 
+/// Return the claims object and the header decoded by the selected verifier.
+///
+/// # Arguments
+///
+/// * `input` - a JWT string representation.
+/// * `verifier` - a verifier of the signing algorithm.
+pub fn decode_with_verifier_typed<T: DeserializeOwned>(
+    input: impl AsRef<[u8]>,
+    verifier: &dyn JwsVerifier,
+) -> Result<(T, JwsHeader), JoseError> {
+    DEFAULT_CONTEXT.decode_with_verifier_typed(input, verifier)
+}
+
 /// Return the JWT object decoded with a selected verifying algorithm.
 ///
 /// # Arguments
@@ -154,6 +214,40 @@ where
     DEFAULT_CONTEXT.decode_with_decrypter_selector(input, selector)
 }
 
+/// Return the nested JWT object decoded by the selected decrypter and verifier.
+///
+/// # Arguments
+///
+/// * `input` - a JWT string representation.
+/// * `decrypter` - a decrypter of the decrypting algorithm.
+/// * `verifier` - a verifier of the signing algorithm.
+pub fn decode_nested(
+    input: impl AsRef<[u8]>,
+    decrypter: &dyn JweDecrypter,
+    verifier: &dyn JwsVerifier,
+) -> Result<(JwtPayload, JwsHeader, JweHeader), JoseError> {
+    DEFAULT_CONTEXT.decode_nested(input, decrypter, verifier)
+}
+
+/// Return the nested JWT object decoded with a selected decrypting and verifying algorithm.
+///
+/// # Arguments
+///
+/// * `input` - a JWT string representation.
+/// * `decrypter_selector` - a function for selecting the decrypting algorithm.
+/// * `verifier_selector` - a function for selecting the verifying algorithm.
+pub fn decode_nested_with_selector<'a, 'b, F, G>(
+    input: impl AsRef<[u8]>,
+    decrypter_selector: F,
+    verifier_selector: G,
+) -> Result<(JwtPayload, JwsHeader, JweHeader), JoseError>
+where
+    F: Fn(&JweHeader) -> Result<Option<&'a dyn JweDecrypter>, JoseError>,
+    G: Fn(&JwsHeader) -> Result<Option<&'b dyn JwsVerifier>, JoseError>,
+{
+    DEFAULT_CONTEXT.decode_nested_with_selector(input, decrypter_selector, verifier_selector)
+}
+
 /// Return the JWT object decoded by using a JWK set.
 ///
 /// # Arguments
@@ -179,22 +273,25 @@ mod tests {
     use std::time::{Duration, SystemTime};
 
     use anyhow::Result;
+    use serde::{Deserialize, Serialize};
     use serde_json::json;
 
     #[allow(deprecated)]
     use crate::jwe::{
-        Dir, A128GCMKW, A128KW, A192GCMKW, A192KW, A256GCMKW, A256KW, ECDH_ES, ECDH_ES_A128KW,
-        ECDH_ES_A192KW, ECDH_ES_A256KW, PBES2_HS256_A128KW, PBES2_HS384_A192KW, PBES2_HS512_A256KW,
-        RSA1_5, RSA_OAEP, RSA_OAEP_256,
+        self, Dir, JweHeader, A128GCMKW, A128KW, A192GCMKW, A192KW, A256GCMKW, A256KW, ECDH_ES,
+        ECDH_ES_A128KW, ECDH_ES_A192KW, ECDH_ES_A256KW, PBES2_HS256_A128KW, PBES2_HS384_A192KW,
+        PBES2_HS512_A256KW, RSA1_5, RSA_OAEP, RSA_OAEP_256,
     };
     use crate::jwk::Jwk;
     use crate::jws::{
         EdDSA, JwsHeader, ES256, ES256K, ES384, ES512, HS256, HS384, HS512, PS256, PS384, PS512,
         RS256, RS384, RS512,
     };
-    use crate::jwt::{self, JwtPayload};
+    use crate::jwt::{self, JwtContext, JwtHeaderValidator, JwtPayload};
     use crate::util;
-    use crate::Value;
+    #[cfg(feature = "async")]
+    use crate::util::mock_kms::{assert_send, MockKmsSigner};
+    use crate::{JoseError, Value};
 
     #[test]
     fn test_decode_header() -> Result<()> {
@@ -797,6 +894,227 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_jwt_typed() -> Result<()> {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Claims {
+            sub: String,
+            exp: u64,
+            admin: bool,
+        }
+
+        let private_key = util::random_bytes(32);
+        let signer = HS256.signer_from_bytes(&private_key)?;
+        let verifier = HS256.verifier_from_bytes(&private_key)?;
+
+        let src_claims = Claims {
+            sub: "subject".to_string(),
+            exp: 4102444800,
+            admin: true,
+        };
+        let jwt_string = jwt::encode_with_signer_typed(&src_claims, &JwsHeader::new(), &signer)?;
+
+        let (dst_claims, dst_header) =
+            jwt::decode_with_verifier_typed::<Claims>(&jwt_string, &verifier)?;
+        assert_eq!(src_claims, dst_claims);
+        assert_eq!(dst_header.algorithm(), Some("HS256"));
+
+        #[derive(Serialize)]
+        struct InvalidClaims {
+            exp: String,
+        }
+        let invalid_claims = InvalidClaims {
+            exp: "tomorrow".to_string(),
+        };
+        assert!(
+            jwt::encode_with_signer_typed(&invalid_claims, &JwsHeader::new(), &signer).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_nested() -> Result<()> {
+        let private_key = load_file("pem/RSA_2048bit_private.pem")?;
+        let public_key = load_file("pem/RSA_2048bit_public.pem")?;
+
+        let mut src_jws_header = JwsHeader::new();
+        src_jws_header.set_token_type("JWT");
+        let mut src_jwe_header = JweHeader::new();
+        src_jwe_header.set_content_encryption("A128GCM");
+        let mut src_payload = JwtPayload::new();
+        src_payload.set_subject("subject");
+
+        let signer = RS256.signer_from_pem(&private_key)?;
+        let encrypter = RSA_OAEP.encrypter_from_pem(&public_key)?;
+        let jwt_string = jwt::encode_nested(
+            &src_payload,
+            &src_jws_header,
+            &signer,
+            &src_jwe_header,
+            &encrypter,
+        )?;
+
+        let verifier = RS256.verifier_from_pem(&public_key)?;
+        let decrypter = RSA_OAEP.decrypter_from_pem(&private_key)?;
+        let (dst_payload, dst_jws_header, dst_jwe_header) =
+            jwt::decode_nested(&jwt_string, &decrypter, &verifier)?;
+
+        src_jws_header.set_claim("alg", Some(json!("RS256")))?;
+        assert_eq!(src_jws_header, dst_jws_header);
+        assert_eq!(src_payload, dst_payload);
+        assert_eq!(dst_jwe_header.content_type(), Some("JWT"));
+        assert_eq!(dst_jwe_header.algorithm(), Some("RSA-OAEP"));
+
+        let other_verifier = HS256.verifier_from_bytes(util::random_bytes(32))?;
+        assert!(jwt::decode_nested(&jwt_string, &decrypter, &other_verifier).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_nested_requires_cty() -> Result<()> {
+        let private_key = load_file("pem/RSA_2048bit_private.pem")?;
+        let public_key = load_file("pem/RSA_2048bit_public.pem")?;
+
+        let signer = RS256.signer_from_pem(&private_key)?;
+        let inner = jwt::encode_with_signer(&JwtPayload::new(), &JwsHeader::new(), &signer)?;
+
+        let mut jwe_header = JweHeader::new();
+        jwe_header.set_content_encryption("A128GCM");
+        let encrypter = RSA_OAEP.encrypter_from_pem(&public_key)?;
+        let jwt_string = jwe::serialize_compact(inner.as_bytes(), &jwe_header, &encrypter)?;
+
+        let verifier = RS256.verifier_from_pem(&public_key)?;
+        let decrypter = RSA_OAEP.decrypter_from_pem(&private_key)?;
+        assert!(jwt::decode_nested(&jwt_string, &decrypter, &verifier).is_err());
+
+        jwe_header.set_content_type("JSON");
+        assert!(jwt::encode_nested(
+            &JwtPayload::new(),
+            &JwsHeader::new(),
+            &signer,
+            &jwe_header,
+            &encrypter,
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_with_header_validator() -> Result<()> {
+        let key = util::random_bytes(32);
+        let signer = HS256.signer_from_bytes(&key)?;
+        let verifier = HS256.verifier_from_bytes(&key)?;
+
+        let mut header = JwsHeader::new();
+        header.set_token_type("JWT");
+        header.set_key_id("key-1");
+        let id_token = jwt::encode_with_signer(&JwtPayload::new(), &header, &signer)?;
+        header.set_token_type("at+jwt");
+        let access_token = jwt::encode_with_signer(&JwtPayload::new(), &header, &signer)?;
+
+        let mut validator = JwtHeaderValidator::new();
+        validator.set_token_type("at+jwt");
+        validator.set_algorithms(vec!["HS256"]);
+        validator.set_key_id_required(true);
+
+        let mut context = JwtContext::new();
+        context.set_header_validator(Some(validator));
+        context.decode_with_verifier(&access_token, &verifier)?;
+
+        let selected = std::cell::Cell::new(false);
+        let result = context.decode_with_verifier_selector(&id_token, |_header| {
+            selected.set(true);
+            Ok(Some(&verifier))
+        });
+        assert!(result.is_err());
+        assert!(!selected.get());
+
+        let mut validator = JwtHeaderValidator::new();
+        validator.set_algorithms(vec!["RS256"]);
+        context.set_header_validator(Some(validator));
+        assert!(context
+            .decode_with_verifier(&access_token, &verifier)
+            .is_err());
+
+        context.set_header_validator(None);
+        context.decode_with_verifier(&id_token, &verifier)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_verification_error_kinds() -> Result<()> {
+        let key = util::random_bytes(64);
+        let signer = HS256.signer_from_bytes(&key)?;
+
+        let mut header = JwsHeader::new();
+        header.set_key_id("key-1");
+        header.set_critical(&vec!["exp"]);
+        header.set_claim("exp", Some(json!(0)))?;
+        let jwt_string = jwt::encode_with_signer(&JwtPayload::new(), &header, &signer)?;
+
+        assert!(matches!(
+            jwt::decode_with_verifier_selector(&jwt_string, |_header| Ok(None)),
+            Err(JoseError::KeyNotFound(Some(ref val))) if val == "key-1"
+        ));
+
+        let verifier = HS384.verifier_from_bytes(&key)?;
+        assert!(matches!(
+            jwt::decode_with_verifier(&jwt_string, &verifier),
+            Err(JoseError::AlgorithmMismatch { ref expected, ref actual })
+                if expected == "HS384" && actual == "HS256"
+        ));
+
+        let verifier = HS256.verifier_from_bytes(&key)?;
+        assert!(matches!(
+            jwt::decode_with_verifier(&jwt_string, &verifier),
+            Err(JoseError::CriticalHeaderUnsupported(ref val)) if val == "exp"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_jwt_with_async_signer() -> Result<()> {
+        let private_key = load_file("pem/EC_P-256_private.pem")?;
+        let public_key = load_file("pem/EC_P-256_public.pem")?;
+
+        let kms_signer = MockKmsSigner {
+            key: Box::new(ES256.signer_from_pem(&private_key)?),
+        };
+        let verifier = ES256.verifier_from_pem(&public_key)?;
+
+        let mut src_header = JwsHeader::new();
+        src_header.set_token_type("JWT");
+        let mut src_payload = JwtPayload::new();
+        src_payload.set_subject("test");
+
+        let context = JwtContext::new();
+        let future = context.encode_with_signer_async(&src_payload, &src_header, &kms_signer);
+        assert_send(&future);
+        let jwt_string = pollster::block_on(future)?;
+
+        let (dst_payload, dst_header) = context.decode_with_verifier(&jwt_string, &verifier)?;
+        assert_eq!(dst_header.algorithm(), Some("ES256"));
+        assert_eq!(dst_payload, src_payload);
+
+        let mut src_header = JwsHeader::new();
+        src_header.set_base64url_encode_payload(false);
+        src_header.set_critical(&vec!["b64"]);
+        assert!(pollster::block_on(context.encode_with_signer_async(
+            &src_payload,
+            &src_header,
+            &kms_signer,
+        ))
+        .is_err());
+
+        Ok(())
+    }
+
     fn load_file(path: &str) -> Result<Vec<u8>> {
         let mut pb = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         pb.push("data");
@@ -0,0 +1,293 @@
+use std::convert::Into;
+
+use anyhow::bail;
+
+use crate::jwk::Jwk;
+use crate::util::{self, HashAlgorithm};
+use crate::{JoseError, Map, Value};
+
+/// Represents a confirmation claim (cnf) of a proof-of-possession token (RFC 7800).
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Confirmation {
+    map: Map<String, Value>,
+}
+
+impl Confirmation {
+    /// Return a new empty Confirmation.
+    pub fn new() -> Self {
+        Self { map: Map::new() }
+    }
+
+    /// Return the Confirmation from map.
+    ///
+    /// # Arguments
+    ///
+    /// * `map` - confirmation members.
+    pub fn from_map(map: impl Into<Map<String, Value>>) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            let map: Map<String, Value> = map.into();
+            for (key, value) in &map {
+                match key.as_str() {
+                    "jwk" => match value {
+                        Value::Object(val) => {
+                            Jwk::from_map(val.clone())?;
+                        }
+                        _ => bail!("The confirmation member {} must be a object.", key),
+                    },
+                    "jkt" | "x5t#S256" => match value {
+                        Value::String(val) if util::is_base64_urlsafe_nopad(val) => {}
+                        _ => bail!(
+                            "The confirmation member {} must be a base64url encoded string.",
+                            key
+                        ),
+                    },
+                    "kid" | "jku" => match value {
+                        Value::String(_) => {}
+                        _ => bail!("The confirmation member {} must be a string.", key),
+                    },
+                    _ => {}
+                }
+            }
+            Ok(Self { map })
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+
+    /// Set a public key for JSON web key member (jwk).
+    ///
+    /// # Arguments
+    ///
+    /// * `jwk` - a key of the presenter. Only the public key parameters are set.
+    pub fn set_jwk(&mut self, jwk: &Jwk) -> Result<(), JoseError> {
+        let jwk = jwk.to_public_key()?;
+        self.map
+            .insert("jwk".to_string(), Value::Object(jwk.into()));
+        Ok(())
+    }
+
+    /// Return the value for JSON web key member (jwk).
+    pub fn jwk(&self) -> Option<Jwk> {
+        match self.map.get("jwk") {
+            Some(Value::Object(val)) => Jwk::from_map(val.clone()).ok(),
+            _ => None,
+        }
+    }
+
+    /// Set a value for JWK SHA-256 thumbprint member (jkt) used by DPoP (RFC 9449).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a base64url encoded JWK SHA-256 thumbprint.
+    pub fn set_jwk_thumbprint(&mut self, value: impl Into<String>) {
+        self.map
+            .insert("jkt".to_string(), Value::String(value.into()));
+    }
+
+    /// Return the value for JWK SHA-256 thumbprint member (jkt).
+    pub fn jwk_thumbprint(&self) -> Option<&str> {
+        match self.map.get("jkt") {
+            Some(Value::String(val)) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Set a value for X.509 certificate SHA-256 thumbprint member (x5t#S256)
+    /// used by certificate-bound tokens (RFC 8705).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a SHA-256 digest of the DER encoded certificate.
+    pub fn set_x509_certificate_sha256_thumbprint(&mut self, value: impl AsRef<[u8]>) {
+        self.map.insert(
+            "x5t#S256".to_string(),
+            Value::String(util::encode_base64_urlsafe_nopad(value)),
+        );
+    }
+
+    /// Return the value for X.509 certificate SHA-256 thumbprint member (x5t#S256).
+    pub fn x509_certificate_sha256_thumbprint(&self) -> Option<Vec<u8>> {
+        match self.map.get("x5t#S256") {
+            Some(Value::String(val)) => util::decode_base64_urlsafe_no_pad(val).ok(),
+            _ => None,
+        }
+    }
+
+    /// Set a value for key ID member (kid).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.map
+            .insert("kid".to_string(), Value::String(value.into()));
+    }
+
+    /// Return the value for key ID member (kid).
+    pub fn key_id(&self) -> Option<&str> {
+        match self.map.get("kid") {
+            Some(Value::String(val)) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Return a value for a member of a specified key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of the member
+    pub fn parameter(&self, key: &str) -> Option<&Value> {
+        self.map.get(key)
+    }
+
+    /// Verify that the presented key is the confirmation key.
+    ///
+    /// The key is compared with the jwk member or the jkt member by its
+    /// SHA-256 thumbprint.
+    ///
+    /// # Arguments
+    ///
+    /// * `jwk` - a key that the presenter proved possession of.
+    pub fn verify_jwk(&self, jwk: &Jwk) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            let thumbprint = jwk.thumbprint(HashAlgorithm::Sha256)?;
+
+            let mut verified = false;
+            if let Some(expected) = self.jwk() {
+                if expected.thumbprint(HashAlgorithm::Sha256)? != thumbprint {
+                    bail!("The key does not match the confirmation jwk.");
+                }
+                verified = true;
+            }
+            if let Some(expected) = self.jwk_thumbprint() {
+                if expected != thumbprint {
+                    bail!(
+                        "The key does not match the confirmation jkt: {}",
+                        thumbprint
+                    );
+                }
+                verified = true;
+            }
+            if !verified {
+                bail!("The confirmation does not contain a key.");
+            }
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+
+    /// Verify that the client certificate of the mutual TLS connection
+    /// is the confirmation certificate (RFC 8705 section 3.1).
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate` - a DER encoded client certificate.
+    pub fn verify_certificate(&self, certificate: impl AsRef<[u8]>) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            let expected = match self.x509_certificate_sha256_thumbprint() {
+                Some(val) => val,
+                None => bail!("The confirmation does not contain a x5t#S256 member."),
+            };
+            let actual =
+                openssl::hash::hash(HashAlgorithm::Sha256.message_digest(), certificate.as_ref())?;
+            if expected.as_slice() != &*actual {
+                bail!("The client certificate does not match the confirmation x5t#S256.");
+            }
+            Ok(())
+        })()
+        .map_err(JoseError::InvalidClaim)
+    }
+
+    /// Verify that the key ID is the confirmation key ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - a key ID that the presenter proved possession of.
+    pub fn verify_key_id(&self, key_id: &str) -> Result<(), JoseError> {
+        match self.key_id() {
+            Some(val) if val == key_id => Ok(()),
+            Some(val) => Err(JoseError::InvalidClaim(anyhow::anyhow!(
+                "The key ID does not match the confirmation kid: {}",
+                val
+            ))),
+            None => Err(JoseError::InvalidClaim(anyhow::anyhow!(
+                "The confirmation does not contain a kid member."
+            ))),
+        }
+    }
+}
+
+impl AsRef<Map<String, Value>> for Confirmation {
+    fn as_ref(&self) -> &Map<String, Value> {
+        &self.map
+    }
+}
+
+impl From<Confirmation> for Map<String, Value> {
+    fn from(value: Confirmation) -> Self {
+        value.map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::jwk::alg::ec::EcCurve;
+    use crate::jwk::Jwk;
+    use crate::jwt::{Confirmation, JwtPayload};
+    use crate::util::{self, SHA_256};
+
+    #[test]
+    fn test_confirmation() -> Result<()> {
+        let jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let other_jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+
+        let mut cnf = Confirmation::new();
+        cnf.set_jwk(&jwk)?;
+        assert_eq!(cnf.jwk(), Some(jwk.to_public_key()?));
+        cnf.verify_jwk(&jwk)?;
+        cnf.verify_jwk(&jwk.to_public_key()?)?;
+        assert!(cnf.verify_jwk(&other_jwk).is_err());
+
+        let mut cnf = Confirmation::new();
+        cnf.set_jwk_thumbprint(jwk.thumbprint(SHA_256)?);
+        cnf.set_key_id("key-1");
+        cnf.verify_jwk(&jwk)?;
+        assert!(cnf.verify_jwk(&other_jwk).is_err());
+        cnf.verify_key_id("key-1")?;
+        assert!(cnf.verify_key_id("key-2").is_err());
+
+        let mut payload = JwtPayload::new();
+        payload.set_confirmation(cnf.clone());
+        assert_eq!(payload.confirmation(), Some(cnf));
+        assert!(payload
+            .set_claim("cnf", Some(serde_json::json!("jkt")))
+            .is_err());
+        assert!(payload
+            .set_claim("cnf", Some(serde_json::json!({"jkt": 1})))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_confirmation_with_certificate() -> Result<()> {
+        // The thumbprint is computed over the DER bytes as they are.
+        let der = util::random_bytes(512);
+        let other_der = util::random_bytes(512);
+
+        let mut cnf = Confirmation::new();
+        assert!(cnf.verify_certificate(&der).is_err());
+        cnf.set_x509_certificate_sha256_thumbprint(openssl::sha::sha256(&der));
+        cnf.verify_certificate(&der)?;
+        assert!(cnf.verify_certificate(&other_der).is_err());
+
+        Ok(())
+    }
+}
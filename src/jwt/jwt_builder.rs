@@ -0,0 +1,420 @@
+use std::convert::Into;
+use std::time::{Duration, SystemTime};
+
+use anyhow::bail;
+
+use crate::jwe::{JweEncrypter, JweHeader};
+use crate::jws::{JwsHeader, JwsSigner};
+use crate::jwt::{JwtContext, JwtPayload};
+use crate::util;
+use crate::{JoseError, Map, Value};
+
+/// Represents JWT builder that fills the registered claims.
+///
+/// The iat claim is set to the current time, and the exp claim is
+/// required by default. The setters can be chained.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct JwtBuilder {
+    context: JwtContext,
+    payload: JwtPayload,
+    header_claims: Map<String, Value>,
+    issued_at: Option<SystemTime>,
+    issued_at_auto: bool,
+    expires_in: Option<Duration>,
+    expiration_required: bool,
+    jwt_id_generated: bool,
+    content_encryption: String,
+}
+
+impl JwtBuilder {
+    /// Return a new JwtBuilder.
+    pub fn new() -> Self {
+        Self {
+            context: JwtContext::new(),
+            payload: JwtPayload::new(),
+            header_claims: Map::new(),
+            issued_at: None,
+            issued_at_auto: true,
+            expires_in: None,
+            expiration_required: true,
+            jwt_id_generated: false,
+            content_encryption: "A128CBC-HS256".to_string(),
+        }
+    }
+
+    /// Set a value for issuer payload claim (iss).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issuer
+    pub fn set_issuer(&mut self, value: impl Into<String>) -> &mut Self {
+        self.payload.set_issuer(value);
+        self
+    }
+
+    /// Set a value for subject payload claim (sub).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a subject
+    pub fn set_subject(&mut self, value: impl Into<String>) -> &mut Self {
+        self.payload.set_subject(value);
+        self
+    }
+
+    /// Set values for audience payload claim (aud).
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - a list of audiences
+    pub fn set_audience(&mut self, values: Vec<impl Into<String>>) -> &mut Self {
+        self.payload.set_audience(values);
+        self
+    }
+
+    /// Set a value for issued at payload claim (iat).
+    /// The default value is the current time when the JWT is built.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issued time
+    pub fn set_issued_at(&mut self, value: SystemTime) -> &mut Self {
+        self.issued_at = Some(value);
+        self
+    }
+
+    /// Set whether the iat claim is filled automatically. The default value is true.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - false if the iat claim is not needed.
+    pub fn set_issued_at_auto(&mut self, value: bool) -> &mut Self {
+        self.issued_at_auto = value;
+        self
+    }
+
+    /// Set a time to live to decide expiration time payload claim (exp) from the issued time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a lifetime of the JWT.
+    pub fn set_expires_in(&mut self, value: Duration) -> &mut Self {
+        self.expires_in = Some(value);
+        self
+    }
+
+    /// Set a value for expiration time payload claim (exp).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a expiration time
+    pub fn set_expires_at(&mut self, value: SystemTime) -> &mut Self {
+        self.expires_in = None;
+        self.payload.set_expires_at(&value);
+        self
+    }
+
+    /// Set whether the exp claim is required to build. The default value is true.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - false if the JWT may be issued without expiration.
+    pub fn set_expiration_required(&mut self, value: bool) -> &mut Self {
+        self.expiration_required = value;
+        self
+    }
+
+    /// Set a value for not before payload claim (nbf).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a time before which the JWT must not be accepted.
+    pub fn set_not_before(&mut self, value: SystemTime) -> &mut Self {
+        self.payload.set_not_before(&value);
+        self
+    }
+
+    /// Set a value for JWT ID payload claim (jti).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a JWT ID
+    pub fn set_jwt_id(&mut self, value: impl Into<String>) -> &mut Self {
+        self.jwt_id_generated = false;
+        self.payload.set_jwt_id(value);
+        self
+    }
+
+    /// Generate a random JWT ID for the jti claim whenever the JWT is built.
+    pub fn set_random_jwt_id(&mut self) -> &mut Self {
+        self.jwt_id_generated = true;
+        self
+    }
+
+    /// Set a value for a other payload claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of payload claim
+    /// * `value` - a typed value of payload claim
+    pub fn set_claim(&mut self, key: &str, value: Option<Value>) -> Result<&mut Self, JoseError> {
+        self.payload.set_claim(key, value)?;
+        Ok(self)
+    }
+
+    /// Set a value for token type header claim (typ).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a token type like "JWT" or "at+jwt".
+    pub fn set_token_type(&mut self, value: impl Into<String>) -> &mut Self {
+        self.header_claims
+            .insert("typ".to_string(), Value::String(value.into()));
+        self
+    }
+
+    /// Set a value for key ID header claim (kid).
+    /// The key ID of the signer or the encrypter is used if it is not set.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID
+    pub fn set_key_id(&mut self, value: impl Into<String>) -> &mut Self {
+        self.header_claims
+            .insert("kid".to_string(), Value::String(value.into()));
+        self
+    }
+
+    /// Set a value for content encryption header claim (enc) of JWE.
+    /// The default value is "A128CBC-HS256".
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a content encryption algorithm name.
+    pub fn set_content_encryption(&mut self, value: impl Into<String>) -> &mut Self {
+        self.content_encryption = value.into();
+        self
+    }
+
+    /// Set a value for a other header claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of header claim
+    /// * `value` - a typed value of header claim
+    pub fn set_header_claim(
+        &mut self,
+        key: &str,
+        value: Option<Value>,
+    ) -> Result<&mut Self, JoseError> {
+        match key {
+            "alg" | "enc" => {
+                return Err(JoseError::InvalidJwtFormat(anyhow::anyhow!(
+                    "The {} header claim is decided by the algorithm.",
+                    key
+                )))
+            }
+            _ => {}
+        }
+        match value {
+            Some(val) => self.header_claims.insert(key.to_string(), val),
+            None => self.header_claims.remove(key),
+        };
+        Ok(self)
+    }
+
+    /// Return the payload that the registered claims are filled in.
+    pub fn build_payload(&self) -> Result<JwtPayload, JoseError> {
+        (|| -> anyhow::Result<JwtPayload> {
+            let mut payload = self.payload.clone();
+
+            let issued_at = match self.issued_at {
+                Some(val) => val,
+                None => SystemTime::now(),
+            };
+            if self.issued_at.is_some() || self.issued_at_auto {
+                payload.set_issued_at(&issued_at);
+            }
+            if let Some(val) = self.expires_in {
+                match issued_at.checked_add(val) {
+                    Some(expires_at) => payload.set_expires_at(&expires_at),
+                    None => bail!("The expiration time is out of range."),
+                }
+            }
+            if self.expiration_required && payload.expires_at().is_none() {
+                bail!(JoseError::MissingClaim("exp".to_string()));
+            }
+            if self.jwt_id_generated {
+                payload.set_jwt_id(util::encode_base64_urlsafe_nopad(util::random_bytes(16)));
+            }
+
+            Ok(payload)
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+
+    /// Return the payload and the JWS header for the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    pub fn build_jws(&self, signer: &dyn JwsSigner) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        let payload = self.build_payload()?;
+        let mut header = JwsHeader::from_map(self.header_claims.clone())?;
+        header.set_algorithm(signer.algorithm().name());
+        if let (None, Some(val)) = (header.key_id(), signer.key_id()) {
+            header.set_key_id(val);
+        }
+        Ok((payload, header))
+    }
+
+    /// Return the payload and the JWE header for the encrypter.
+    ///
+    /// # Arguments
+    ///
+    /// * `encrypter` - a encrypter object.
+    pub fn build_jwe(
+        &self,
+        encrypter: &dyn JweEncrypter,
+    ) -> Result<(JwtPayload, JweHeader), JoseError> {
+        let payload = self.build_payload()?;
+        let mut header = JweHeader::from_map(self.header_claims.clone())?;
+        header.set_algorithm(encrypter.algorithm().name());
+        header.set_content_encryption(&self.content_encryption);
+        if let (None, Some(val)) = (header.key_id(), encrypter.key_id()) {
+            header.set_key_id(val);
+        }
+        Ok((payload, header))
+    }
+
+    /// Return the string repsentation of the JWT signed by the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    pub fn sign(&self, signer: &dyn JwsSigner) -> Result<String, JoseError> {
+        let (payload, header) = self.build_jws(signer)?;
+        self.context.encode_with_signer(&payload, &header, signer)
+    }
+
+    /// Return the string repsentation of the JWT encrypted by the encrypter.
+    ///
+    /// # Arguments
+    ///
+    /// * `encrypter` - a encrypter object.
+    pub fn encrypt(&self, encrypter: &dyn JweEncrypter) -> Result<String, JoseError> {
+        let (payload, header) = self.build_jwe(encrypter)?;
+        self.context
+            .encode_with_encrypter(&payload, &header, encrypter)
+    }
+}
+
+impl Default for JwtBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::jwe::Dir;
+    use crate::jws::HS256;
+    use crate::jwt::{self, JwtBuilder};
+    use crate::util;
+    use crate::JoseError;
+
+    #[test]
+    fn test_jwt_builder_sign() -> Result<()> {
+        let key = util::random_bytes(32);
+        let signer = HS256.signer_from_bytes(&key)?;
+        let verifier = HS256.verifier_from_bytes(&key)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let jwt = JwtBuilder::new()
+            .set_issuer("https://issuer.example.com")
+            .set_subject("user")
+            .set_audience(vec!["client"])
+            .set_issued_at(now)
+            .set_expires_in(Duration::from_secs(600))
+            .set_not_before(now)
+            .set_random_jwt_id()
+            .set_token_type("JWT")
+            .set_key_id("key-1")
+            .sign(&signer)?;
+
+        let (payload, header) = jwt::decode_with_verifier(&jwt, &verifier)?;
+        assert_eq!(payload.issuer(), Some("https://issuer.example.com"));
+        assert_eq!(payload.issued_at(), Some(now));
+        assert_eq!(payload.expires_at(), Some(now + Duration::from_secs(600)));
+        assert_eq!(payload.not_before(), Some(now));
+        assert_eq!(payload.jwt_id().map(|val| val.len()), Some(22));
+        assert_eq!(header.algorithm(), Some("HS256"));
+        assert_eq!(header.token_type(), Some("JWT"));
+        assert_eq!(header.key_id(), Some("key-1"));
+
+        let mut builder = JwtBuilder::new();
+        builder.set_subject("user");
+        assert!(builder.sign(&signer).is_err());
+        builder
+            .set_expiration_required(false)
+            .set_issued_at_auto(false);
+        let payload = builder.build_payload()?;
+        assert!(payload.issued_at().is_none());
+        assert!(payload.expires_at().is_none());
+
+        builder.set_claim("scope", Some(json!("read")))?;
+        assert!(builder
+            .set_header_claim("alg", Some(json!("none")))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_builder_with_out_of_range_expiration() -> Result<()> {
+        let key = util::random_bytes(32);
+        let signer = HS256.signer_from_bytes(&key)?;
+
+        let mut builder = JwtBuilder::new();
+        builder.set_subject("user").set_expires_in(Duration::MAX);
+        assert!(matches!(
+            builder.build_payload(),
+            Err(JoseError::InvalidClaim(_))
+        ));
+        assert!(matches!(
+            builder.sign(&signer),
+            Err(JoseError::InvalidClaim(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_builder_encrypt() -> Result<()> {
+        let key = util::random_bytes(32);
+        let encrypter = Dir.encrypter_from_bytes(&key)?;
+        let decrypter = Dir.decrypter_from_bytes(&key)?;
+
+        let jwt = JwtBuilder::new()
+            .set_subject("user")
+            .set_expires_in(Duration::from_secs(600))
+            .set_content_encryption("A256GCM")
+            .encrypt(&encrypter)?;
+
+        let (payload, header) = jwt::decode_with_decrypter(&jwt, &decrypter)?;
+        assert_eq!(payload.subject(), Some("user"));
+        assert!(payload.issued_at().is_some());
+        assert_eq!(header.algorithm(), Some("dir"));
+        assert_eq!(header.content_encryption(), Some("A256GCM"));
+
+        Ok(())
+    }
+}
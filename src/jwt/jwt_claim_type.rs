@@ -0,0 +1,50 @@
+use std::fmt::Display;
+
+use crate::Value;
+
+/// Represents a JSON type of a JWT payload claim.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum JwtClaimType {
+    String,
+    Number,
+    /// A number that is a integer within 64bit.
+    Integer,
+    Boolean,
+    Array,
+    Object,
+}
+
+impl JwtClaimType {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Integer => "integer",
+            Self::Boolean => "boolean",
+            Self::Array => "array",
+            Self::Object => "object",
+        }
+    }
+
+    /// Test a value is this type.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a value of payload claim
+    pub fn is_instance(&self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Number => value.is_number(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Boolean => value.is_boolean(),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+        }
+    }
+}
+
+impl Display for JwtClaimType {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        fmt.write_str(self.name())
+    }
+}
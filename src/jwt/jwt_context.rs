@@ -1,9 +1,13 @@
 use anyhow::bail;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 use crate::jwe::{JweContext, JweDecrypter, JweEncrypter, JweHeader};
 use crate::jwk::{Jwk, JwkSet};
+#[cfg(feature = "async")]
+use crate::jws::AsyncJwsSigner;
 use crate::jws::{JwsContext, JwsHeader, JwsSigner, JwsVerifier};
-use crate::jwt::{self, JwtPayload};
+use crate::jwt::{self, JwtHeaderValidator, JwtPayload};
 use crate::util;
 use crate::{JoseError, JoseHeader, Map, Value};
 
@@ -11,6 +15,7 @@ use crate::{JoseError, JoseHeader, Map, Value};
 pub struct JwtContext {
     jws_context: JwsContext,
     jwe_context: JweContext,
+    header_validator: Option<JwtHeaderValidator>,
 }
 
 impl JwtContext {
@@ -18,9 +23,26 @@ impl JwtContext {
         Self {
             jws_context: JwsContext::new(),
             jwe_context: JweContext::new(),
+            header_validator: None,
         }
     }
 
+    /// Set a header validator that is applied to the JWT header before selecting a key.
+    ///
+    /// In the case of a nested JWT, it is applied to the header of the inner JWS.
+    ///
+    /// # Arguments
+    ///
+    /// * `validator` - a header validator or None
+    pub fn set_header_validator(&mut self, validator: Option<JwtHeaderValidator>) {
+        self.header_validator = validator;
+    }
+
+    /// Return the header validator that is applied to the JWT header.
+    pub fn header_validator(&self) -> Option<&JwtHeaderValidator> {
+        self.header_validator.as_ref()
+    }
+
     /// Test a critical header claim name is acceptable.
     ///
     /// # Arguments
@@ -78,13 +100,7 @@ impl JwtContext {
         signer: &dyn JwsSigner,
     ) -> Result<String, JoseError> {
         (|| -> anyhow::Result<String> {
-            if let Some(vals) = header.critical() {
-                if vals.contains(&"b64") {
-                    bail!("JWT is not support b64 header claim.");
-                }
-            }
-
-            let payload_bytes = serde_json::to_vec(payload.claims_set()).unwrap();
+            let payload_bytes = signing_payload(payload, header)?;
             let jwt = self
                 .jws_context
                 .serialize_compact(&payload_bytes, header, signer)?;
@@ -96,6 +112,53 @@ impl JwtContext {
         })
     }
 
+    /// Return the string repsentation of the JWT with a asynchronous signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload data.
+    /// * `header` - The JWS heaser claims.
+    /// * `signer` - a asynchronous signer object.
+    #[cfg(feature = "async")]
+    pub async fn encode_with_signer_async(
+        &self,
+        payload: &JwtPayload,
+        header: &JwsHeader,
+        signer: &dyn AsyncJwsSigner,
+    ) -> Result<String, JoseError> {
+        async {
+            let payload_bytes = signing_payload(payload, header)?;
+            let jwt = self
+                .jws_context
+                .serialize_compact_async(&payload_bytes, header, signer)
+                .await?;
+            Ok(jwt)
+        }
+        .await
+        .map_err(|err: anyhow::Error| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })
+    }
+
+    /// Return the string repsentation of the JWT with the siginig algorithm
+    /// from a serializable claims object.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - a claims object.
+    /// * `header` - The JWS heaser claims.
+    /// * `signer` - a signer object.
+    pub fn encode_with_signer_typed<T: Serialize + ?Sized>(
+        &self,
+        claims: &T,
+        header: &JwsHeader,
+        signer: &dyn JwsSigner,
+    ) -> Result<String, JoseError> {
+        let payload = JwtPayload::from_claims(claims)?;
+        self.encode_with_signer(&payload, header, signer)
+    }
+
     /// Return the string repsentation of the JWT with the encrypting algorithm.
     ///
     /// # Arguments
@@ -116,6 +179,46 @@ impl JwtContext {
         Ok(jwt)
     }
 
+    /// Return the string repsentation of the nested JWT that is signed and then encrypted.
+    ///
+    /// The cty header claim of the JWE is set to "JWT".
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload data.
+    /// * `jws_header` - The JWS heaser claims of the inner JWT.
+    /// * `signer` - a signer object.
+    /// * `jwe_header` - The JWE heaser claims of the outer JWT.
+    /// * `encrypter` - a encrypter object.
+    pub fn encode_nested(
+        &self,
+        payload: &JwtPayload,
+        jws_header: &JwsHeader,
+        signer: &dyn JwsSigner,
+        jwe_header: &JweHeader,
+        encrypter: &dyn JweEncrypter,
+    ) -> Result<String, JoseError> {
+        (|| -> anyhow::Result<String> {
+            let inner = self.encode_with_signer(payload, jws_header, signer)?;
+
+            let mut jwe_header = jwe_header.clone();
+            match jwe_header.content_type() {
+                Some(val) if val.eq_ignore_ascii_case("JWT") => {}
+                Some(val) => bail!("The cty header claim of a nested JWT must be JWT: {}", val),
+                None => jwe_header.set_content_type("JWT"),
+            }
+
+            let jwt =
+                self.jwe_context
+                    .serialize_compact(inner.as_bytes(), &jwe_header, encrypter)?;
+            Ok(jwt)
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })
+    }
+
     /// Return the Jose header decoded from JWT.
     ///
     /// # Arguments
@@ -173,6 +276,21 @@ impl JwtContext {
         self.decode_with_verifier_selector(input, |_header| Ok(Some(verifier)))
     }
 
+    /// Return the claims object and the header decoded by the selected verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a JWT string representation.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_with_verifier_typed<T: DeserializeOwned>(
+        &self,
+        input: impl AsRef<[u8]>,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<(T, JwsHeader), JoseError> {
+        let (payload, header) = self.decode_with_verifier(input, verifier)?;
+        Ok((payload.to_claims()?, header))
+    }
+
     /// Return the JWT object decoded with a selected verifying algorithm.
     ///
     /// # Arguments
@@ -192,6 +310,10 @@ impl JwtContext {
                 self.jws_context
                     .deserialize_compact_with_selector(input, |header| {
                         (|| -> anyhow::Result<Option<&'a dyn JwsVerifier>> {
+                            if let Some(validator) = &self.header_validator {
+                                validator.validate(header)?;
+                            }
+
                             let verifier = match selector(&header)? {
                                 Some(val) => val,
                                 None => return Ok(None),
@@ -285,6 +407,10 @@ impl JwtContext {
             let (payload, header) =
                 self.jwe_context
                     .deserialize_compact_with_selector(input, |header| {
+                        if let Some(validator) = &self.header_validator {
+                            validator.validate(header)?;
+                        }
+
                         let decrypter = match selector(&header)? {
                             Some(val) => val,
                             None => return Ok(None),
@@ -304,6 +430,68 @@ impl JwtContext {
         })
     }
 
+    /// Return the nested JWT object decoded by the selected decrypter and verifier.
+    ///
+    /// The outer JWE must have the cty header claim "JWT",
+    /// and the signature of the inner JWS is always verified.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a JWT string representation.
+    /// * `decrypter` - a decrypter of the decrypting algorithm.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_nested(
+        &self,
+        input: impl AsRef<[u8]>,
+        decrypter: &dyn JweDecrypter,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<(JwtPayload, JwsHeader, JweHeader), JoseError> {
+        self.decode_nested_with_selector(
+            input,
+            |_header| Ok(Some(decrypter)),
+            |_header| Ok(Some(verifier)),
+        )
+    }
+
+    /// Return the nested JWT object decoded with a selected decrypting and verifying algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a JWT string representation.
+    /// * `decrypter_selector` - a function for selecting the decrypting algorithm.
+    /// * `verifier_selector` - a function for selecting the verifying algorithm.
+    pub fn decode_nested_with_selector<'a, 'b, F, G>(
+        &self,
+        input: impl AsRef<[u8]>,
+        decrypter_selector: F,
+        verifier_selector: G,
+    ) -> Result<(JwtPayload, JwsHeader, JweHeader), JoseError>
+    where
+        F: Fn(&JweHeader) -> Result<Option<&'a dyn JweDecrypter>, JoseError>,
+        G: Fn(&JwsHeader) -> Result<Option<&'b dyn JwsVerifier>, JoseError>,
+    {
+        (|| -> anyhow::Result<(JwtPayload, JwsHeader, JweHeader)> {
+            let (inner, jwe_header) = self
+                .jwe_context
+                .deserialize_compact_with_selector(input, decrypter_selector)?;
+
+            match jwe_header.content_type() {
+                Some(val) if val.eq_ignore_ascii_case("JWT") => {}
+                Some(val) => bail!("The cty header claim of a nested JWT must be JWT: {}", val),
+                None => bail!("The cty header claim is required for a nested JWT."),
+            }
+
+            let (payload, jws_header) =
+                self.decode_with_verifier_selector(&inner, verifier_selector)?;
+
+            Ok((payload, jws_header, jwe_header))
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })
+    }
+
     /// Return the JWT object decoded by using a JWK set.
     ///
     /// # Arguments
@@ -335,3 +523,14 @@ impl JwtContext {
         })
     }
 }
+
+/// Return the JWS payload of the JWT.
+fn signing_payload(payload: &JwtPayload, header: &JwsHeader) -> anyhow::Result<Vec<u8>> {
+    if let Some(vals) = header.critical() {
+        if vals.contains(&"b64") {
+            bail!("JWT is not support b64 header claim.");
+        }
+    }
+
+    Ok(serde_json::to_vec(payload.claims_set()).unwrap())
+}
@@ -0,0 +1,232 @@
+use std::convert::Into;
+
+use anyhow::bail;
+
+use crate::{JoseError, JoseHeader};
+
+/// Represents JWT header validator.
+///
+/// The validator is applied to the header before selecting a key,
+/// so a JWT of other token type or algorithm is rejected as early as possible.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct JwtHeaderValidator {
+    token_type: Option<String>,
+    algorithms: Option<Vec<String>>,
+    key_id_required: bool,
+    forbidden_headers: Vec<String>,
+}
+
+impl JwtHeaderValidator {
+    /// Return a new JwtHeaderValidator.
+    pub fn new() -> Self {
+        Self {
+            token_type: None,
+            algorithms: None,
+            key_id_required: false,
+            forbidden_headers: Vec::new(),
+        }
+    }
+
+    /// Set a value for token type header claim (typ) validation.
+    ///
+    /// The typ header claim is required. It is compared case-insensitively
+    /// and the "application/" prefix can be omitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a expected token type like "at+jwt"
+    pub fn set_token_type(&mut self, value: impl Into<String>) {
+        self.token_type = Some(value.into());
+    }
+
+    /// Return the value for token type header claim (typ) validation.
+    pub fn token_type(&self) -> Option<&str> {
+        self.token_type.as_deref()
+    }
+
+    /// Set acceptable values for algorithm header claim (alg) validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - acceptable algorithm names
+    pub fn set_algorithms(&mut self, values: Vec<impl Into<String>>) {
+        let values: Vec<String> = values.into_iter().map(|val| val.into()).collect();
+        self.algorithms = Some(values);
+    }
+
+    /// Return the acceptable values for algorithm header claim (alg) validation.
+    pub fn algorithms(&self) -> Option<Vec<&str>> {
+        self.algorithms
+            .as_ref()
+            .map(|vals| vals.iter().map(|val| val.as_str()).collect())
+    }
+
+    /// Set whether the key ID header claim (kid) is required.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - true if the kid header claim is required.
+    pub fn set_key_id_required(&mut self, value: bool) {
+        self.key_id_required = value;
+    }
+
+    /// Return whether the key ID header claim (kid) is required.
+    pub fn is_key_id_required(&self) -> bool {
+        self.key_id_required
+    }
+
+    /// Add a header claim name that must not be present.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a forbidden header claim name like "jku" or "x5u"
+    pub fn add_forbidden_header(&mut self, key: &str) {
+        if !self.forbidden_headers.iter().any(|val| val == key) {
+            self.forbidden_headers.push(key.to_string());
+        }
+    }
+
+    /// Return the header claim names that must not be present.
+    pub fn forbidden_headers(&self) -> Vec<&str> {
+        self.forbidden_headers
+            .iter()
+            .map(|val| val.as_str())
+            .collect()
+    }
+
+    /// Validate a decoded JWT header.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - a decoded JWS or JWE header.
+    pub fn validate(&self, header: &dyn JoseHeader) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            if let Some(expected) = &self.token_type {
+                match header.claim("typ") {
+                    Some(serde_json::Value::String(val)) => {
+                        if !Self::is_same_media_type(expected, val) {
+                            bail!("Header typ is invalid: {}", val);
+                        }
+                    }
+                    Some(val) => bail!("Header typ must be a string: {}", val),
+                    None => bail!("Header typ is missing."),
+                }
+            }
+
+            if let Some(algorithms) = &self.algorithms {
+                match header.claim("alg") {
+                    Some(serde_json::Value::String(val)) => {
+                        if !algorithms.iter().any(|alg| alg == val) {
+                            bail!(JoseError::AlgorithmMismatch {
+                                expected: algorithms.join(" or "),
+                                actual: val.to_string(),
+                            });
+                        }
+                    }
+                    Some(val) => bail!("Header alg must be a string: {}", val),
+                    None => bail!("Header alg is missing."),
+                }
+            }
+
+            if self.key_id_required && header.claim("kid").is_none() {
+                bail!("Header kid is missing.");
+            }
+
+            for key in &self.forbidden_headers {
+                if header.claim(key).is_some() {
+                    bail!("Header {} is forbidden.", key);
+                }
+            }
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })
+    }
+
+    fn is_same_media_type(expected: &str, actual: &str) -> bool {
+        fn strip_prefix(val: &str) -> &str {
+            match (val.get(..12), val.get(12..)) {
+                (Some(prefix), Some(rest))
+                    if !rest.is_empty() && prefix.eq_ignore_ascii_case("application/") =>
+                {
+                    rest
+                }
+                _ => val,
+            }
+        }
+
+        strip_prefix(expected).eq_ignore_ascii_case(strip_prefix(actual))
+    }
+}
+
+impl Default for JwtHeaderValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::jws::JwsHeader;
+    use crate::jwt::JwtHeaderValidator;
+    use crate::JoseError;
+
+    #[test]
+    fn test_jwt_header_validate() -> Result<()> {
+        let mut header = JwsHeader::new();
+        header.set_algorithm("RS256");
+        header.set_token_type("application/AT+JWT");
+        header.set_key_id("key-1");
+
+        let mut validator = JwtHeaderValidator::new();
+        validator.validate(&header)?;
+
+        validator.set_token_type("at+jwt");
+        validator.set_algorithms(vec!["RS256", "ES256"]);
+        validator.set_key_id_required(true);
+        validator.add_forbidden_header("jku");
+        validator.validate(&header)?;
+
+        validator.set_token_type("JWT");
+        assert!(validator.validate(&header).is_err());
+        validator.set_token_type("at+jwt");
+
+        validator.set_algorithms(vec!["ES256", "PS256"]);
+        assert!(matches!(
+            validator.validate(&header),
+            Err(JoseError::AlgorithmMismatch { ref expected, ref actual })
+                if expected == "ES256 or PS256" && actual == "RS256"
+        ));
+        validator.set_algorithms(vec!["RS256"]);
+
+        header.set_jwk_set_url("https://example.com/jwks");
+        assert!(validator.validate(&header).is_err());
+
+        let mut header = JwsHeader::new();
+        header.set_algorithm("RS256");
+        header.set_token_type("at+jwt");
+        assert!(validator.validate(&header).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_header_validate_multibyte_token_type() -> Result<()> {
+        let mut validator = JwtHeaderValidator::new();
+        validator.set_token_type("at+jwt");
+
+        for typ in ["aéééééé", "applicationé/at+jwt", "éééééééé"] {
+            let mut header = JwsHeader::new();
+            header.set_algorithm("RS256");
+            header.set_token_type(typ);
+            assert!(validator.validate(&header).is_err());
+        }
+
+        Ok(())
+    }
+}
@@ -2,8 +2,11 @@ use std::convert::Into;
 use std::fmt::Display;
 use std::time::{Duration, SystemTime};
 
+use crate::jwt::Confirmation;
 use crate::{JoseError, Map, Number, Value};
-use anyhow::bail;
+use anyhow::{anyhow, bail};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 #[derive(Debug, Eq, PartialEq, Clone, Default)]
 pub struct JwtPayload {
@@ -30,6 +33,39 @@ impl JwtPayload {
         Ok(Self { claims: map })
     }
 
+    /// Return the JWT payload from a serializable claims object.
+    ///
+    /// The claims object must be serialized as a JSON object,
+    /// and the registered claims are checked in the same way as `from_map`.
+    /// Top-level claims serialized as null (e.g. a `None` field) are omitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - a claims object.
+    pub fn from_claims<T: Serialize + ?Sized>(claims: &T) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            let map = match serde_json::to_value(claims)? {
+                Value::Object(mut val) => {
+                    val.retain(|_, value| !value.is_null());
+                    val
+                }
+                _ => bail!("The JWT claims must be serialized as a JSON object."),
+            };
+            Ok(Self::from_map(map)?)
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })
+    }
+
+    /// Return a claims object deserialized from the JWT payload.
+    pub fn to_claims<T: DeserializeOwned>(&self) -> Result<T, JoseError> {
+        serde_json::from_value(Value::Object(self.claims.clone())).map_err(|err| {
+            JoseError::InvalidClaim(anyhow!("The JWT payload cannot be deserialized: {}", err))
+        })
+    }
+
     /// Set a value for issuer payload claim (iss).
     ///
     /// # Arguments
@@ -210,6 +246,24 @@ impl JwtPayload {
         }
     }
 
+    /// Set a value for confirmation payload claim (cnf).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a confirmation
+    pub fn set_confirmation(&mut self, value: Confirmation) {
+        self.claims
+            .insert("cnf".to_string(), Value::Object(value.into()));
+    }
+
+    /// Return the value for confirmation payload claim (cnf).
+    pub fn confirmation(&self) -> Option<Confirmation> {
+        match self.claims.get("cnf") {
+            Some(Value::Object(val)) => Confirmation::from_map(val.clone()).ok(),
+            _ => None,
+        }
+    }
+
     /// Set a value for payload claim of a specified key.
     ///
     /// # Arguments
@@ -279,6 +333,12 @@ impl JwtPayload {
                     },
                     _ => bail!("The JWT {} header claim must be a string.", key),
                 },
+                "cnf" => match &value {
+                    Value::Object(val) => {
+                        Confirmation::from_map(val.clone())?;
+                    }
+                    _ => bail!("The JWT {} payload claim must be a object.", key),
+                },
                 _ => {}
             }
 
@@ -312,6 +372,7 @@ mod tests {
     use std::time::SystemTime;
 
     use anyhow::Result;
+    use serde::{Deserialize, Serialize};
     use serde_json::json;
 
     use super::JwtPayload;
@@ -343,4 +404,69 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_payload_from_and_to_claims() -> Result<()> {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Claims {
+            iss: String,
+            exp: u64,
+            scope: Vec<String>,
+        }
+
+        let src = Claims {
+            iss: "iss".to_string(),
+            exp: 1234,
+            scope: vec!["read".to_string()],
+        };
+        let payload = JwtPayload::from_claims(&src)?;
+        assert_eq!(payload.issuer(), Some("iss"));
+        assert_eq!(payload.claim("scope"), Some(&json!(["read"])));
+
+        let dst: Claims = payload.to_claims()?;
+        assert_eq!(src, dst);
+
+        #[derive(Serialize)]
+        struct InvalidClaims {
+            iss: u64,
+        }
+        assert!(JwtPayload::from_claims(&InvalidClaims { iss: 1 }).is_err());
+        assert!(JwtPayload::from_claims(&"claims").is_err());
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct OtherClaims {
+            nonce: String,
+        }
+        let err = payload.to_claims::<OtherClaims>().unwrap_err();
+        assert!(format!("{}", err).contains("nonce"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_payload_from_claims_with_none() -> Result<()> {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Claims {
+            iss: String,
+            exp: Option<u64>,
+            nonce: Option<String>,
+        }
+
+        let src = Claims {
+            iss: "iss".to_string(),
+            exp: None,
+            nonce: None,
+        };
+        let payload = JwtPayload::from_claims(&src)?;
+        assert_eq!(payload.issuer(), Some("iss"));
+        assert!(payload.claim("exp").is_none());
+        assert!(payload.claim("nonce").is_none());
+        assert_eq!(payload.claims_set().len(), 1);
+
+        let dst: Claims = payload.to_claims()?;
+        assert_eq!(src, dst);
+
+        Ok(())
+    }
 }
@@ -1,18 +1,39 @@
+use std::collections::BTreeMap;
 use std::convert::Into;
-use std::time::SystemTime;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use anyhow::bail;
 
-use crate::jwt::JwtPayload;
+use crate::jwt::{JwtClaimType, JwtPayload, ReplayGuard};
+use crate::util::{Clock, SystemClock};
 use crate::{JoseError, Map, Value};
 
+type ClaimPredicate = Box<dyn Fn(&Value) -> bool + Send + Sync>;
+
 /// Represents JWT payload validator.
-#[derive(Debug, Eq, PartialEq)]
+///
+/// Two validators are equal if their plain settings are equal.
+/// The clock, the replay guard and the claim predicates are not compared,
+/// only the claim names of the predicates are.
 pub struct JwtPayloadValidator {
+    clock: Box<dyn Clock>,
     base_time: Option<SystemTime>,
+    expires_at_leeway: Duration,
+    not_before_leeway: Duration,
+    max_age: Option<Duration>,
     min_issued_time: Option<SystemTime>,
     max_issued_time: Option<SystemTime>,
     audience: Option<String>,
+    issuers: Option<Vec<String>>,
+    audiences: Option<Vec<String>>,
+    required_claims: Vec<String>,
+    claim_types: BTreeMap<String, JwtClaimType>,
+    claim_contains: BTreeMap<String, Vec<Value>>,
+    claim_predicates: Vec<(String, ClaimPredicate)>,
+    replay_guard: Option<Arc<dyn ReplayGuard>>,
+    replay_key_with_issuer: bool,
     claims: Map<String, Value>,
 }
 
@@ -20,14 +41,97 @@ impl JwtPayloadValidator {
     /// Return a new JwtPayloadValidator.
     pub fn new() -> Self {
         Self {
+            clock: Box::new(SystemClock),
             base_time: None,
+            expires_at_leeway: Duration::ZERO,
+            not_before_leeway: Duration::ZERO,
+            max_age: None,
             min_issued_time: None,
             max_issued_time: None,
             audience: None,
+            issuers: None,
+            audiences: None,
+            required_claims: Vec::new(),
+            claim_types: BTreeMap::new(),
+            claim_contains: BTreeMap::new(),
+            claim_predicates: Vec::new(),
+            replay_guard: None,
+            replay_key_with_issuer: false,
             claims: Map::new(),
         }
     }
 
+    /// Set a clock that is used as the current time when the base time is not set.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - a clock
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Return the clock that is used as the current time.
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// Set a same leeway for expiration time (exp) and not before (nbf) payload claims validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `leeway` - a tolerance for clock skew
+    pub fn set_leeway(&mut self, leeway: Duration) {
+        self.expires_at_leeway = leeway;
+        self.not_before_leeway = leeway;
+    }
+
+    /// Set a leeway for expiration time payload claim (exp) validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `leeway` - a tolerance after the expiration time
+    pub fn set_expires_at_leeway(&mut self, leeway: Duration) {
+        self.expires_at_leeway = leeway;
+    }
+
+    /// Return the leeway for expiration time payload claim (exp) validation.
+    pub fn expires_at_leeway(&self) -> Duration {
+        self.expires_at_leeway
+    }
+
+    /// Set a leeway for not before payload claim (nbf) validation.
+    ///
+    /// This leeway is also applied to a future issued at payload claim (iat)
+    /// when the maximum issued time is not set.
+    ///
+    /// # Arguments
+    ///
+    /// * `leeway` - a tolerance before the not before time
+    pub fn set_not_before_leeway(&mut self, leeway: Duration) {
+        self.not_before_leeway = leeway;
+    }
+
+    /// Return the leeway for not before payload claim (nbf) validation.
+    pub fn not_before_leeway(&self) -> Duration {
+        self.not_before_leeway
+    }
+
+    /// Set a maximum age of the JWT relative to issued at payload claim (iat).
+    ///
+    /// If it is set, the iat payload claim is required.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_age` - a maximum elapsed time since the JWT was issued.
+    pub fn set_max_age(&mut self, max_age: Duration) {
+        self.max_age = Some(max_age);
+    }
+
+    /// Return the maximum age of the JWT relative to issued at payload claim (iat).
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
     /// Set a base time for time related claims (exp, nbf) validation.
     ///
     /// # Arguments
@@ -124,6 +228,157 @@ impl JwtPayloadValidator {
         }
     }
 
+    /// Set acceptable values for issuer payload claim (iss) validation.
+    ///
+    /// The iss payload claim is required and must be one of the values.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - acceptable issuers
+    pub fn set_issuers(&mut self, values: Vec<impl Into<String>>) {
+        let values: Vec<String> = values.into_iter().map(|val| val.into()).collect();
+        self.issuers = Some(values);
+    }
+
+    /// Return the acceptable values for issuer payload claim (iss) validation.
+    pub fn issuers(&self) -> Option<Vec<&str>> {
+        self.issuers
+            .as_ref()
+            .map(|vals| vals.iter().map(|val| val.as_str()).collect())
+    }
+
+    /// Set acceptable values for audience payload claim (aud) validation.
+    ///
+    /// The aud payload claim is required and must contain at least one of the values.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - acceptable audiences
+    pub fn set_audiences(&mut self, values: Vec<impl Into<String>>) {
+        let values: Vec<String> = values.into_iter().map(|val| val.into()).collect();
+        self.audiences = Some(values);
+    }
+
+    /// Return the acceptable values for audience payload claim (aud) validation.
+    pub fn audiences(&self) -> Option<Vec<&str>> {
+        self.audiences
+            .as_ref()
+            .map(|vals| vals.iter().map(|val| val.as_str()).collect())
+    }
+
+    /// Add a name of payload claim that must be present.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of payload claim
+    pub fn add_required_claim(&mut self, key: &str) {
+        if !self.required_claims.iter().any(|val| val == key) {
+            self.required_claims.push(key.to_string());
+        }
+    }
+
+    /// Return the names of payload claim that must be present.
+    pub fn required_claims(&self) -> Vec<&str> {
+        self.required_claims
+            .iter()
+            .map(|val| val.as_str())
+            .collect()
+    }
+
+    /// Set a JSON type of payload claim of a specified key.
+    ///
+    /// The type is checked only if the claim is present.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of payload claim
+    /// * `claim_type` - a JSON type of payload claim
+    pub fn set_claim_type(&mut self, key: &str, claim_type: JwtClaimType) {
+        self.claim_types.insert(key.to_string(), claim_type);
+    }
+
+    /// Return the JSON type of payload claim of a specified key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of payload claim
+    pub fn claim_type(&self, key: &str) -> Option<JwtClaimType> {
+        self.claim_types.get(key).copied()
+    }
+
+    /// Add a value that payload claim of a specified key must contain.
+    ///
+    /// The claim must be an array that contains the value,
+    /// or a space-delimited string that contains the value like a scope claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of payload claim
+    /// * `value` - a value that must be contained
+    pub fn add_claim_contains(&mut self, key: &str, value: impl Into<Value>) {
+        self.claim_contains
+            .entry(key.to_string())
+            .or_default()
+            .push(value.into());
+    }
+
+    /// Return the values that payload claim of a specified key must contain.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of payload claim
+    pub fn claim_contains(&self, key: &str) -> Option<&Vec<Value>> {
+        self.claim_contains.get(key)
+    }
+
+    /// Add a custom predicate for payload claim of a specified key.
+    ///
+    /// The claim is required, and the validation fails if the predicate returns false.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of payload claim
+    /// * `predicate` - a function for testing the value of payload claim
+    pub fn add_claim_predicate<F>(&mut self, key: &str, predicate: F)
+    where
+        F: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        self.claim_predicates
+            .push((key.to_string(), Box::new(predicate)));
+    }
+
+    /// Set a replay guard that rejects a JWT ID payload claim (jti) seen before.
+    ///
+    /// The jti payload claim is required, and the exp payload claim or
+    /// the iat payload claim with a max age is required to decide how long the jti is remembered.
+    /// The jti is recorded only when all other validations succeed.
+    ///
+    /// # Arguments
+    ///
+    /// * `guard` - a replay guard that is shared among validators.
+    pub fn set_replay_guard(&mut self, guard: Arc<dyn ReplayGuard>) {
+        self.replay_guard = Some(guard);
+    }
+
+    /// Return the replay guard.
+    pub fn replay_guard(&self) -> Option<&Arc<dyn ReplayGuard>> {
+        self.replay_guard.as_ref()
+    }
+
+    /// Set whether the issuer payload claim (iss) is combined with the jti as a replay key.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - true if the jti is unique only in the issuer.
+    pub fn set_replay_key_with_issuer(&mut self, value: bool) {
+        self.replay_key_with_issuer = value;
+    }
+
+    /// Return whether the issuer payload claim (iss) is combined with the jti as a replay key.
+    pub fn is_replay_key_with_issuer(&self) -> bool {
+        self.replay_key_with_issuer
+    }
+
     /// Set a value for JWT ID payload claim (jti) validation.
     ///
     /// # Arguments
@@ -168,26 +423,36 @@ impl JwtPayloadValidator {
     /// * `payload` - a decoded JWT payload.
     pub fn validate(&self, payload: &JwtPayload) -> Result<(), JoseError> {
         (|| -> anyhow::Result<()> {
-            let now = SystemTime::now();
-            let current_time = self.base_time().unwrap_or(&now);
+            let now = match self.base_time {
+                Some(val) => val,
+                None => self.clock.now(),
+            };
             let min_issued_time = self.min_issued_time().unwrap_or(&SystemTime::UNIX_EPOCH);
-            let max_issued_time = self.max_issued_time().unwrap_or(&now);
+            let default_max_issued_time = match now.checked_add(self.not_before_leeway) {
+                Some(val) => val,
+                None => bail!("The not before leeway is out of range."),
+            };
+            let max_issued_time = self.max_issued_time().unwrap_or(&default_max_issued_time);
 
             if let Some(not_before) = payload.not_before() {
-                if &not_before > current_time {
-                    bail!(
-                        "The token is not yet valid: {}",
-                        time::OffsetDateTime::from(not_before),
-                    );
+                if not_before > default_max_issued_time {
+                    bail!(JoseError::NotYetValid {
+                        nbf: not_before,
+                        now,
+                    });
                 }
             }
 
             if let Some(expires_at) = payload.expires_at() {
-                if &expires_at <= current_time {
-                    bail!(
-                        "The token has expired: {}",
-                        time::OffsetDateTime::from(expires_at),
-                    );
+                let limit = match expires_at.checked_add(self.expires_at_leeway) {
+                    Some(val) => val,
+                    None => bail!("The expiration time is out of range."),
+                };
+                if limit <= now {
+                    bail!(JoseError::Expired {
+                        exp: expires_at,
+                        now,
+                    });
                 }
             }
 
@@ -207,21 +472,152 @@ impl JwtPayloadValidator {
                 }
             }
 
+            if let Some(max_age) = self.max_age {
+                match payload.issued_at() {
+                    Some(issued_at) => {
+                        let limit = match issued_at
+                            .checked_add(max_age)
+                            .and_then(|val| val.checked_add(self.expires_at_leeway))
+                        {
+                            Some(val) => val,
+                            None => bail!("The issued time is out of range for the max age."),
+                        };
+                        if limit < now {
+                            bail!(
+                                "The issued time is older than the max age {}s: {}",
+                                max_age.as_secs(),
+                                time::OffsetDateTime::from(issued_at),
+                            );
+                        }
+                    }
+                    None => bail!(JoseError::MissingClaim("iat".to_string())),
+                }
+            }
+
             if let Some(audience) = &self.audience {
                 if let Some(audiences) = payload.audience() {
                     if !audiences.contains(&audience.as_str()) {
-                        bail!("Key aud is invalid: {}", audiences.join(", "));
+                        bail!(JoseError::AudienceMismatch(
+                            audiences.iter().map(|val| val.to_string()).collect(),
+                        ));
                     }
                 }
             }
 
+            if let Some(issuers) = &self.issuers {
+                match payload.issuer() {
+                    Some(issuer) => {
+                        if !issuers.iter().any(|val| val == issuer) {
+                            bail!(JoseError::IssuerMismatch(issuer.to_string()));
+                        }
+                    }
+                    None => bail!(JoseError::MissingClaim("iss".to_string())),
+                }
+            }
+
+            if let Some(audiences) = &self.audiences {
+                match payload.audience() {
+                    Some(vals) => {
+                        if !audiences.iter().any(|val| vals.contains(&val.as_str())) {
+                            bail!(JoseError::AudienceMismatch(
+                                vals.iter().map(|val| val.to_string()).collect(),
+                            ));
+                        }
+                    }
+                    None => bail!(JoseError::MissingClaim("aud".to_string())),
+                }
+            }
+
+            for key in &self.required_claims {
+                if payload.claim(key).is_none() {
+                    bail!(JoseError::MissingClaim(key.to_string()));
+                }
+            }
+
+            for (key, claim_type) in &self.claim_types {
+                if let Some(value) = payload.claim(key) {
+                    if !claim_type.is_instance(value) {
+                        bail!("Key {} must be a {}: {}", key, claim_type, value);
+                    }
+                }
+            }
+
+            for (key, expected_values) in &self.claim_contains {
+                let value = match payload.claim(key) {
+                    Some(val) => val,
+                    None => bail!(JoseError::MissingClaim(key.to_string())),
+                };
+
+                for expected in expected_values {
+                    let contained = match value {
+                        Value::Array(vals) => vals.contains(expected),
+                        Value::String(val) => match expected {
+                            Value::String(expected) => {
+                                val.split_whitespace().any(|val| val == expected)
+                            }
+                            _ => false,
+                        },
+                        _ => bail!("Key {} must be a array or string: {}", key, value),
+                    };
+                    if !contained {
+                        bail!("Key {} does not contain {}: {}", key, expected, value);
+                    }
+                }
+            }
+
+            for (key, predicate) in &self.claim_predicates {
+                match payload.claim(key) {
+                    Some(value) => {
+                        if !predicate(value) {
+                            bail!("Key {} is invalid: {}", key, value);
+                        }
+                    }
+                    None => bail!(JoseError::MissingClaim(key.to_string())),
+                }
+            }
+
             for (key, value1) in &self.claims {
                 if let Some(value2) = payload.claim(key) {
                     if value1 != value2 {
+                        if let ("iss", Value::String(val)) = (key.as_str(), value2) {
+                            bail!(JoseError::IssuerMismatch(val.to_string()));
+                        }
                         bail!("Key {} is invalid: {}", key, value2);
                     }
                 } else {
-                    bail!("Key {} is missing.", key);
+                    bail!(JoseError::MissingClaim(key.to_string()));
+                }
+            }
+
+            if let Some(guard) = &self.replay_guard {
+                let jwt_id = match payload.jwt_id() {
+                    Some(val) => val,
+                    None => bail!(JoseError::MissingClaim("jti".to_string())),
+                };
+
+                let expires_at = match (payload.expires_at(), payload.issued_at(), self.max_age) {
+                    (Some(val), _, _) => val,
+                    (None, Some(issued_at), Some(max_age)) => {
+                        match issued_at.checked_add(max_age) {
+                            Some(val) => val,
+                            None => bail!("The issued time is out of range for the max age."),
+                        }
+                    }
+                    _ => bail!(JoseError::MissingClaim("exp".to_string())),
+                };
+
+                let replay_key = if self.replay_key_with_issuer {
+                    format!("{}\u{0}{}", payload.issuer().unwrap_or(""), jwt_id)
+                } else {
+                    jwt_id.to_string()
+                };
+
+                let forget_at = match expires_at.checked_add(self.expires_at_leeway) {
+                    Some(val) => val,
+                    None => bail!("The expiration time is out of range."),
+                };
+                if !guard.check_and_record(&replay_key, forget_at, now)? {
+                    bail!("The token has already been used: {}", jwt_id);
                 }
             }
 
@@ -234,14 +630,79 @@ impl JwtPayloadValidator {
     }
 }
 
+impl Default for JwtPayloadValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for JwtPayloadValidator {
+    fn eq(&self, other: &Self) -> bool {
+        self.base_time == other.base_time
+            && self.expires_at_leeway == other.expires_at_leeway
+            && self.not_before_leeway == other.not_before_leeway
+            && self.max_age == other.max_age
+            && self.min_issued_time == other.min_issued_time
+            && self.max_issued_time == other.max_issued_time
+            && self.audience == other.audience
+            && self.issuers == other.issuers
+            && self.audiences == other.audiences
+            && self.required_claims == other.required_claims
+            && self.claim_types == other.claim_types
+            && self.claim_contains == other.claim_contains
+            && self
+                .claim_predicates
+                .iter()
+                .map(|(key, _)| key)
+                .eq(other.claim_predicates.iter().map(|(key, _)| key))
+            && self.replay_key_with_issuer == other.replay_key_with_issuer
+            && self.claims == other.claims
+    }
+}
+
+impl Eq for JwtPayloadValidator {}
+
+impl Debug for JwtPayloadValidator {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let predicate_keys: Vec<&str> = self
+            .claim_predicates
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect();
+
+        fmt.debug_struct("JwtPayloadValidator")
+            .field("clock", &self.clock)
+            .field("base_time", &self.base_time)
+            .field("expires_at_leeway", &self.expires_at_leeway)
+            .field("not_before_leeway", &self.not_before_leeway)
+            .field("max_age", &self.max_age)
+            .field("min_issued_time", &self.min_issued_time)
+            .field("max_issued_time", &self.max_issued_time)
+            .field("audience", &self.audience)
+            .field("issuers", &self.issuers)
+            .field("audiences", &self.audiences)
+            .field("required_claims", &self.required_claims)
+            .field("claim_types", &self.claim_types)
+            .field("claim_contains", &self.claim_contains)
+            .field("claim_predicates", &predicate_keys)
+            .field("replay_guard", &self.replay_guard)
+            .field("replay_key_with_issuer", &self.replay_key_with_issuer)
+            .field("claims", &self.claims)
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
     use std::time::{Duration, SystemTime};
 
     use anyhow::Result;
     use serde_json::json;
 
-    use crate::jwt::{JwtPayload, JwtPayloadValidator};
+    use crate::jwt::{JwtClaimType, JwtPayload, JwtPayloadValidator, MemoryReplayGuard};
+    use crate::util::FixedClock;
+    use crate::JoseError;
 
     #[test]
     fn test_jwt_payload_validate() -> Result<()> {
@@ -264,4 +725,336 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_jwt_payload_validate_with_leeway() -> Result<()> {
+        let mut payload = JwtPayload::new();
+        payload.set_expires_at(&(SystemTime::UNIX_EPOCH + Duration::from_secs(60)));
+        payload.set_not_before(&(SystemTime::UNIX_EPOCH + Duration::from_secs(10)));
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(65));
+        assert!(validator.validate(&payload).is_err());
+        validator.set_expires_at_leeway(Duration::from_secs(10));
+        validator.validate(&payload)?;
+
+        validator.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(5));
+        assert!(validator.validate(&payload).is_err());
+        validator.set_not_before_leeway(Duration::from_secs(5));
+        validator.validate(&payload)?;
+
+        validator.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(4));
+        assert!(validator.validate(&payload).is_err());
+
+        validator.set_leeway(Duration::from_secs(6));
+        validator.validate(&payload)?;
+        validator.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(66));
+        assert!(validator.validate(&payload).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_with_out_of_range_time() -> Result<()> {
+        let mut payload = JwtPayload::new();
+        payload.set_claim("exp", Some(json!(i64::MAX)))?;
+        payload.set_claim("iat", Some(json!(i64::MAX)))?;
+        payload.set_jwt_id("jti");
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(30));
+        validator
+            .set_max_issued_time(SystemTime::UNIX_EPOCH + Duration::from_secs(i64::MAX as u64));
+        validator.set_leeway(Duration::from_secs(60));
+        assert!(matches!(
+            validator.validate(&payload),
+            Err(JoseError::InvalidClaim(_))
+        ));
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(30));
+        validator
+            .set_max_issued_time(SystemTime::UNIX_EPOCH + Duration::from_secs(i64::MAX as u64));
+        validator.set_max_age(Duration::MAX);
+        assert!(matches!(
+            validator.validate(&payload),
+            Err(JoseError::InvalidClaim(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_with_max_age() -> Result<()> {
+        let mut payload = JwtPayload::new();
+        payload.set_issued_at(&(SystemTime::UNIX_EPOCH + Duration::from_secs(100)));
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_max_age(Duration::from_secs(30));
+        validator.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(130));
+        validator.validate(&payload)?;
+
+        validator.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(131));
+        assert!(validator.validate(&payload).is_err());
+
+        validator.set_expires_at_leeway(Duration::from_secs(1));
+        validator.validate(&payload)?;
+
+        assert!(validator.validate(&JwtPayload::new()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validator_eq() -> Result<()> {
+        assert_eq!(JwtPayloadValidator::new(), JwtPayloadValidator::default());
+
+        let mut validator1 = JwtPayloadValidator::new();
+        validator1.set_issuer("issuer1");
+        validator1.set_clock(FixedClock::new(SystemTime::UNIX_EPOCH));
+        let mut validator2 = JwtPayloadValidator::new();
+        validator2.set_issuer("issuer1");
+        assert_eq!(validator1, validator2);
+
+        validator1.add_claim_predicate("scope", |_| true);
+        assert_ne!(validator1, validator2);
+        validator2.add_claim_predicate("scope", |_| false);
+        assert_eq!(validator1, validator2);
+
+        validator2.set_max_age(Duration::from_secs(30));
+        assert_ne!(validator1, validator2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_with_clock() -> Result<()> {
+        let mut payload = JwtPayload::new();
+        payload.set_expires_at(&(SystemTime::UNIX_EPOCH + Duration::from_secs(60)));
+        payload.set_issued_at(&(SystemTime::UNIX_EPOCH + Duration::from_secs(10)));
+
+        let mut validator = JwtPayloadValidator::new();
+        assert!(validator.validate(&payload).is_err());
+
+        validator.set_clock(FixedClock::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(30),
+        ));
+        validator.validate(&payload)?;
+
+        validator.set_clock(FixedClock::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(5),
+        ));
+        assert!(validator.validate(&payload).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_with_issuers() -> Result<()> {
+        let mut payload = JwtPayload::new();
+        payload.set_issuer("https://tenant2.example.com");
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_issuers(vec![
+            "https://tenant1.example.com",
+            "https://tenant2.example.com",
+            "https://tenant3.example.com",
+        ]);
+        validator.validate(&payload)?;
+
+        validator.set_issuers(vec!["https://tenant1.example.com"]);
+        assert!(validator.validate(&payload).is_err());
+        assert!(validator.validate(&JwtPayload::new()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_with_audiences() -> Result<()> {
+        let mut payload = JwtPayload::new();
+        payload.set_audience(vec!["api1", "api2"]);
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_audiences(vec!["api0", "api2"]);
+        validator.validate(&payload)?;
+
+        validator.set_audiences(vec!["api3"]);
+        assert!(validator.validate(&payload).is_err());
+        assert!(validator.validate(&JwtPayload::new()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_with_required_claims() -> Result<()> {
+        let mut payload = JwtPayload::new();
+        payload.set_claim("nonce", Some(json!("n-0S6_WzA2Mj")))?;
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.add_required_claim("nonce");
+        validator.validate(&payload)?;
+
+        assert!(validator.validate(&JwtPayload::new()).is_err());
+
+        validator.add_required_claim("exp");
+        assert!(validator.validate(&payload).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_with_claim_types() -> Result<()> {
+        let mut payload = JwtPayload::new();
+        payload.set_expires_at(&(SystemTime::UNIX_EPOCH + Duration::from_secs(60)));
+        payload.set_claim("scope", Some(json!("openid read write")))?;
+        payload.set_claim("roles", Some(json!(["admin", "user"])))?;
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(30));
+        validator.set_claim_type("exp", JwtClaimType::Integer);
+        validator.set_claim_type("roles", JwtClaimType::Array);
+        validator.set_claim_type("missing", JwtClaimType::String);
+        validator.validate(&payload)?;
+
+        validator.set_claim_type("scope", JwtClaimType::Array);
+        assert!(validator.validate(&payload).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_with_claim_contains() -> Result<()> {
+        let mut payload = JwtPayload::new();
+        payload.set_claim("scope", Some(json!("openid read write")))?;
+        payload.set_claim("roles", Some(json!(["admin", "user"])))?;
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.add_claim_contains("scope", "read");
+        validator.add_claim_contains("roles", "admin");
+        validator.validate(&payload)?;
+
+        let mut validator2 = JwtPayloadValidator::new();
+        validator2.add_claim_contains("scope", "delete");
+        assert!(validator2.validate(&payload).is_err());
+
+        let mut validator2 = JwtPayloadValidator::new();
+        validator2.add_claim_contains("roles", "guest");
+        assert!(validator2.validate(&payload).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_with_claim_predicates() -> Result<()> {
+        let mut payload = JwtPayload::new();
+        payload.set_claim("level", Some(json!(3)))?;
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.add_claim_predicate("level", |value| value.as_u64().is_some_and(|val| val >= 2));
+        validator.validate(&payload)?;
+
+        validator.add_claim_predicate("level", |value| value.as_u64() == Some(5));
+        assert!(validator.validate(&payload).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_with_replay_guard() -> Result<()> {
+        let guard = Arc::new(MemoryReplayGuard::new());
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(30));
+        validator.set_replay_guard(guard.clone());
+
+        let mut payload = JwtPayload::new();
+        payload.set_issuer("issuer1");
+        payload.set_jwt_id("id1");
+        payload.set_expires_at(&(SystemTime::UNIX_EPOCH + Duration::from_secs(60)));
+        validator.validate(&payload)?;
+        assert!(validator.validate(&payload).is_err());
+
+        payload.set_issuer("issuer2");
+        assert!(validator.validate(&payload).is_err());
+        validator.set_replay_key_with_issuer(true);
+        validator.validate(&payload)?;
+        assert!(validator.validate(&payload).is_err());
+
+        let mut validator2 = JwtPayloadValidator::new();
+        validator2.set_base_time(SystemTime::UNIX_EPOCH + Duration::from_secs(90));
+        validator2.set_replay_guard(guard.clone());
+        payload.set_jwt_id("id2");
+        payload.set_expires_at(&(SystemTime::UNIX_EPOCH + Duration::from_secs(120)));
+        validator2.validate(&payload)?;
+        assert_eq!(guard.len(), 1);
+
+        let mut payload = JwtPayload::new();
+        payload.set_expires_at(&(SystemTime::UNIX_EPOCH + Duration::from_secs(120)));
+        assert!(validator2.validate(&payload).is_err());
+
+        let mut payload = JwtPayload::new();
+        payload.set_jwt_id("id3");
+        assert!(validator2.validate(&payload).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_payload_validate_error_kinds() -> Result<()> {
+        let base_time = SystemTime::UNIX_EPOCH + Duration::from_secs(30);
+
+        let mut payload = JwtPayload::new();
+        payload.set_issuer("issuer1");
+        payload.set_audience(vec!["aud1"]);
+        payload.set_not_before(&(SystemTime::UNIX_EPOCH + Duration::from_secs(60)));
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_base_time(base_time);
+        assert!(matches!(
+            validator.validate(&payload),
+            Err(JoseError::NotYetValid { now, .. }) if now == base_time
+        ));
+
+        payload.set_not_before(&SystemTime::UNIX_EPOCH);
+        payload.set_expires_at(&(SystemTime::UNIX_EPOCH + Duration::from_secs(10)));
+        assert!(matches!(
+            validator.validate(&payload),
+            Err(JoseError::Expired { exp, now })
+                if exp == SystemTime::UNIX_EPOCH + Duration::from_secs(10) && now == base_time
+        ));
+
+        payload.set_expires_at(&(SystemTime::UNIX_EPOCH + Duration::from_secs(60)));
+        validator.set_audience("aud2");
+        assert!(matches!(
+            validator.validate(&payload),
+            Err(JoseError::AudienceMismatch(ref vals)) if vals == &vec!["aud1".to_string()]
+        ));
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_base_time(base_time);
+        validator.set_issuer("issuer2");
+        assert!(matches!(
+            validator.validate(&payload),
+            Err(JoseError::IssuerMismatch(ref val)) if val == "issuer1"
+        ));
+
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_base_time(base_time);
+        validator.add_required_claim("nonce");
+        assert!(matches!(
+            validator.validate(&payload),
+            Err(JoseError::MissingClaim(ref val)) if val == "nonce"
+        ));
+
+        let mut payload = JwtPayload::new();
+        payload.set_issued_at(&SystemTime::UNIX_EPOCH);
+        let mut validator = JwtPayloadValidator::new();
+        validator.set_base_time(base_time);
+        validator.set_max_age(Duration::from_secs(10));
+        assert!(matches!(
+            validator.validate(&payload),
+            Err(JoseError::InvalidClaim(_))
+        ));
+
+        Ok(())
+    }
 }
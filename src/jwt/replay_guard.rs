@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use anyhow::anyhow;
+
+use crate::JoseError;
+
+/// Represent a store of token identifiers that are already used.
+pub trait ReplayGuard: Debug + Send + Sync {
+    /// Record a token identifier and return true if it has not been seen yet.
+    ///
+    /// The identifier must be remembered at least until the expiration time.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a token identifier.
+    /// * `expires_at` - a time after which the identifier may be forgotten.
+    /// * `now` - the current time.
+    fn check_and_record(
+        &self,
+        key: &str,
+        expires_at: SystemTime,
+        now: SystemTime,
+    ) -> Result<bool, JoseError>;
+}
+
+/// A replay guard that keeps token identifiers in memory.
+///
+/// Expired identifiers are evicted in order of their expiration time
+/// whenever a new identifier is recorded.
+#[derive(Debug, Default)]
+pub struct MemoryReplayGuard {
+    entries: Mutex<MemoryReplayEntries>,
+    max_entries: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryReplayEntries {
+    keys: HashMap<String, SystemTime>,
+    expirations: BTreeMap<SystemTime, Vec<String>>,
+}
+
+impl MemoryReplayEntries {
+    fn evict_expired(&mut self, now: SystemTime) {
+        while let Some(entry) = self.expirations.first_entry() {
+            if *entry.key() > now {
+                break;
+            }
+
+            let (expires_at, keys) = entry.remove_entry();
+            for key in keys {
+                if self.keys.get(&key) == Some(&expires_at) {
+                    self.keys.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+impl MemoryReplayGuard {
+    /// Return a new empty MemoryReplayGuard.
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(MemoryReplayEntries::default()),
+            max_entries: None,
+        }
+    }
+
+    /// Set a maximum number of token identifiers that are remembered at once.
+    ///
+    /// When the limit is reached, a new identifier is rejected with an error
+    /// until older identifiers expire.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a maximum number of token identifiers.
+    pub fn set_max_entries(&mut self, value: usize) {
+        self.max_entries = Some(value);
+    }
+
+    /// Return the maximum number of token identifiers that are remembered at once.
+    pub fn max_entries(&self) -> Option<usize> {
+        self.max_entries
+    }
+
+    /// Return the number of token identifiers that are currently remembered.
+    pub fn len(&self) -> usize {
+        match self.entries.lock() {
+            Ok(val) => val.keys.len(),
+            Err(err) => err.into_inner().keys.len(),
+        }
+    }
+
+    /// Test whether no token identifiers are remembered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ReplayGuard for MemoryReplayGuard {
+    fn check_and_record(
+        &self,
+        key: &str,
+        expires_at: SystemTime,
+        now: SystemTime,
+    ) -> Result<bool, JoseError> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| JoseError::InvalidClaim(anyhow!("The replay guard is poisoned.")))?;
+
+        entries.evict_expired(now);
+
+        if entries.keys.contains_key(key) {
+            return Ok(false);
+        }
+
+        if let Some(max_entries) = self.max_entries {
+            if entries.keys.len() >= max_entries {
+                return Err(JoseError::InvalidClaim(anyhow!(
+                    "The replay guard is full: {}",
+                    max_entries
+                )));
+            }
+        }
+
+        entries.keys.insert(key.to_string(), expires_at);
+        entries
+            .expirations
+            .entry(expires_at)
+            .or_default()
+            .push(key.to_string());
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+
+    use crate::jwt::{MemoryReplayGuard, ReplayGuard};
+
+    #[test]
+    fn test_memory_replay_guard() -> Result<()> {
+        let guard = MemoryReplayGuard::new();
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+
+        assert!(guard.check_and_record("a", now + Duration::from_secs(10), now)?);
+        assert!(guard.check_and_record("b", now + Duration::from_secs(60), now)?);
+        assert!(!guard.check_and_record("a", now + Duration::from_secs(10), now)?);
+        assert_eq!(guard.len(), 2);
+
+        let later = now + Duration::from_secs(30);
+        assert!(guard.check_and_record("a", later + Duration::from_secs(10), later)?);
+        assert!(!guard.check_and_record("b", later + Duration::from_secs(60), later)?);
+        assert_eq!(guard.len(), 2);
+
+        let much_later = now + Duration::from_secs(3600);
+        assert!(guard.check_and_record("c", much_later, much_later - Duration::from_secs(1))?);
+        assert_eq!(guard.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_replay_guard_max_entries() -> Result<()> {
+        let mut guard = MemoryReplayGuard::new();
+        guard.set_max_entries(2);
+        assert_eq!(guard.max_entries(), Some(2));
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+
+        assert!(guard.check_and_record("a", now + Duration::from_secs(10), now)?);
+        assert!(guard.check_and_record("b", now + Duration::from_secs(60), now)?);
+        assert!(guard
+            .check_and_record("c", now + Duration::from_secs(10), now)
+            .is_err());
+        assert!(!guard.check_and_record("a", now + Duration::from_secs(10), now)?);
+
+        let later = now + Duration::from_secs(30);
+        assert!(guard.check_and_record("c", later + Duration::from_secs(10), later)?);
+        assert_eq!(guard.len(), 2);
+
+        Ok(())
+    }
+}
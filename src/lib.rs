@@ -2,17 +2,27 @@
 //!
 //! `josekit` is a JOSE (Javascript Object Signing and Encryption: JWT, JWS, JWE, JWA, JWK) library.
 
+pub mod dpop;
 pub mod jwe;
 pub mod jwk;
 pub mod jws;
 pub mod jwt;
+pub mod oauth;
+pub mod oidc;
+#[cfg(feature = "pkcs11")]
+pub mod pkcs11;
+pub mod sd_jwt;
+pub mod secevent;
+pub mod status_list;
 pub mod util;
 
 mod jose_error;
 mod jose_header;
+mod jose_object;
 
 pub use crate::jose_error::JoseError;
 pub use crate::jose_header::JoseHeader;
+pub use crate::jose_object::{JoseObject, JoseObjectEntry, JoseObjectKind, JoseSerialization};
 
 pub use serde_json::{Map, Number, Value};
 
@@ -0,0 +1,30 @@
+//! OAuth 2.0 profiles of JWT support.
+
+mod authorization_response_builder;
+mod authorization_response_validator;
+mod client_assertion_builder;
+mod client_assertion_validator;
+mod jwt_access_token;
+mod jwt_access_token_builder;
+mod jwt_access_token_validator;
+mod request_object_builder;
+mod request_object_validator;
+
+pub use crate::oauth::authorization_response_builder::AuthorizationResponseBuilder;
+pub use crate::oauth::authorization_response_validator::AuthorizationResponseValidator;
+pub use crate::oauth::client_assertion_builder::ClientAssertionBuilder;
+pub use crate::oauth::client_assertion_validator::ClientAssertionValidator;
+pub use crate::oauth::jwt_access_token::JwtAccessToken;
+pub use crate::oauth::jwt_access_token_builder::JwtAccessTokenBuilder;
+pub use crate::oauth::jwt_access_token_validator::JwtAccessTokenValidator;
+pub use crate::oauth::request_object_builder::RequestObjectBuilder;
+pub use crate::oauth::request_object_validator::RequestObjectValidator;
+
+/// The media type of a JWT access token (RFC 9068).
+pub const ACCESS_TOKEN_TYPE: &str = "at+jwt";
+
+/// The client_assertion_type parameter value of a JWT client assertion (RFC 7523).
+pub const CLIENT_ASSERTION_TYPE: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+/// The media type of a JWT-secured authorization request object (RFC 9101).
+pub const REQUEST_OBJECT_TYPE: &str = "oauth-authz-req+jwt";
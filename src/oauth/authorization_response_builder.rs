@@ -0,0 +1,141 @@
+use std::convert::Into;
+use std::time::{Duration, SystemTime};
+
+use anyhow::bail;
+
+use crate::jwe::{JweEncrypter, JweHeader};
+use crate::jws::{JwsHeader, JwsSigner};
+use crate::jwt::{JwtContext, JwtPayload};
+use crate::{JoseError, Value};
+
+/// Represents JWT-secured authorization response (JARM) builder.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct AuthorizationResponseBuilder {
+    context: JwtContext,
+    payload: JwtPayload,
+    header: JwsHeader,
+    expires_in: Duration,
+    issued_at: Option<SystemTime>,
+}
+
+impl AuthorizationResponseBuilder {
+    /// Return a new AuthorizationResponseBuilder.
+    ///
+    /// The response expires in 10 minutes by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a issuer identifier of the authorization server.
+    /// * `client_id` - a client ID of the OAuth client.
+    pub fn new(issuer: impl Into<String>, client_id: impl Into<String>) -> Self {
+        let mut payload = JwtPayload::new();
+        payload.set_issuer(issuer);
+        payload.set_audience(vec![client_id.into()]);
+
+        Self {
+            context: JwtContext::new(),
+            payload,
+            header: JwsHeader::new(),
+            expires_in: Duration::from_secs(600),
+            issued_at: None,
+        }
+    }
+
+    /// Set a value for a authorization response parameter like code, state or error.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a parameter name
+    /// * `value` - a parameter value
+    pub fn set_parameter(&mut self, key: &str, value: Option<Value>) -> Result<(), JoseError> {
+        self.payload.set_claim(key, value)
+    }
+
+    /// Return a value for a authorization response parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a parameter name
+    pub fn parameter(&self, key: &str) -> Option<&Value> {
+        self.payload.claim(key)
+    }
+
+    /// Set a lifetime of the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `expires_in` - a lifetime from the issued time.
+    pub fn set_expires_in(&mut self, expires_in: Duration) {
+        self.expires_in = expires_in;
+    }
+
+    /// Set a issued time that is the base of the expiration time (exp).
+    /// The default value is the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issued time
+    pub fn set_issued_at(&mut self, value: SystemTime) {
+        self.issued_at = Some(value);
+    }
+
+    /// Set a value for key ID header claim (kid).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.header.set_key_id(value);
+    }
+
+    /// Return the payload and the header of the response.
+    pub fn build(&self) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        (|| -> anyhow::Result<(JwtPayload, JwsHeader)> {
+            let mut payload = self.payload.clone();
+
+            let issued_at = match self.issued_at {
+                Some(val) => val,
+                None => SystemTime::now(),
+            };
+            match issued_at.checked_add(self.expires_in) {
+                Some(val) => payload.set_expires_at(&val),
+                None => bail!("The expiration time is out of range."),
+            }
+
+            Ok((payload, self.header.clone()))
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+
+    /// Return the string repsentation of the response signed by the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    pub fn sign(&self, signer: &dyn JwsSigner) -> Result<String, JoseError> {
+        let (payload, header) = self.build()?;
+        self.context.encode_with_signer(&payload, &header, signer)
+    }
+
+    /// Return the string repsentation of the response signed by the signer
+    /// and encrypted by the encrypter.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    /// * `jwe_header` - The JWE heaser claims.
+    /// * `encrypter` - a encrypter object.
+    pub fn sign_and_encrypt(
+        &self,
+        signer: &dyn JwsSigner,
+        jwe_header: &JweHeader,
+        encrypter: &dyn JweEncrypter,
+    ) -> Result<String, JoseError> {
+        let (payload, header) = self.build()?;
+        self.context
+            .encode_nested(&payload, &header, signer, jwe_header, encrypter)
+    }
+}
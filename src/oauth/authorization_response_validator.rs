@@ -0,0 +1,235 @@
+use std::convert::Into;
+
+use anyhow::bail;
+
+use crate::jwe::JweDecrypter;
+use crate::jws::{JwsHeader, JwsVerifier};
+use crate::jwt::{JwtContext, JwtPayload, JwtPayloadValidator};
+use crate::{JoseError, Value};
+
+/// Represents JWT-secured authorization response (JARM) validator.
+#[derive(Debug)]
+pub struct AuthorizationResponseValidator {
+    context: JwtContext,
+    payload_validator: JwtPayloadValidator,
+    issuer: String,
+    client_id: String,
+    state: Option<String>,
+}
+
+impl AuthorizationResponseValidator {
+    /// Return a new AuthorizationResponseValidator.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a expected issuer identifier of the authorization server.
+    /// * `client_id` - a client ID of the OAuth client.
+    pub fn new(issuer: impl Into<String>, client_id: impl Into<String>) -> Self {
+        Self {
+            context: JwtContext::new(),
+            payload_validator: JwtPayloadValidator::new(),
+            issuer: issuer.into(),
+            client_id: client_id.into(),
+            state: None,
+        }
+    }
+
+    /// Return the mutable JWT context that is used for decoding.
+    pub fn context_mut(&mut self) -> &mut JwtContext {
+        &mut self.context
+    }
+
+    /// Return the mutable payload validator that checks the time claims.
+    pub fn payload_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.payload_validator
+    }
+
+    /// Set a state value that is sent in the authorization request.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a state value
+    pub fn set_state(&mut self, value: impl Into<String>) {
+        self.state = Some(value.into());
+    }
+
+    /// Return the response decoded and validated by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a response string representation.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_with_verifier(
+        &self,
+        input: impl AsRef<[u8]>,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        let (payload, header) = self.context.decode_with_verifier(input, verifier)?;
+        self.validate(&payload, &header)?;
+        Ok((payload, header))
+    }
+
+    /// Return the nested response decoded by the decrypter and validated by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a response string representation.
+    /// * `decrypter` - a decrypter of the decrypting algorithm.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_nested(
+        &self,
+        input: impl AsRef<[u8]>,
+        decrypter: &dyn JweDecrypter,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        let (payload, header, _) = self.context.decode_nested(input, decrypter, verifier)?;
+        self.validate(&payload, &header)?;
+        Ok((payload, header))
+    }
+
+    /// Validate a decoded response.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - a decoded response payload.
+    /// * `header` - a decoded response header.
+    pub fn validate(&self, payload: &JwtPayload, header: &JwsHeader) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            if let Some("none") | None = header.algorithm() {
+                bail!("The authorization response must be signed.");
+            }
+
+            match payload.issuer() {
+                Some(val) if val == self.issuer => {}
+                Some(val) => bail!(JoseError::IssuerMismatch(val.to_string())),
+                None => bail!(JoseError::MissingClaim("iss".to_string())),
+            }
+
+            match payload.audience() {
+                Some(vals) if vals.contains(&self.client_id.as_str()) => {}
+                Some(vals) => bail!(JoseError::AudienceMismatch(
+                    vals.iter().map(|val| val.to_string()).collect(),
+                )),
+                None => bail!(JoseError::MissingClaim("aud".to_string())),
+            }
+
+            if payload.expires_at().is_none() {
+                bail!(JoseError::MissingClaim("exp".to_string()));
+            }
+            self.payload_validator.validate(payload)?;
+
+            if let Some(state) = &self.state {
+                match payload.claim("state") {
+                    Some(Value::String(val)) => {
+                        if val != state {
+                            bail!("Key state is invalid: {}", val);
+                        }
+                    }
+                    Some(val) => bail!("Key state must be a string: {}", val),
+                    None => bail!(JoseError::MissingClaim("state".to_string())),
+                }
+            }
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::jwe::{JweHeader, A128KW};
+    use crate::jwk::alg::ec::EcCurve;
+    use crate::jwk::Jwk;
+    use crate::jws::ES256;
+    use crate::oauth::{AuthorizationResponseBuilder, AuthorizationResponseValidator};
+    use crate::util;
+    use crate::JoseError;
+
+    #[test]
+    fn test_authorization_response() -> Result<()> {
+        let jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let signer = ES256.signer_from_jwk(&jwk)?;
+        let verifier = ES256.verifier_from_jwk(&jwk.to_public_key()?)?;
+        let key = util::random_bytes(16);
+        let encrypter = A128KW.encrypter_from_bytes(&key)?;
+        let decrypter = A128KW.decrypter_from_bytes(&key)?;
+
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut builder = AuthorizationResponseBuilder::new("https://as.example.com", "client1");
+        builder.set_issued_at(now);
+        builder.set_parameter(
+            "code",
+            Some(json!("PyyFaux2o7Q0YfXBU32jhw.5FXSQpvr8akv9CeRDSd0QA")),
+        )?;
+        builder.set_parameter(
+            "state",
+            Some(json!("S8NJ7uqk5fY4EjNvP_G_FtyJu6pUsvH9jsYni9dMAJw")),
+        )?;
+        let response = builder.sign(&signer)?;
+
+        let mut jwe_header = JweHeader::new();
+        jwe_header.set_content_encryption("A128GCM");
+        let encrypted_response = builder.sign_and_encrypt(&signer, &jwe_header, &encrypter)?;
+
+        let mut validator =
+            AuthorizationResponseValidator::new("https://as.example.com", "client1");
+        validator.payload_validator_mut().set_base_time(now);
+        validator.set_state("S8NJ7uqk5fY4EjNvP_G_FtyJu6pUsvH9jsYni9dMAJw");
+        let (payload, _) = validator.decode_with_verifier(&response, &verifier)?;
+        assert_eq!(
+            payload.claim("code"),
+            Some(&json!("PyyFaux2o7Q0YfXBU32jhw.5FXSQpvr8akv9CeRDSd0QA"))
+        );
+        validator.decode_nested(&encrypted_response, &decrypter, &verifier)?;
+
+        validator.set_state("other");
+        assert!(validator
+            .decode_with_verifier(&response, &verifier)
+            .is_err());
+
+        let mut validator2 =
+            AuthorizationResponseValidator::new("https://mixup.example.com", "client1");
+        validator2.payload_validator_mut().set_base_time(now);
+        assert!(matches!(
+            validator2.decode_with_verifier(&response, &verifier),
+            Err(JoseError::IssuerMismatch(_))
+        ));
+
+        let mut validator2 =
+            AuthorizationResponseValidator::new("https://as.example.com", "client2");
+        validator2.payload_validator_mut().set_base_time(now);
+        assert!(matches!(
+            validator2.decode_with_verifier(&response, &verifier),
+            Err(JoseError::AudienceMismatch(_))
+        ));
+
+        let mut validator2 =
+            AuthorizationResponseValidator::new("https://as.example.com", "client1");
+        validator2
+            .payload_validator_mut()
+            .set_base_time(now + Duration::from_secs(601));
+        assert!(matches!(
+            validator2.decode_with_verifier(&response, &verifier),
+            Err(JoseError::Expired { .. })
+        ));
+
+        builder.set_expires_in(Duration::MAX);
+        assert!(matches!(builder.build(), Err(JoseError::InvalidClaim(_))));
+        assert!(matches!(
+            builder.sign(&signer),
+            Err(JoseError::InvalidClaim(_))
+        ));
+
+        Ok(())
+    }
+}
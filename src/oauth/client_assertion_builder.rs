@@ -0,0 +1,95 @@
+use std::convert::Into;
+use std::time::{Duration, SystemTime};
+
+use crate::jws::{JwsHeader, JwsSigner};
+use crate::jwt::{JwtBuilder, JwtContext, JwtPayload};
+use crate::JoseError;
+
+/// Represents client assertion builder for private_key_jwt and client_secret_jwt (RFC 7523).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ClientAssertionBuilder {
+    context: JwtContext,
+    builder: JwtBuilder,
+    header: JwsHeader,
+}
+
+impl ClientAssertionBuilder {
+    /// Return a new ClientAssertionBuilder.
+    ///
+    /// The assertion expires in 60 seconds by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - a client ID of the OAuth client.
+    /// * `audience` - a token endpoint URL or a issuer identifier of the authorization server.
+    pub fn new(client_id: impl Into<String>, audience: impl Into<String>) -> Self {
+        let client_id: String = client_id.into();
+
+        let mut builder = JwtBuilder::new();
+        builder
+            .set_issuer(&client_id)
+            .set_subject(client_id)
+            // A single audience is serialized as a string.
+            .set_audience(vec![audience.into()])
+            .set_expires_in(Duration::from_secs(60))
+            .set_random_jwt_id();
+
+        Self {
+            context: JwtContext::new(),
+            builder,
+            header: JwsHeader::new(),
+        }
+    }
+
+    /// Set a lifetime of the assertion.
+    ///
+    /// # Arguments
+    ///
+    /// * `expires_in` - a lifetime from the issued time.
+    pub fn set_expires_in(&mut self, expires_in: Duration) {
+        self.builder.set_expires_in(expires_in);
+    }
+
+    /// Set a value for issued at claim (iat). The default value is the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issued time
+    pub fn set_issued_at(&mut self, value: SystemTime) {
+        self.builder.set_issued_at(value);
+    }
+
+    /// Set a value for JWT ID claim (jti). The default value is a random string.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a JWT ID
+    pub fn set_jwt_id(&mut self, value: impl Into<String>) {
+        self.builder.set_jwt_id(value);
+    }
+
+    /// Set a value for key ID header claim (kid).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.header.set_key_id(value);
+    }
+
+    /// Return the payload and the header of the assertion.
+    pub fn build(&self) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        let payload = self.builder.build_payload()?;
+        Ok((payload, self.header.clone()))
+    }
+
+    /// Return the string repsentation of the assertion signed by the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object like a HMAC signer for client_secret_jwt.
+    pub fn sign(&self, signer: &dyn JwsSigner) -> Result<String, JoseError> {
+        let (payload, header) = self.build()?;
+        self.context.encode_with_signer(&payload, &header, signer)
+    }
+}
@@ -0,0 +1,284 @@
+use std::convert::Into;
+use std::sync::Arc;
+
+use anyhow::bail;
+
+use crate::jws::{JwsHeader, JwsVerifier};
+use crate::jwt::{JwtContext, JwtPayload, JwtPayloadValidator, ReplayGuard};
+use crate::{JoseError, Value};
+
+/// Represents client assertion validator for authorization servers (RFC 7523).
+#[derive(Debug)]
+pub struct ClientAssertionValidator {
+    context: JwtContext,
+    payload_validator: JwtPayloadValidator,
+    client_id: String,
+    issuer: String,
+    audiences: Vec<String>,
+    issuer_audience_only: bool,
+}
+
+impl ClientAssertionValidator {
+    /// Return a new ClientAssertionValidator.
+    ///
+    /// The issuer identifier is always acceptable as the audience.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - a client ID of the authenticating client.
+    /// * `issuer` - a issuer identifier of the authorization server.
+    pub fn new(client_id: impl Into<String>, issuer: impl Into<String>) -> Self {
+        let mut payload_validator = JwtPayloadValidator::new();
+        payload_validator.add_required_claim("jti");
+
+        Self {
+            context: JwtContext::new(),
+            payload_validator,
+            client_id: client_id.into(),
+            issuer: issuer.into(),
+            audiences: Vec::new(),
+            issuer_audience_only: false,
+        }
+    }
+
+    /// Return the mutable JWT context that is used for decoding.
+    pub fn context_mut(&mut self) -> &mut JwtContext {
+        &mut self.context
+    }
+
+    /// Return the mutable payload validator that checks the time claims.
+    pub fn payload_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.payload_validator
+    }
+
+    /// Set a replay guard that rejects a assertion with a jti seen before.
+    ///
+    /// The jti is combined with the iss claim as a replay key.
+    ///
+    /// # Arguments
+    ///
+    /// * `guard` - a replay guard that is shared among validators.
+    pub fn set_replay_guard(&mut self, guard: Arc<dyn ReplayGuard>) {
+        self.payload_validator.set_replay_guard(guard);
+        self.payload_validator.set_replay_key_with_issuer(true);
+    }
+
+    /// Add a audience that is acceptable in addition to the issuer identifier
+    /// like the token endpoint URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a acceptable audience
+    pub fn add_audience(&mut self, value: impl Into<String>) {
+        self.audiences.push(value.into());
+    }
+
+    /// Set whether only the issuer identifier as a string is acceptable as the audience
+    /// like FAPI 2.0.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - true if only the issuer identifier is acceptable.
+    pub fn set_issuer_audience_only(&mut self, value: bool) {
+        self.issuer_audience_only = value;
+    }
+
+    /// Return the assertion decoded and validated by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a assertion string representation.
+    /// * `verifier` - a verifier of the client key or secret.
+    pub fn decode_with_verifier(
+        &self,
+        input: impl AsRef<[u8]>,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        self.decode_with_verifier_selector(input, |_header| Ok(Some(verifier)))
+    }
+
+    /// Return the assertion decoded and validated with a selected verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a assertion string representation.
+    /// * `selector` - a function for selecting the verifying algorithm.
+    pub fn decode_with_verifier_selector<'a, F>(
+        &self,
+        input: impl AsRef<[u8]>,
+        selector: F,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError>
+    where
+        F: Fn(&JwsHeader) -> Result<Option<&'a dyn JwsVerifier>, JoseError>,
+    {
+        let (payload, header) = self
+            .context
+            .decode_with_verifier_selector(input, selector)?;
+        self.validate(&payload, &header)?;
+        Ok((payload, header))
+    }
+
+    /// Validate a decoded assertion.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - a decoded assertion payload.
+    /// * `header` - a decoded assertion header.
+    pub fn validate(&self, payload: &JwtPayload, header: &JwsHeader) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            if let Some("none") | None = header.algorithm() {
+                bail!("The client assertion must be signed.");
+            }
+
+            match payload.issuer() {
+                Some(val) if val == self.client_id => {}
+                Some(val) => bail!(JoseError::IssuerMismatch(val.to_string())),
+                None => bail!(JoseError::MissingClaim("iss".to_string())),
+            }
+
+            match payload.subject() {
+                Some(val) if val == self.client_id => {}
+                Some(val) => bail!("Key sub is invalid: {}", val),
+                None => bail!(JoseError::MissingClaim("sub".to_string())),
+            }
+
+            if self.issuer_audience_only {
+                match payload.claim("aud") {
+                    Some(Value::String(val)) if val == &self.issuer => {}
+                    Some(Value::String(val)) => {
+                        bail!(JoseError::AudienceMismatch(vec![val.to_string()]))
+                    }
+                    Some(val) => bail!("Key aud must be a string: {}", val),
+                    None => bail!(JoseError::MissingClaim("aud".to_string())),
+                }
+            } else {
+                match payload.audience() {
+                    Some(vals) => {
+                        let acceptable = vals.iter().any(|val| {
+                            *val == self.issuer || self.audiences.iter().any(|val2| val2 == val)
+                        });
+                        if !acceptable {
+                            bail!(JoseError::AudienceMismatch(
+                                vals.iter().map(|val| val.to_string()).collect(),
+                            ));
+                        }
+                    }
+                    None => bail!(JoseError::MissingClaim("aud".to_string())),
+                }
+            }
+
+            if payload.expires_at().is_none() {
+                bail!(JoseError::MissingClaim("exp".to_string()));
+            }
+            self.payload_validator.validate(payload)?;
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+
+    use crate::jwk::alg::ec::EcCurve;
+    use crate::jwk::Jwk;
+    use crate::jws::{ES256, HS256};
+    use crate::jwt::MemoryReplayGuard;
+    use crate::oauth::{ClientAssertionBuilder, ClientAssertionValidator};
+    use crate::util;
+    use crate::JoseError;
+
+    #[test]
+    fn test_private_key_jwt() -> Result<()> {
+        let jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let signer = ES256.signer_from_jwk(&jwk)?;
+        let verifier = ES256.verifier_from_jwk(&jwk.to_public_key()?)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut builder = ClientAssertionBuilder::new("client1", "https://as.example.com/token");
+        builder.set_issued_at(now);
+        let assertion = builder.sign(&signer)?;
+
+        let mut validator = ClientAssertionValidator::new("client1", "https://as.example.com");
+        validator.payload_validator_mut().set_base_time(now);
+        validator.set_replay_guard(Arc::new(MemoryReplayGuard::new()));
+        assert!(matches!(
+            validator.decode_with_verifier(&assertion, &verifier),
+            Err(JoseError::AudienceMismatch(_))
+        ));
+
+        validator.add_audience("https://as.example.com/token");
+        let (payload, _) = validator.decode_with_verifier(&assertion, &verifier)?;
+        assert_eq!(payload.issuer(), Some("client1"));
+        assert_eq!(payload.subject(), Some("client1"));
+        assert_eq!(payload.expires_at(), Some(now + Duration::from_secs(60)));
+        assert!(payload.jwt_id().is_some());
+
+        // replayed
+        assert!(validator
+            .decode_with_verifier(&assertion, &verifier)
+            .is_err());
+
+        let mut validator2 = ClientAssertionValidator::new("client2", "https://as.example.com");
+        validator2.payload_validator_mut().set_base_time(now);
+        validator2.add_audience("https://as.example.com/token");
+        assert!(matches!(
+            validator2.decode_with_verifier(&assertion, &verifier),
+            Err(JoseError::IssuerMismatch(_))
+        ));
+
+        let mut validator2 = ClientAssertionValidator::new("client1", "https://as.example.com");
+        validator2
+            .payload_validator_mut()
+            .set_base_time(now + Duration::from_secs(61));
+        validator2.add_audience("https://as.example.com/token");
+        assert!(matches!(
+            validator2.decode_with_verifier(&assertion, &verifier),
+            Err(JoseError::Expired { .. })
+        ));
+
+        builder.set_expires_in(Duration::MAX);
+        assert!(matches!(builder.build(), Err(JoseError::InvalidClaim(_))));
+        assert!(matches!(
+            builder.sign(&signer),
+            Err(JoseError::InvalidClaim(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_secret_jwt_with_issuer_audience() -> Result<()> {
+        let secret = util::random_bytes(32);
+        let signer = HS256.signer_from_bytes(&secret)?;
+        let verifier = HS256.verifier_from_bytes(&secret)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut validator = ClientAssertionValidator::new("client1", "https://as.example.com");
+        validator.payload_validator_mut().set_base_time(now);
+        validator.set_issuer_audience_only(true);
+        validator.add_audience("https://as.example.com/token");
+
+        let mut builder = ClientAssertionBuilder::new("client1", "https://as.example.com");
+        builder.set_issued_at(now);
+        let assertion = builder.sign(&signer)?;
+        validator.decode_with_verifier(&assertion, &verifier)?;
+
+        let mut builder = ClientAssertionBuilder::new("client1", "https://as.example.com/token");
+        builder.set_issued_at(now);
+        let assertion = builder.sign(&signer)?;
+        assert!(validator
+            .decode_with_verifier(&assertion, &verifier)
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use crate::jws::JwsHeader;
+use crate::jwt::{Confirmation, JwtPayload};
+use crate::Value;
+
+/// Represents a decoded and validated JWT access token (RFC 9068).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct JwtAccessToken {
+    payload: JwtPayload,
+    header: JwsHeader,
+}
+
+impl JwtAccessToken {
+    pub(crate) fn new(payload: JwtPayload, header: JwsHeader) -> Self {
+        Self { payload, header }
+    }
+
+    /// Return the payload of the access token.
+    pub fn payload(&self) -> &JwtPayload {
+        &self.payload
+    }
+
+    /// Return the header of the access token.
+    pub fn header(&self) -> &JwsHeader {
+        &self.header
+    }
+
+    /// Return the value for client identifier claim (client_id).
+    pub fn client_id(&self) -> Option<&str> {
+        match self.payload.claim("client_id") {
+            Some(Value::String(val)) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Return the scopes that is parsed from the space-delimited scope claim.
+    pub fn scopes(&self) -> Vec<&str> {
+        match self.payload.claim("scope") {
+            Some(Value::String(val)) => val.split_whitespace().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Test the scope claim contains a specified scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - a scope value
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes().contains(&scope)
+    }
+
+    /// Return the values of groups claim.
+    pub fn groups(&self) -> Vec<&str> {
+        self.attribute_values("groups")
+    }
+
+    /// Return the values of roles claim.
+    pub fn roles(&self) -> Vec<&str> {
+        self.attribute_values("roles")
+    }
+
+    /// Return the values of entitlements claim.
+    pub fn entitlements(&self) -> Vec<&str> {
+        self.attribute_values("entitlements")
+    }
+
+    /// Return the confirmation claim (cnf) of a sender-constrained access token.
+    pub fn confirmation(&self) -> Option<Confirmation> {
+        self.payload.confirmation()
+    }
+
+    /// Return the payload that is consumed.
+    pub fn into_payload(self) -> JwtPayload {
+        self.payload
+    }
+
+    /// Return the values of a SCIM attribute claim that is a array of strings
+    /// or a array of objects that have a value member.
+    fn attribute_values(&self, key: &str) -> Vec<&str> {
+        let mut vec = Vec::new();
+        if let Some(Value::Array(vals)) = self.payload.claim(key) {
+            for val in vals {
+                match val {
+                    Value::String(val) => vec.push(val.as_str()),
+                    Value::Object(map) => {
+                        if let Some(Value::String(val)) = map.get("value") {
+                            vec.push(val.as_str());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        vec
+    }
+}
@@ -0,0 +1,166 @@
+use std::convert::Into;
+use std::time::{Duration, SystemTime};
+
+use crate::jws::{JwsHeader, JwsSigner};
+use crate::jwt::{self, JwtBuilder, JwtPayload};
+use crate::oauth::ACCESS_TOKEN_TYPE;
+use crate::{JoseError, Value};
+
+/// Represents JWT access token builder (RFC 9068).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct JwtAccessTokenBuilder {
+    builder: JwtBuilder,
+    header: JwsHeader,
+}
+
+impl JwtAccessTokenBuilder {
+    /// Return a new JwtAccessTokenBuilder.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a issuer identifier of the authorization server.
+    /// * `subject` - a subject of the access token.
+    /// * `client_id` - a client identifier of the OAuth client.
+    pub fn new(
+        issuer: impl Into<String>,
+        subject: impl Into<String>,
+        client_id: impl Into<String>,
+    ) -> Self {
+        let mut builder = JwtBuilder::new();
+        builder
+            .set_issuer(issuer)
+            .set_subject(subject)
+            .set_random_jwt_id()
+            .set_claim("client_id", Some(Value::String(client_id.into())))
+            .unwrap();
+
+        let mut header = JwsHeader::new();
+        header.set_token_type(ACCESS_TOKEN_TYPE);
+
+        Self { builder, header }
+    }
+
+    /// Set values for audience claim (aud).
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - resource indicators of the resource servers.
+    pub fn set_audience(&mut self, values: Vec<impl Into<String>>) {
+        self.builder.set_audience(values);
+    }
+
+    /// Set a lifetime of the access token.
+    ///
+    /// # Arguments
+    ///
+    /// * `expires_in` - a lifetime from the issued time.
+    pub fn set_expires_in(&mut self, expires_in: Duration) {
+        self.builder.set_expires_in(expires_in);
+    }
+
+    /// Set a value for issued at claim (iat). The default value is the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issued time
+    pub fn set_issued_at(&mut self, value: SystemTime) {
+        self.builder.set_issued_at(value);
+    }
+
+    /// Set a value for JWT ID claim (jti). The default value is a random string.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a JWT ID
+    pub fn set_jwt_id(&mut self, value: impl Into<String>) {
+        self.builder.set_jwt_id(value);
+    }
+
+    /// Set a value for key ID header claim (kid).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.header.set_key_id(value);
+    }
+
+    /// Set values for scope claim. The values are joined with spaces.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - scope values
+    pub fn set_scopes(&mut self, values: Vec<impl Into<String>>) {
+        let values: Vec<String> = values.into_iter().map(|val| val.into()).collect();
+        self.set_raw_claim("scope", Value::String(values.join(" ")));
+    }
+
+    /// Set values for groups claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - group names
+    pub fn set_groups(&mut self, values: Vec<impl Into<String>>) {
+        self.set_string_array("groups", values);
+    }
+
+    /// Set values for roles claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - role names
+    pub fn set_roles(&mut self, values: Vec<impl Into<String>>) {
+        self.set_string_array("roles", values);
+    }
+
+    /// Set values for entitlements claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - entitlement names
+    pub fn set_entitlements(&mut self, values: Vec<impl Into<String>>) {
+        self.set_string_array("entitlements", values);
+    }
+
+    /// Set a value for a other claim like auth_time or acr.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of claim
+    /// * `value` - a value of claim
+    pub fn set_claim(&mut self, key: &str, value: Option<Value>) -> Result<(), JoseError> {
+        self.builder.set_claim(key, value)?;
+        Ok(())
+    }
+
+    /// Return the payload and the header of the access token.
+    pub fn build(&self) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        let payload = self.builder.build_payload()?;
+        if payload.audience().is_none() {
+            return Err(JoseError::MissingClaim("aud".to_string()));
+        }
+        Ok((payload, self.header.clone()))
+    }
+
+    /// Return the string repsentation of the access token signed by the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    pub fn sign(&self, signer: &dyn JwsSigner) -> Result<String, JoseError> {
+        let (payload, header) = self.build()?;
+        jwt::encode_with_signer(&payload, &header, signer)
+    }
+
+    fn set_string_array(&mut self, key: &str, values: Vec<impl Into<String>>) {
+        let values: Vec<Value> = values
+            .into_iter()
+            .map(|val| Value::String(val.into()))
+            .collect();
+        self.set_raw_claim(key, Value::Array(values));
+    }
+
+    fn set_raw_claim(&mut self, key: &str, value: Value) {
+        self.builder.set_claim(key, Some(value)).unwrap();
+    }
+}
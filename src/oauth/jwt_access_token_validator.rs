@@ -0,0 +1,350 @@
+use std::convert::Into;
+
+use anyhow::bail;
+
+use crate::jws::{JwsHeader, JwsVerifier};
+use crate::jwt::{JwtContext, JwtHeaderValidator, JwtPayload, JwtPayloadValidator};
+use crate::oauth::{JwtAccessToken, ACCESS_TOKEN_TYPE};
+use crate::{JoseError, Value};
+
+/// Represents JWT access token validator for resource servers (RFC 9068).
+#[derive(Debug)]
+pub struct JwtAccessTokenValidator {
+    context: JwtContext,
+    payload_validator: JwtPayloadValidator,
+    issuer: String,
+    audience: String,
+    required_scopes: Vec<String>,
+}
+
+impl JwtAccessTokenValidator {
+    /// Return a new JwtAccessTokenValidator.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a expected issuer identifier of the authorization server.
+    /// * `audience` - a resource indicator of the resource server.
+    pub fn new(issuer: impl Into<String>, audience: impl Into<String>) -> Self {
+        let mut header_validator = JwtHeaderValidator::new();
+        header_validator.set_token_type(ACCESS_TOKEN_TYPE);
+
+        let mut context = JwtContext::new();
+        context.set_header_validator(Some(header_validator));
+
+        Self {
+            context,
+            payload_validator: JwtPayloadValidator::new(),
+            issuer: issuer.into(),
+            audience: audience.into(),
+            required_scopes: Vec::new(),
+        }
+    }
+
+    /// Return the JWT context that is used for decoding.
+    pub fn context(&self) -> &JwtContext {
+        &self.context
+    }
+
+    /// Return the mutable JWT context that is used for decoding.
+    ///
+    /// The header validator of the context checks the typ header claim by default.
+    pub fn context_mut(&mut self) -> &mut JwtContext {
+        &mut self.context
+    }
+
+    /// Return the payload validator that checks the time claims.
+    pub fn payload_validator(&self) -> &JwtPayloadValidator {
+        &self.payload_validator
+    }
+
+    /// Return the mutable payload validator that checks the time claims.
+    pub fn payload_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.payload_validator
+    }
+
+    /// Return the expected issuer identifier.
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Return the resource indicator of the resource server.
+    pub fn audience(&self) -> &str {
+        &self.audience
+    }
+
+    /// Add a scope that the access token must contain.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - a scope value
+    pub fn add_required_scope(&mut self, scope: impl Into<String>) {
+        self.required_scopes.push(scope.into());
+    }
+
+    /// Return the scopes that the access token must contain.
+    pub fn required_scopes(&self) -> Vec<&str> {
+        self.required_scopes
+            .iter()
+            .map(|val| val.as_str())
+            .collect()
+    }
+
+    /// Return the access token decoded and validated by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a access token string representation.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_with_verifier(
+        &self,
+        input: impl AsRef<[u8]>,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<JwtAccessToken, JoseError> {
+        self.decode_with_verifier_selector(input, |_header| Ok(Some(verifier)))
+    }
+
+    /// Return the access token decoded and validated with a selected verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a access token string representation.
+    /// * `selector` - a function for selecting the verifying algorithm.
+    pub fn decode_with_verifier_selector<'a, F>(
+        &self,
+        input: impl AsRef<[u8]>,
+        selector: F,
+    ) -> Result<JwtAccessToken, JoseError>
+    where
+        F: Fn(&JwsHeader) -> Result<Option<&'a dyn JwsVerifier>, JoseError>,
+    {
+        let (payload, header) = self
+            .context
+            .decode_with_verifier_selector(input, selector)?;
+        self.validate(&payload, &header)?;
+        Ok(JwtAccessToken::new(payload, header))
+    }
+
+    /// Validate a decoded access token.
+    ///
+    /// The typ header claim must be at+jwt even if the header validator of the context
+    /// is replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - a decoded access token payload.
+    /// * `header` - a decoded access token header.
+    pub fn validate(&self, payload: &JwtPayload, header: &JwsHeader) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            if let Some("none") | None = header.algorithm() {
+                bail!("The access token must be signed.");
+            }
+
+            let mut header_validator = JwtHeaderValidator::new();
+            header_validator.set_token_type(ACCESS_TOKEN_TYPE);
+            header_validator.validate(header)?;
+
+            for key in ["iss", "exp", "aud", "sub", "client_id", "iat", "jti"] {
+                if payload.claim(key).is_none() {
+                    bail!(JoseError::MissingClaim(key.to_string()));
+                }
+            }
+
+            match payload.issuer() {
+                Some(val) if val == self.issuer => {}
+                Some(val) => bail!(JoseError::IssuerMismatch(val.to_string())),
+                None => bail!("Key iss must be a string."),
+            }
+
+            match payload.audience() {
+                Some(vals) if vals.contains(&self.audience.as_str()) => {}
+                Some(vals) => bail!(JoseError::AudienceMismatch(
+                    vals.iter().map(|val| val.to_string()).collect(),
+                )),
+                None => bail!("Key aud must be a string or array of string."),
+            }
+
+            match payload.claim("client_id") {
+                Some(Value::String(_)) => {}
+                Some(val) => bail!("Key client_id must be a string: {}", val),
+                None => {}
+            }
+
+            self.payload_validator.validate(payload)?;
+
+            if !self.required_scopes.is_empty() {
+                let scopes: Vec<&str> = match payload.claim("scope") {
+                    Some(Value::String(val)) => val.split_whitespace().collect(),
+                    Some(val) => bail!("Key scope must be a string: {}", val),
+                    None => bail!(JoseError::MissingClaim("scope".to_string())),
+                };
+                for scope in &self.required_scopes {
+                    if !scopes.contains(&scope.as_str()) {
+                        bail!("Key scope does not contain {}.", scope);
+                    }
+                }
+            }
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::jws::{JwsHeader, ES256};
+    use crate::jwt::{self, JwtPayload};
+    use crate::oauth::{JwtAccessTokenBuilder, JwtAccessTokenValidator};
+    use crate::JoseError;
+
+    #[test]
+    fn test_jwt_access_token() -> Result<()> {
+        let private_key = load_file("pem/EC_P-256_private.pem")?;
+        let public_key = load_file("pem/EC_P-256_public.pem")?;
+        let signer = ES256.signer_from_pem(&private_key)?;
+        let verifier = ES256.verifier_from_pem(&public_key)?;
+
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut builder = JwtAccessTokenBuilder::new("https://as.example.com", "user1", "client1");
+        builder.set_audience(vec!["https://rs.example.com"]);
+        builder.set_issued_at(now);
+        builder.set_expires_in(Duration::from_secs(300));
+        builder.set_scopes(vec!["openid", "read", "write"]);
+        builder.set_groups(vec!["staff"]);
+        builder.set_roles(vec!["admin", "user"]);
+        builder.set_entitlements(vec!["premium"]);
+        builder.set_claim("acr", Some(json!("urn:example:mfa")))?;
+        let access_token = builder.sign(&signer)?;
+
+        let mut validator =
+            JwtAccessTokenValidator::new("https://as.example.com", "https://rs.example.com");
+        validator.payload_validator_mut().set_base_time(now);
+        validator.add_required_scope("read");
+        let token = validator.decode_with_verifier(&access_token, &verifier)?;
+
+        assert_eq!(token.header().token_type(), Some("at+jwt"));
+        assert_eq!(token.payload().subject(), Some("user1"));
+        assert_eq!(token.client_id(), Some("client1"));
+        assert_eq!(token.scopes(), vec!["openid", "read", "write"]);
+        assert!(token.has_scope("write"));
+        assert_eq!(token.groups(), vec!["staff"]);
+        assert_eq!(token.roles(), vec!["admin", "user"]);
+        assert_eq!(token.entitlements(), vec!["premium"]);
+        assert!(token.payload().jwt_id().is_some());
+        assert_eq!(
+            token.payload().expires_at(),
+            Some(now + Duration::from_secs(300))
+        );
+
+        validator.add_required_scope("delete");
+        assert!(validator
+            .decode_with_verifier(&access_token, &verifier)
+            .is_err());
+
+        let mut validator2 =
+            JwtAccessTokenValidator::new("https://as.example.com", "https://other.example.com");
+        validator2.payload_validator_mut().set_base_time(now);
+        assert!(matches!(
+            validator2.decode_with_verifier(&access_token, &verifier),
+            Err(JoseError::AudienceMismatch(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jwt_access_token_rejects_other_token_type() -> Result<()> {
+        let private_key = load_file("pem/EC_P-256_private.pem")?;
+        let public_key = load_file("pem/EC_P-256_public.pem")?;
+        let signer = ES256.signer_from_pem(&private_key)?;
+        let verifier = ES256.verifier_from_pem(&public_key)?;
+
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut builder = JwtAccessTokenBuilder::new("https://as.example.com", "user1", "client1");
+        builder.set_audience(vec!["https://rs.example.com"]);
+        builder.set_issued_at(now);
+        builder.set_expires_in(Duration::from_secs(300));
+        let (payload, _) = builder.build()?;
+
+        let mut header = JwsHeader::new();
+        header.set_token_type("JWT");
+        let id_token = jwt::encode_with_signer(&payload, &header, &signer)?;
+
+        let mut validator =
+            JwtAccessTokenValidator::new("https://as.example.com", "https://rs.example.com");
+        validator.payload_validator_mut().set_base_time(now);
+        assert!(validator
+            .decode_with_verifier(&id_token, &verifier)
+            .is_err());
+
+        let mut payload2 = JwtPayload::from_map(payload.claims_set().clone())?;
+        payload2.set_claim("client_id", None)?;
+        header.set_token_type("application/at+jwt");
+        let access_token = jwt::encode_with_signer(&payload2, &header, &signer)?;
+        assert!(matches!(
+            validator.decode_with_verifier(&access_token, &verifier),
+            Err(JoseError::MissingClaim(ref val)) if val == "client_id"
+        ));
+
+        let mut validator2 =
+            JwtAccessTokenValidator::new("https://as.example.com", "https://rs.example.com");
+        validator2.payload_validator_mut().set_base_time(now);
+        validator2.context_mut().set_header_validator(None);
+        let (decoded_payload, decoded_header) = validator2
+            .context()
+            .decode_with_verifier(&id_token, &verifier)?;
+        assert!(matches!(
+            validator2.validate(&decoded_payload, &decoded_header),
+            Err(JoseError::InvalidJwtFormat(_))
+        ));
+        assert!(matches!(
+            validator2.decode_with_verifier(&id_token, &verifier),
+            Err(JoseError::InvalidJwtFormat(_))
+        ));
+
+        header.set_token_type("AT+JWT");
+        let access_token = jwt::encode_with_signer(&payload, &header, &signer)?;
+        validator2.decode_with_verifier(&access_token, &verifier)?;
+
+        header.set_token_type("aéééééé");
+        let access_token = jwt::encode_with_signer(&payload, &header, &signer)?;
+        assert!(matches!(
+            validator.decode_with_verifier(&access_token, &verifier),
+            Err(JoseError::InvalidJwtFormat(_))
+        ));
+        assert!(matches!(
+            validator2.decode_with_verifier(&access_token, &verifier),
+            Err(JoseError::InvalidJwtFormat(_))
+        ));
+
+        let mut builder = JwtAccessTokenBuilder::new("https://as.example.com", "user1", "client1");
+        assert!(matches!(
+            builder.build(),
+            Err(JoseError::MissingClaim(ref val)) if val == "exp"
+        ));
+        builder.set_expires_in(Duration::MAX);
+        assert!(matches!(builder.build(), Err(JoseError::InvalidClaim(_))));
+
+        Ok(())
+    }
+
+    fn load_file(path: &str) -> Result<Vec<u8>> {
+        let mut pb = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        pb.push("data");
+        pb.push(path);
+
+        let data = fs::read(&pb)?;
+        Ok(data)
+    }
+}
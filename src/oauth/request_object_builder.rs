@@ -0,0 +1,158 @@
+use std::convert::Into;
+use std::time::{Duration, SystemTime};
+
+use anyhow::bail;
+
+use crate::jwe::{JweEncrypter, JweHeader};
+use crate::jws::{JwsHeader, JwsSigner};
+use crate::jwt::{JwtContext, JwtPayload};
+use crate::oauth::REQUEST_OBJECT_TYPE;
+use crate::util;
+use crate::{JoseError, Value};
+
+/// Represents JWT-secured authorization request object builder (RFC 9101).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RequestObjectBuilder {
+    context: JwtContext,
+    payload: JwtPayload,
+    header: JwsHeader,
+    expires_in: Duration,
+    issued_at: Option<SystemTime>,
+}
+
+impl RequestObjectBuilder {
+    /// Return a new RequestObjectBuilder.
+    ///
+    /// The client_id and iss claims are set to the client ID,
+    /// and the request object expires in 5 minutes by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - a client ID of the OAuth client.
+    /// * `audience` - a issuer identifier of the authorization server.
+    pub fn new(client_id: impl Into<String>, audience: impl Into<String>) -> Self {
+        let client_id: String = client_id.into();
+
+        let mut payload = JwtPayload::new();
+        payload.set_issuer(&client_id);
+        payload.set_audience(vec![audience.into()]);
+        payload
+            .set_claim("client_id", Some(Value::String(client_id)))
+            .unwrap();
+
+        let mut header = JwsHeader::new();
+        header.set_token_type(REQUEST_OBJECT_TYPE);
+
+        Self {
+            context: JwtContext::new(),
+            payload,
+            header,
+            expires_in: Duration::from_secs(300),
+            issued_at: None,
+        }
+    }
+
+    /// Set a value for a authorization request parameter like response_type or redirect_uri.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a parameter name
+    /// * `value` - a parameter value
+    pub fn set_parameter(&mut self, key: &str, value: Option<Value>) -> Result<(), JoseError> {
+        self.payload.set_claim(key, value)
+    }
+
+    /// Return a value for a authorization request parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a parameter name
+    pub fn parameter(&self, key: &str) -> Option<&Value> {
+        self.payload.claim(key)
+    }
+
+    /// Set a lifetime of the request object.
+    ///
+    /// # Arguments
+    ///
+    /// * `expires_in` - a lifetime from the issued time.
+    pub fn set_expires_in(&mut self, expires_in: Duration) {
+        self.expires_in = expires_in;
+    }
+
+    /// Set a value for issued at claim (iat) and not before claim (nbf).
+    /// The default value is the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issued time
+    pub fn set_issued_at(&mut self, value: SystemTime) {
+        self.issued_at = Some(value);
+    }
+
+    /// Set a value for key ID header claim (kid).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.header.set_key_id(value);
+    }
+
+    /// Return the payload and the header of the request object.
+    pub fn build(&self) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        (|| -> anyhow::Result<(JwtPayload, JwsHeader)> {
+            let mut payload = self.payload.clone();
+
+            let issued_at = match self.issued_at {
+                Some(val) => val,
+                None => SystemTime::now(),
+            };
+            let expires_at = match issued_at.checked_add(self.expires_in) {
+                Some(val) => val,
+                None => bail!("The expiration time is out of range."),
+            };
+            payload.set_issued_at(&issued_at);
+            payload.set_not_before(&issued_at);
+            payload.set_expires_at(&expires_at);
+            if payload.jwt_id().is_none() {
+                payload.set_jwt_id(util::encode_base64_urlsafe_nopad(util::random_bytes(16)));
+            }
+
+            Ok((payload, self.header.clone()))
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+
+    /// Return the string repsentation of the request object signed by the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    pub fn sign(&self, signer: &dyn JwsSigner) -> Result<String, JoseError> {
+        let (payload, header) = self.build()?;
+        self.context.encode_with_signer(&payload, &header, signer)
+    }
+
+    /// Return the string repsentation of the request object signed by the signer
+    /// and encrypted by the encrypter.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    /// * `jwe_header` - The JWE heaser claims.
+    /// * `encrypter` - a encrypter object.
+    pub fn sign_and_encrypt(
+        &self,
+        signer: &dyn JwsSigner,
+        jwe_header: &JweHeader,
+        encrypter: &dyn JweEncrypter,
+    ) -> Result<String, JoseError> {
+        let (payload, header) = self.build()?;
+        self.context
+            .encode_nested(&payload, &header, signer, jwe_header, encrypter)
+    }
+}
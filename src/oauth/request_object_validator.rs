@@ -0,0 +1,340 @@
+use std::convert::Into;
+use std::time::Duration;
+
+use anyhow::bail;
+
+use crate::jwe::JweDecrypter;
+use crate::jws::{JwsHeader, JwsVerifier};
+use crate::jwt::{JwtContext, JwtHeaderValidator, JwtPayload, JwtPayloadValidator};
+use crate::oauth::REQUEST_OBJECT_TYPE;
+use crate::{JoseError, Value};
+
+/// Represents JWT-secured authorization request object validator (RFC 9101).
+#[derive(Debug)]
+pub struct RequestObjectValidator {
+    context: JwtContext,
+    payload_validator: JwtPayloadValidator,
+    issuer: String,
+    client_id: String,
+    token_type_required: bool,
+    max_lifetime: Option<Duration>,
+}
+
+impl RequestObjectValidator {
+    /// Return a new RequestObjectValidator.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a issuer identifier of the authorization server.
+    /// * `client_id` - a client_id parameter of the authorization request.
+    pub fn new(issuer: impl Into<String>, client_id: impl Into<String>) -> Self {
+        Self {
+            context: JwtContext::new(),
+            payload_validator: JwtPayloadValidator::new(),
+            issuer: issuer.into(),
+            client_id: client_id.into(),
+            token_type_required: false,
+            max_lifetime: None,
+        }
+    }
+
+    /// Return the mutable JWT context that is used for decoding.
+    pub fn context_mut(&mut self) -> &mut JwtContext {
+        &mut self.context
+    }
+
+    /// Return the mutable payload validator that checks the time claims.
+    pub fn payload_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.payload_validator
+    }
+
+    /// Set whether the typ header claim "oauth-authz-req+jwt" is required.
+    ///
+    /// Even if it is not required, the other value of typ header claim is rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - true if the typ header claim is required.
+    pub fn set_token_type_required(&mut self, value: bool) {
+        self.token_type_required = value;
+    }
+
+    /// Set a maximum lifetime between the nbf and exp claims like 60 minutes of FAPI.
+    ///
+    /// The nbf and exp claims are required.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a maximum lifetime
+    pub fn set_max_lifetime(&mut self, value: Duration) {
+        self.max_lifetime = Some(value);
+    }
+
+    /// Return the request object decoded and validated by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a request object string representation.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_with_verifier(
+        &self,
+        input: impl AsRef<[u8]>,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        let (payload, header) = self.context.decode_with_verifier(input, verifier)?;
+        self.validate(&payload, &header)?;
+        Ok((payload, header))
+    }
+
+    /// Return the nested request object decoded by the decrypter and validated by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a request object string representation.
+    /// * `decrypter` - a decrypter of the decrypting algorithm.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_nested(
+        &self,
+        input: impl AsRef<[u8]>,
+        decrypter: &dyn JweDecrypter,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        let (payload, header, _) = self.context.decode_nested(input, decrypter, verifier)?;
+        self.validate(&payload, &header)?;
+        Ok((payload, header))
+    }
+
+    /// Validate a decoded request object.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - a decoded request object payload.
+    /// * `header` - a decoded request object header.
+    pub fn validate(&self, payload: &JwtPayload, header: &JwsHeader) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            if let Some("none") | None = header.algorithm() {
+                bail!("The request object must be signed.");
+            }
+
+            if self.token_type_required || header.token_type().is_some() {
+                let mut header_validator = JwtHeaderValidator::new();
+                header_validator.set_token_type(REQUEST_OBJECT_TYPE);
+                header_validator.validate(header)?;
+            }
+
+            match payload.claim("client_id") {
+                Some(Value::String(val)) => {
+                    if val != &self.client_id {
+                        bail!("Key client_id is invalid: {}", val);
+                    }
+                }
+                Some(val) => bail!("Key client_id must be a string: {}", val),
+                None => bail!(JoseError::MissingClaim("client_id".to_string())),
+            }
+
+            match payload.issuer() {
+                Some(val) if val == self.client_id => {}
+                Some(val) => bail!(JoseError::IssuerMismatch(val.to_string())),
+                None => bail!(JoseError::MissingClaim("iss".to_string())),
+            }
+
+            match payload.audience() {
+                Some(vals) if vals.contains(&self.issuer.as_str()) => {}
+                Some(vals) => bail!(JoseError::AudienceMismatch(
+                    vals.iter().map(|val| val.to_string()).collect(),
+                )),
+                None => bail!(JoseError::MissingClaim("aud".to_string())),
+            }
+
+            for key in ["request", "request_uri"] {
+                if payload.claim(key).is_some() {
+                    bail!("The request object must not contain {}.", key);
+                }
+            }
+
+            if let Some(max_lifetime) = self.max_lifetime {
+                let not_before = match payload.not_before() {
+                    Some(val) => val,
+                    None => bail!(JoseError::MissingClaim("nbf".to_string())),
+                };
+                let expires_at = match payload.expires_at() {
+                    Some(val) => val,
+                    None => bail!(JoseError::MissingClaim("exp".to_string())),
+                };
+                match not_before.checked_add(max_lifetime) {
+                    Some(val) if expires_at <= val => {}
+                    _ => bail!("The lifetime of the request object is too long."),
+                }
+            }
+
+            self.payload_validator.validate(payload)?;
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::jwe::{JweHeader, RSA_OAEP};
+    use crate::jws::{JwsHeader, PS256};
+    use crate::jwt::{self, JwtPayload};
+    use crate::oauth::{RequestObjectBuilder, RequestObjectValidator};
+    use crate::JoseError;
+
+    #[test]
+    fn test_request_object() -> Result<()> {
+        let private_key = load_file("pem/RSA-PSS_2048bit_SHA-256_private.pem")?;
+        let public_key = load_file("pem/RSA-PSS_2048bit_SHA-256_public.pem")?;
+        let signer = PS256.signer_from_pem(&private_key)?;
+        let verifier = PS256.verifier_from_pem(&public_key)?;
+
+        let private_key = load_file("pem/RSA_2048bit_private.pem")?;
+        let public_key = load_file("pem/RSA_2048bit_public.pem")?;
+        let encrypter = RSA_OAEP.encrypter_from_pem(&public_key)?;
+        let decrypter = RSA_OAEP.decrypter_from_pem(&private_key)?;
+
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut builder = RequestObjectBuilder::new("client1", "https://as.example.com");
+        builder.set_issued_at(now);
+        builder.set_parameter("response_type", Some(json!("code")))?;
+        builder.set_parameter("redirect_uri", Some(json!("https://client.example.com/cb")))?;
+        builder.set_parameter("state", Some(json!("af0ifjsldkj")))?;
+        let request_object = builder.sign(&signer)?;
+
+        let mut jwe_header = JweHeader::new();
+        jwe_header.set_content_encryption("A256GCM");
+        let encrypted_request_object =
+            builder.sign_and_encrypt(&signer, &jwe_header, &encrypter)?;
+
+        let mut validator = RequestObjectValidator::new("https://as.example.com", "client1");
+        validator.payload_validator_mut().set_base_time(now);
+        validator.set_token_type_required(true);
+        validator.set_max_lifetime(Duration::from_secs(3600));
+
+        let (payload, header) = validator.decode_with_verifier(&request_object, &verifier)?;
+        assert_eq!(header.token_type(), Some("oauth-authz-req+jwt"));
+        assert_eq!(payload.claim("state"), Some(&json!("af0ifjsldkj")));
+
+        let (payload2, _) =
+            validator.decode_nested(&encrypted_request_object, &decrypter, &verifier)?;
+        assert_eq!(payload2.claim("state"), Some(&json!("af0ifjsldkj")));
+
+        let mut validator2 = RequestObjectValidator::new("https://as.example.com", "client2");
+        validator2.payload_validator_mut().set_base_time(now);
+        assert!(validator2
+            .decode_with_verifier(&request_object, &verifier)
+            .is_err());
+
+        let mut validator2 = RequestObjectValidator::new("https://other.example.com", "client1");
+        validator2.payload_validator_mut().set_base_time(now);
+        assert!(matches!(
+            validator2.decode_with_verifier(&request_object, &verifier),
+            Err(JoseError::AudienceMismatch(_))
+        ));
+
+        builder.set_expires_in(Duration::from_secs(7200));
+        let long_request_object = builder.sign(&signer)?;
+        assert!(validator
+            .decode_with_verifier(&long_request_object, &verifier)
+            .is_err());
+
+        builder.set_expires_in(Duration::MAX);
+        assert!(matches!(builder.build(), Err(JoseError::InvalidClaim(_))));
+        assert!(matches!(
+            builder.sign_and_encrypt(&signer, &jwe_header, &encrypter),
+            Err(JoseError::InvalidClaim(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_object_rules() -> Result<()> {
+        let private_key = load_file("pem/RSA-PSS_2048bit_SHA-256_private.pem")?;
+        let public_key = load_file("pem/RSA-PSS_2048bit_SHA-256_public.pem")?;
+        let signer = PS256.signer_from_pem(&private_key)?;
+        let verifier = PS256.verifier_from_pem(&public_key)?;
+
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut builder = RequestObjectBuilder::new("client1", "https://as.example.com");
+        builder.set_issued_at(now);
+        let (payload, _) = builder.build()?;
+
+        let mut validator = RequestObjectValidator::new("https://as.example.com", "client1");
+        validator.payload_validator_mut().set_base_time(now);
+
+        let mut header = JwsHeader::new();
+        header.set_token_type("JWT");
+        let jwt_string = jwt::encode_with_signer(&payload, &header, &signer)?;
+        assert!(validator
+            .decode_with_verifier(&jwt_string, &verifier)
+            .is_err());
+
+        header.set_token_type("application/oauth-authz-req+jwt");
+        let jwt_string = jwt::encode_with_signer(&payload, &header, &signer)?;
+        validator.decode_with_verifier(&jwt_string, &verifier)?;
+
+        header.set_token_type("aéééééé");
+        let jwt_string = jwt::encode_with_signer(&payload, &header, &signer)?;
+        assert!(matches!(
+            validator.decode_with_verifier(&jwt_string, &verifier),
+            Err(JoseError::InvalidJwtFormat(_))
+        ));
+
+        let jwt_string = jwt::encode_with_signer(&payload, &JwsHeader::new(), &signer)?;
+        validator.decode_with_verifier(&jwt_string, &verifier)?;
+        validator.set_token_type_required(true);
+        assert!(validator
+            .decode_with_verifier(&jwt_string, &verifier)
+            .is_err());
+        validator.set_token_type_required(false);
+
+        let mut payload2 = JwtPayload::from_map(payload.claims_set().clone())?;
+        payload2.set_issuer("client2");
+        let jwt_string = jwt::encode_with_signer(&payload2, &JwsHeader::new(), &signer)?;
+        assert!(matches!(
+            validator.decode_with_verifier(&jwt_string, &verifier),
+            Err(JoseError::IssuerMismatch(_))
+        ));
+
+        let mut payload2 = JwtPayload::from_map(payload.claims_set().clone())?;
+        payload2.set_claim("request_uri", Some(json!("https://client.example.com/r")))?;
+        let jwt_string = jwt::encode_with_signer(&payload2, &JwsHeader::new(), &signer)?;
+        assert!(validator
+            .decode_with_verifier(&jwt_string, &verifier)
+            .is_err());
+
+        let mut payload2 = JwtPayload::from_map(payload.claims_set().clone())?;
+        payload2.set_claim("nbf", Some(json!(i64::MAX)))?;
+        payload2.set_claim("exp", Some(json!(i64::MAX)))?;
+        let jwt_string = jwt::encode_with_signer(&payload2, &JwsHeader::new(), &signer)?;
+        validator.set_max_lifetime(Duration::from_secs(3600));
+        assert!(validator
+            .decode_with_verifier(&jwt_string, &verifier)
+            .is_err());
+
+        Ok(())
+    }
+
+    fn load_file(path: &str) -> Result<Vec<u8>> {
+        let mut pb = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        pb.push("data");
+        pb.push(path);
+
+        let data = fs::read(&pb)?;
+        Ok(data)
+    }
+}
@@ -0,0 +1,105 @@
+//! OpenID Connect support.
+
+mod id_token_validator;
+mod logout_token_validator;
+
+pub use crate::oidc::id_token_validator::IdTokenValidator;
+pub use crate::oidc::logout_token_validator::LogoutTokenValidator;
+
+use anyhow::bail;
+use openssl::hash::MessageDigest;
+
+use crate::jwk::alg::ed::EdCurve;
+use crate::util::{self, HashAlgorithm};
+use crate::JoseError;
+
+/// The media type of a Back-Channel Logout token.
+pub const LOGOUT_TOKEN_TYPE: &str = "logout+jwt";
+
+/// The event URI of a Back-Channel Logout token.
+pub const BACKCHANNEL_LOGOUT_EVENT: &str = "http://schemas.openid.net/event/backchannel-logout";
+
+/// Return the left-most half hash of a value like the at_hash or c_hash claims.
+///
+/// The hash algorithm is decided by the JWS alg header claim of the ID Token.
+/// EdDSA uses SHA-512 for Ed25519 and SHAKE256 for Ed448, so the curve is required.
+///
+/// # Arguments
+///
+/// * `alg` - a JWS algorithm name of the ID Token.
+/// * `curve` - a curve of the signing key if the algorithm is EdDSA.
+/// * `value` - a value like an access token or an authorization code.
+pub fn left_half_hash(
+    alg: &str,
+    curve: Option<EdCurve>,
+    value: impl AsRef<[u8]>,
+) -> Result<String, JoseError> {
+    (|| -> anyhow::Result<String> {
+        let hash_algorithm = match (alg, curve) {
+            ("HS256" | "RS256" | "PS256" | "ES256" | "ES256K", _) => HashAlgorithm::Sha256,
+            ("HS384" | "RS384" | "PS384" | "ES384", _) => HashAlgorithm::Sha384,
+            ("HS512" | "RS512" | "PS512" | "ES512", _) | ("EdDSA", Some(EdCurve::Ed25519)) => {
+                HashAlgorithm::Sha512
+            }
+            ("EdDSA", Some(EdCurve::Ed448)) => {
+                // SHAKE256 with the output size of a Ed448 signature.
+                let mut digest = [0; 114];
+                openssl::hash::hash_xof(MessageDigest::shake_256(), value.as_ref(), &mut digest)?;
+                return Ok(util::encode_base64_urlsafe_nopad(&digest[..57]));
+            }
+            ("EdDSA", None) => bail!("The left half hash of EdDSA requires the curve."),
+            _ => bail!(
+                "The left half hash is not supported for the algorithm: {}",
+                alg
+            ),
+        };
+
+        let digest = openssl::hash::hash(hash_algorithm.message_digest(), value.as_ref())?;
+        let half = &digest[..(hash_algorithm.output_len() / 2)];
+        Ok(util::encode_base64_urlsafe_nopad(half))
+    })()
+    .map_err(JoseError::UnsupportedSignatureAlgorithm)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::jwk::alg::ed::EdCurve;
+    use crate::oidc;
+    use crate::JoseError;
+
+    #[test]
+    fn test_left_half_hash() -> Result<()> {
+        // OpenID Connect Core 1.0 Appendix A.3
+        let access_token = "jHkWEdUXMU1BwAsC4vtUsZwnNvTIxEl0z9K3vx5KF0Y";
+        assert_eq!(
+            oidc::left_half_hash("RS256", None, access_token)?,
+            "77QmUPtjPfzWtF2AnpK9RQ"
+        );
+
+        // OpenID Connect Core 1.0 Appendix A.4
+        let code = "Qcb0Orv1zh30vL1MPRsbm-diHiMwcLyZvn1arpZv-Jxf_11jnpEX3Tgfvk";
+        assert_eq!(
+            oidc::left_half_hash("RS256", None, code)?,
+            "LDktKdoQak3Pk0cnXxCltA"
+        );
+
+        assert_eq!(oidc::left_half_hash("ES384", None, "token")?.len(), 32);
+        assert_eq!(
+            oidc::left_half_hash("EdDSA", Some(EdCurve::Ed25519), "token")?,
+            oidc::left_half_hash("RS512", None, "token")?
+        );
+        assert_eq!(
+            oidc::left_half_hash("EdDSA", Some(EdCurve::Ed448), "token")?,
+            "ZpsVsPHyCxw-L854tH1BSHo_uyeotZeU9GcDngReCYbbGANfhQ0WKs70DNeb3r5aEWB26f_H-CGq"
+        );
+        assert!(matches!(
+            oidc::left_half_hash("EdDSA", None, "token"),
+            Err(JoseError::UnsupportedSignatureAlgorithm(_))
+        ));
+        assert!(oidc::left_half_hash("none", None, "token").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,639 @@
+use std::convert::Into;
+use std::time::{Duration, SystemTime};
+
+use anyhow::bail;
+
+use crate::jwk::alg::ed::EdCurve;
+use crate::jws::{JwsHeader, JwsVerifier};
+use crate::jwt::{JwtContext, JwtPayload, JwtPayloadValidator};
+use crate::oidc;
+use crate::{JoseError, Value};
+
+/// Represents OpenID Connect ID Token validator.
+///
+/// The validator follows the rules of OpenID Connect Core 1.0 section 3.1.3.7.
+#[derive(Debug)]
+pub struct IdTokenValidator {
+    context: JwtContext,
+    payload_validator: JwtPayloadValidator,
+    issuer: String,
+    client_id: String,
+    trusted_audiences: Vec<String>,
+    nonce: Option<String>,
+    max_age: Option<Duration>,
+    acr_values: Option<Vec<String>>,
+    access_token: Option<String>,
+    at_hash_required: bool,
+    code: Option<String>,
+    c_hash_required: bool,
+    eddsa_curve: Option<EdCurve>,
+}
+
+impl IdTokenValidator {
+    /// Return a new IdTokenValidator.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a expected issuer identifier of the OpenID Provider.
+    /// * `client_id` - a client ID of the Relying Party.
+    pub fn new(issuer: impl Into<String>, client_id: impl Into<String>) -> Self {
+        Self {
+            context: JwtContext::new(),
+            payload_validator: JwtPayloadValidator::new(),
+            issuer: issuer.into(),
+            client_id: client_id.into(),
+            trusted_audiences: Vec::new(),
+            nonce: None,
+            max_age: None,
+            acr_values: None,
+            access_token: None,
+            at_hash_required: false,
+            code: None,
+            c_hash_required: false,
+            eddsa_curve: None,
+        }
+    }
+
+    /// Return the JWT context that is used for decoding.
+    pub fn context(&self) -> &JwtContext {
+        &self.context
+    }
+
+    /// Return the mutable JWT context that is used for decoding.
+    pub fn context_mut(&mut self) -> &mut JwtContext {
+        &mut self.context
+    }
+
+    /// Return the payload validator that checks the time claims.
+    pub fn payload_validator(&self) -> &JwtPayloadValidator {
+        &self.payload_validator
+    }
+
+    /// Return the mutable payload validator that checks the time claims.
+    pub fn payload_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.payload_validator
+    }
+
+    /// Return the expected issuer identifier.
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Return the client ID of the Relying Party.
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Add a audience that is allowed in addition to the client ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a trusted audience
+    pub fn add_trusted_audience(&mut self, value: impl Into<String>) {
+        self.trusted_audiences.push(value.into());
+    }
+
+    /// Return the audiences that is allowed in addition to the client ID.
+    pub fn trusted_audiences(&self) -> Vec<&str> {
+        self.trusted_audiences
+            .iter()
+            .map(|val| val.as_str())
+            .collect()
+    }
+
+    /// Set a nonce value that is sent in the authentication request.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a nonce value
+    pub fn set_nonce(&mut self, value: impl Into<String>) {
+        self.nonce = Some(value.into());
+    }
+
+    /// Return the nonce value that is sent in the authentication request.
+    pub fn nonce(&self) -> Option<&str> {
+        self.nonce.as_deref()
+    }
+
+    /// Set a max_age value that is sent in the authentication request.
+    ///
+    /// The auth_time claim is required and the authentication must be within the max age.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_age` - a allowable elapsed time since the End-User authentication.
+    pub fn set_max_age(&mut self, max_age: Duration) {
+        self.max_age = Some(max_age);
+    }
+
+    /// Return the max_age value that is sent in the authentication request.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// Set acceptable values of the acr claim.
+    ///
+    /// The acr claim is required and must be one of the values.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - acceptable authentication context class references.
+    pub fn set_acr_values(&mut self, values: Vec<impl Into<String>>) {
+        let values: Vec<String> = values.into_iter().map(|val| val.into()).collect();
+        self.acr_values = Some(values);
+    }
+
+    /// Return the acceptable values of the acr claim.
+    pub fn acr_values(&self) -> Option<Vec<&str>> {
+        self.acr_values
+            .as_ref()
+            .map(|vals| vals.iter().map(|val| val.as_str()).collect())
+    }
+
+    /// Set a access token that is issued with the ID Token for at_hash validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a access token
+    /// * `required` - true if the at_hash claim is required like the implicit flow.
+    pub fn set_access_token(&mut self, value: impl Into<String>, required: bool) {
+        self.access_token = Some(value.into());
+        self.at_hash_required = required;
+    }
+
+    /// Return the access token for at_hash validation.
+    pub fn access_token(&self) -> Option<&str> {
+        self.access_token.as_deref()
+    }
+
+    /// Set a authorization code that is issued with the ID Token for c_hash validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a authorization code
+    /// * `required` - true if the c_hash claim is required like the hybrid flow.
+    pub fn set_code(&mut self, value: impl Into<String>, required: bool) {
+        self.code = Some(value.into());
+        self.c_hash_required = required;
+    }
+
+    /// Return the authorization code for c_hash validation.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// Set a curve of the EdDSA signing key.
+    ///
+    /// The at_hash and c_hash claims of a EdDSA ID Token can be validated only if it is set,
+    /// because the hash algorithm depends on the curve.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a curve of the EdDSA signing key.
+    pub fn set_eddsa_curve(&mut self, value: EdCurve) {
+        self.eddsa_curve = Some(value);
+    }
+
+    /// Return the curve of the EdDSA signing key.
+    pub fn eddsa_curve(&self) -> Option<EdCurve> {
+        self.eddsa_curve
+    }
+
+    /// Return the ID Token payload and header decoded and validated by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a ID Token string representation.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_with_verifier(
+        &self,
+        input: impl AsRef<[u8]>,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        self.decode_with_verifier_selector(input, |_header| Ok(Some(verifier)))
+    }
+
+    /// Return the ID Token payload and header decoded and validated with a selected verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a ID Token string representation.
+    /// * `selector` - a function for selecting the verifying algorithm.
+    pub fn decode_with_verifier_selector<'a, F>(
+        &self,
+        input: impl AsRef<[u8]>,
+        selector: F,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError>
+    where
+        F: Fn(&JwsHeader) -> Result<Option<&'a dyn JwsVerifier>, JoseError>,
+    {
+        let (payload, header) = self
+            .context
+            .decode_with_verifier_selector(input, selector)?;
+        self.validate(&payload, &header)?;
+        Ok((payload, header))
+    }
+
+    /// Validate a decoded ID Token.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - a decoded ID Token payload.
+    /// * `header` - a decoded ID Token header.
+    pub fn validate(&self, payload: &JwtPayload, header: &JwsHeader) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            let alg = match header.algorithm() {
+                Some("none") | None => bail!("The ID Token must be signed."),
+                Some(val) => val,
+            };
+
+            match payload.issuer() {
+                Some(val) if val == self.issuer => {}
+                Some(val) => bail!(JoseError::IssuerMismatch(val.to_string())),
+                None => bail!(JoseError::MissingClaim("iss".to_string())),
+            }
+
+            let audiences = match payload.audience() {
+                Some(val) => val,
+                None => bail!(JoseError::MissingClaim("aud".to_string())),
+            };
+            let untrusted = audiences.iter().any(|val| {
+                *val != self.client_id && !self.trusted_audiences.iter().any(|val2| val2 == val)
+            });
+            if !audiences.contains(&self.client_id.as_str()) || untrusted {
+                bail!(JoseError::AudienceMismatch(
+                    audiences.iter().map(|val| val.to_string()).collect(),
+                ));
+            }
+
+            match payload.claim("azp") {
+                Some(Value::String(val)) => {
+                    if val != &self.client_id {
+                        bail!("Key azp is invalid: {}", val);
+                    }
+                }
+                Some(val) => bail!("Key azp must be a string: {}", val),
+                None => {
+                    if audiences.len() > 1 {
+                        bail!(JoseError::MissingClaim("azp".to_string()));
+                    }
+                }
+            }
+
+            if payload.subject().is_none() {
+                bail!(JoseError::MissingClaim("sub".to_string()));
+            }
+            if payload.expires_at().is_none() {
+                bail!(JoseError::MissingClaim("exp".to_string()));
+            }
+            if payload.issued_at().is_none() {
+                bail!(JoseError::MissingClaim("iat".to_string()));
+            }
+            self.payload_validator.validate(payload)?;
+
+            if let Some(expected) = &self.nonce {
+                match payload.claim("nonce") {
+                    Some(Value::String(val)) => {
+                        if val != expected {
+                            bail!("Key nonce is invalid: {}", val);
+                        }
+                    }
+                    Some(val) => bail!("Key nonce must be a string: {}", val),
+                    None => bail!(JoseError::MissingClaim("nonce".to_string())),
+                }
+            }
+
+            if let Some(acr_values) = &self.acr_values {
+                match payload.claim("acr") {
+                    Some(Value::String(val)) => {
+                        if !acr_values.iter().any(|val2| val2 == val) {
+                            bail!("Key acr is invalid: {}", val);
+                        }
+                    }
+                    Some(val) => bail!("Key acr must be a string: {}", val),
+                    None => bail!(JoseError::MissingClaim("acr".to_string())),
+                }
+            }
+
+            let auth_time = match payload.claim("auth_time") {
+                Some(Value::Number(val)) => match val.as_u64() {
+                    Some(val) => match SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(val))
+                    {
+                        Some(val) => Some(val),
+                        None => bail!("Key auth_time is out of range: {}", val),
+                    },
+                    None => bail!("Key auth_time must be a positive integer: {}", val),
+                },
+                Some(val) => bail!("Key auth_time must be a number: {}", val),
+                None => None,
+            };
+
+            if let Some(max_age) = self.max_age {
+                let auth_time = match auth_time {
+                    Some(val) => val,
+                    None => bail!(JoseError::MissingClaim("auth_time".to_string())),
+                };
+
+                let now = match self.payload_validator.base_time() {
+                    Some(val) => *val,
+                    None => self.payload_validator.clock().now(),
+                };
+                let limit = auth_time
+                    .checked_add(max_age)
+                    .and_then(|val| val.checked_add(self.payload_validator.expires_at_leeway()));
+                match limit {
+                    Some(val) if val >= now => {}
+                    Some(_) => bail!(
+                        "The End-User authentication is too old: {}",
+                        time::OffsetDateTime::from(auth_time)
+                    ),
+                    None => bail!("Key auth_time is out of range."),
+                }
+            }
+
+            if let Some(access_token) = &self.access_token {
+                self.validate_hash(payload, "at_hash", alg, access_token, self.at_hash_required)?;
+            }
+
+            if let Some(code) = &self.code {
+                self.validate_hash(payload, "c_hash", alg, code, self.c_hash_required)?;
+            }
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+
+    fn validate_hash(
+        &self,
+        payload: &JwtPayload,
+        key: &str,
+        alg: &str,
+        value: &str,
+        required: bool,
+    ) -> anyhow::Result<()> {
+        match payload.claim(key) {
+            Some(Value::String(val)) => {
+                let expected = oidc::left_half_hash(alg, self.eddsa_curve, value)?;
+                if val != &expected {
+                    bail!("Key {} is invalid: {}", key, val);
+                }
+            }
+            Some(val) => bail!("Key {} must be a string: {}", key, val),
+            None => {
+                if required {
+                    bail!(JoseError::MissingClaim(key.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::jwk::alg::ed::EdCurve;
+    use crate::jws::{JwsHeader, RS256};
+    use crate::jwt::{self, JwtPayload};
+    use crate::oidc::{self, IdTokenValidator};
+    use crate::JoseError;
+
+    #[test]
+    fn test_validate_id_token() -> Result<()> {
+        let private_key = load_file("pem/RSA_2048bit_private.pem")?;
+        let public_key = load_file("pem/RSA_2048bit_public.pem")?;
+        let signer = RS256.signer_from_pem(&private_key)?;
+        let verifier = RS256.verifier_from_pem(&public_key)?;
+
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let access_token = "jHkWEdUXMU1BwAsC4vtUsZwnNvTIxEl0z9K3vx5KF0Y";
+
+        let mut payload = JwtPayload::new();
+        payload.set_issuer("https://server.example.com");
+        payload.set_subject("248289761001");
+        payload.set_audience(vec!["s6BhdRkqt3"]);
+        payload.set_issued_at(&now);
+        payload.set_expires_at(&(now + Duration::from_secs(600)));
+        payload.set_claim("nonce", Some(json!("n-0S6_WzA2Mj")))?;
+        payload.set_claim("auth_time", Some(json!(1_700_000_000 - 60)))?;
+        payload.set_claim("acr", Some(json!("urn:mace:incommon:iap:silver")))?;
+        payload.set_claim(
+            "at_hash",
+            Some(json!(oidc::left_half_hash("RS256", None, access_token)?)),
+        )?;
+        let id_token = jwt::encode_with_signer(&payload, &JwsHeader::new(), &signer)?;
+
+        let mut validator = IdTokenValidator::new("https://server.example.com", "s6BhdRkqt3");
+        validator.payload_validator_mut().set_base_time(now);
+        validator.set_nonce("n-0S6_WzA2Mj");
+        validator.set_max_age(Duration::from_secs(300));
+        validator.set_acr_values(vec!["urn:mace:incommon:iap:silver"]);
+        validator.set_access_token(access_token, true);
+        let (dst_payload, _) = validator.decode_with_verifier(&id_token, &verifier)?;
+        assert_eq!(dst_payload, payload);
+
+        let mut validator2 = IdTokenValidator::new("https://other.example.com", "s6BhdRkqt3");
+        validator2.payload_validator_mut().set_base_time(now);
+        assert!(matches!(
+            validator2.decode_with_verifier(&id_token, &verifier),
+            Err(JoseError::IssuerMismatch(_))
+        ));
+
+        let mut validator2 = IdTokenValidator::new("https://server.example.com", "other");
+        validator2.payload_validator_mut().set_base_time(now);
+        assert!(matches!(
+            validator2.decode_with_verifier(&id_token, &verifier),
+            Err(JoseError::AudienceMismatch(_))
+        ));
+
+        let mut validator2 = IdTokenValidator::new("https://server.example.com", "s6BhdRkqt3");
+        validator2.payload_validator_mut().set_base_time(now);
+        validator2.set_nonce("other");
+        assert!(validator2
+            .decode_with_verifier(&id_token, &verifier)
+            .is_err());
+
+        let mut validator2 = IdTokenValidator::new("https://server.example.com", "s6BhdRkqt3");
+        validator2.payload_validator_mut().set_base_time(now);
+        validator2.set_max_age(Duration::from_secs(30));
+        assert!(validator2
+            .decode_with_verifier(&id_token, &verifier)
+            .is_err());
+
+        let mut validator2 = IdTokenValidator::new("https://server.example.com", "s6BhdRkqt3");
+        validator2.payload_validator_mut().set_base_time(now);
+        validator2.set_acr_values(vec!["urn:mace:incommon:iap:gold"]);
+        assert!(validator2
+            .decode_with_verifier(&id_token, &verifier)
+            .is_err());
+
+        let mut validator2 = IdTokenValidator::new("https://server.example.com", "s6BhdRkqt3");
+        validator2.payload_validator_mut().set_base_time(now);
+        validator2.set_access_token("other", false);
+        assert!(validator2
+            .decode_with_verifier(&id_token, &verifier)
+            .is_err());
+
+        let mut validator2 = IdTokenValidator::new("https://server.example.com", "s6BhdRkqt3");
+        validator2.payload_validator_mut().set_base_time(now);
+        validator2.set_code("code", true);
+        assert!(matches!(
+            validator2.decode_with_verifier(&id_token, &verifier),
+            Err(JoseError::MissingClaim(ref val)) if val == "c_hash"
+        ));
+
+        let mut validator2 = IdTokenValidator::new("https://server.example.com", "s6BhdRkqt3");
+        validator2
+            .payload_validator_mut()
+            .set_base_time(now + Duration::from_secs(601));
+        assert!(matches!(
+            validator2.decode_with_verifier(&id_token, &verifier),
+            Err(JoseError::Expired { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_id_token_requires_subject() -> Result<()> {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut header = JwsHeader::new();
+        header.set_algorithm("RS256");
+        let mut payload = JwtPayload::new();
+        payload.set_issuer("https://server.example.com");
+        payload.set_audience(vec!["client1"]);
+        payload.set_issued_at(&now);
+        payload.set_expires_at(&(now + Duration::from_secs(600)));
+
+        let mut validator = IdTokenValidator::new("https://server.example.com", "client1");
+        validator.payload_validator_mut().set_base_time(now);
+        assert!(matches!(
+            validator.validate(&payload, &header),
+            Err(JoseError::MissingClaim(ref val)) if val == "sub"
+        ));
+
+        payload.set_subject("248289761001");
+        validator.validate(&payload, &header)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_id_token_with_out_of_range_auth_time() -> Result<()> {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut header = JwsHeader::new();
+        header.set_algorithm("RS256");
+        let mut payload = JwtPayload::new();
+        payload.set_issuer("https://server.example.com");
+        payload.set_subject("248289761001");
+        payload.set_audience(vec!["client1"]);
+        payload.set_issued_at(&now);
+        payload.set_expires_at(&(now + Duration::from_secs(600)));
+
+        let mut validator = IdTokenValidator::new("https://server.example.com", "client1");
+        validator.payload_validator_mut().set_base_time(now);
+        validator.set_max_age(Duration::from_secs(300));
+
+        for auth_time in [json!(u64::MAX), json!(i64::MAX)] {
+            payload.set_claim("auth_time", Some(auth_time))?;
+            assert!(validator.validate(&payload, &header).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_id_token_with_multiple_audiences() -> Result<()> {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut header = JwsHeader::new();
+        header.set_algorithm("RS256");
+        let mut payload = JwtPayload::new();
+        payload.set_issuer("https://server.example.com");
+        payload.set_subject("248289761001");
+        payload.set_audience(vec!["client1", "api1"]);
+        payload.set_issued_at(&now);
+        payload.set_expires_at(&(now + Duration::from_secs(600)));
+
+        let mut validator = IdTokenValidator::new("https://server.example.com", "client1");
+        validator.payload_validator_mut().set_base_time(now);
+        assert!(validator.validate(&payload, &header).is_err());
+
+        validator.add_trusted_audience("api1");
+        assert!(matches!(
+            validator.validate(&payload, &header),
+            Err(JoseError::MissingClaim(ref val)) if val == "azp"
+        ));
+
+        payload.set_claim("azp", Some(json!("client1")))?;
+        validator.validate(&payload, &header)?;
+
+        payload.set_claim("azp", Some(json!("client2")))?;
+        assert!(validator.validate(&payload, &header).is_err());
+
+        header.set_algorithm("none");
+        assert!(validator.validate(&payload, &header).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_id_token_eddsa_hash() -> Result<()> {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let access_token = "jHkWEdUXMU1BwAsC4vtUsZwnNvTIxEl0z9K3vx5KF0Y";
+
+        let mut header = JwsHeader::new();
+        header.set_algorithm("EdDSA");
+        let mut payload = JwtPayload::new();
+        payload.set_issuer("https://server.example.com");
+        payload.set_subject("248289761001");
+        payload.set_audience(vec!["client1"]);
+        payload.set_issued_at(&now);
+        payload.set_expires_at(&(now + Duration::from_secs(600)));
+        payload.set_claim(
+            "at_hash",
+            Some(json!(oidc::left_half_hash(
+                "EdDSA",
+                Some(EdCurve::Ed448),
+                access_token
+            )?)),
+        )?;
+
+        let mut validator = IdTokenValidator::new("https://server.example.com", "client1");
+        validator.payload_validator_mut().set_base_time(now);
+        validator.set_access_token(access_token, true);
+        assert!(matches!(
+            validator.validate(&payload, &header),
+            Err(JoseError::UnsupportedSignatureAlgorithm(_))
+        ));
+
+        validator.set_eddsa_curve(EdCurve::Ed25519);
+        assert!(validator.validate(&payload, &header).is_err());
+
+        validator.set_eddsa_curve(EdCurve::Ed448);
+        validator.validate(&payload, &header)?;
+
+        Ok(())
+    }
+
+    fn load_file(path: &str) -> Result<Vec<u8>> {
+        let mut pb = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        pb.push("data");
+        pb.push(path);
+
+        let data = fs::read(&pb)?;
+        Ok(data)
+    }
+}
@@ -0,0 +1,252 @@
+use std::convert::Into;
+
+use anyhow::bail;
+
+use crate::jws::{JwsHeader, JwsVerifier};
+use crate::jwt::{JwtContext, JwtHeaderValidator, JwtPayload, JwtPayloadValidator};
+use crate::oidc::{BACKCHANNEL_LOGOUT_EVENT, LOGOUT_TOKEN_TYPE};
+use crate::secevent;
+use crate::{JoseError, Value};
+
+/// Represents OpenID Connect Back-Channel Logout token validator.
+///
+/// The validator follows the rules of OpenID Connect Back-Channel Logout 1.0 section 2.6.
+#[derive(Debug)]
+pub struct LogoutTokenValidator {
+    context: JwtContext,
+    payload_validator: JwtPayloadValidator,
+    issuer: String,
+    client_id: String,
+}
+
+impl LogoutTokenValidator {
+    /// Return a new LogoutTokenValidator.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a expected issuer identifier of the OpenID Provider.
+    /// * `client_id` - a client ID of the Relying Party.
+    pub fn new(issuer: impl Into<String>, client_id: impl Into<String>) -> Self {
+        let mut payload_validator = JwtPayloadValidator::new();
+        payload_validator.add_required_claim("iat");
+        payload_validator.add_required_claim("exp");
+        payload_validator.add_required_claim("jti");
+
+        Self {
+            context: JwtContext::new(),
+            payload_validator,
+            issuer: issuer.into(),
+            client_id: client_id.into(),
+        }
+    }
+
+    /// Return the mutable JWT context that is used for decoding.
+    pub fn context_mut(&mut self) -> &mut JwtContext {
+        &mut self.context
+    }
+
+    /// Return the mutable payload validator that checks the time claims.
+    ///
+    /// A replay guard can be set to reject a jti that is seen before.
+    pub fn payload_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.payload_validator
+    }
+
+    /// Return the logout token decoded and validated by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a logout token string representation.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_with_verifier(
+        &self,
+        input: impl AsRef<[u8]>,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        let (payload, header) = self.context.decode_with_verifier(input, verifier)?;
+        self.validate(&payload, &header)?;
+        Ok((payload, header))
+    }
+
+    /// Validate a decoded logout token.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - a decoded logout token payload.
+    /// * `header` - a decoded logout token header.
+    pub fn validate(&self, payload: &JwtPayload, header: &JwsHeader) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            if let Some("none") | None = header.algorithm() {
+                bail!("The logout token must be signed.");
+            }
+
+            if let Some(val) = header.token_type() {
+                let accepted = [LOGOUT_TOKEN_TYPE, "JWT"].iter().any(|token_type| {
+                    let mut header_validator = JwtHeaderValidator::new();
+                    header_validator.set_token_type(*token_type);
+                    header_validator.validate(header).is_ok()
+                });
+                if !accepted {
+                    bail!("The typ header claim is invalid: {}", val);
+                }
+            }
+
+            match payload.issuer() {
+                Some(val) if val == self.issuer => {}
+                Some(val) => bail!(JoseError::IssuerMismatch(val.to_string())),
+                None => bail!(JoseError::MissingClaim("iss".to_string())),
+            }
+
+            match payload.audience() {
+                Some(vals) if vals.contains(&self.client_id.as_str()) => {}
+                Some(vals) => bail!(JoseError::AudienceMismatch(
+                    vals.iter().map(|val| val.to_string()).collect(),
+                )),
+                None => bail!(JoseError::MissingClaim("aud".to_string())),
+            }
+
+            let events = secevent::check_events(payload)?;
+            if !events.contains_key(BACKCHANNEL_LOGOUT_EVENT) {
+                bail!("Key events must contain {}.", BACKCHANNEL_LOGOUT_EVENT);
+            }
+
+            let has_subject = payload.subject().is_some();
+            match payload.claim("sid") {
+                Some(Value::String(_)) => {}
+                Some(val) => bail!("Key sid must be a string: {}", val),
+                None => {
+                    if !has_subject {
+                        bail!("Either key sub or sid is required.");
+                    }
+                }
+            }
+
+            if payload.claim("nonce").is_some() {
+                bail!("Key nonce must not be used in the logout token.");
+            }
+
+            self.payload_validator.validate(payload)?;
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::jws::HS256;
+    use crate::oidc::{LogoutTokenValidator, BACKCHANNEL_LOGOUT_EVENT, LOGOUT_TOKEN_TYPE};
+    use crate::secevent::SecurityEventTokenBuilder;
+    use crate::util;
+    use crate::Map;
+
+    #[test]
+    fn test_logout_token() -> Result<()> {
+        let secret = util::random_bytes(32);
+        let signer = HS256.signer_from_bytes(&secret)?;
+        let verifier = HS256.verifier_from_bytes(&secret)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut builder =
+            SecurityEventTokenBuilder::new("https://server.example.com", vec!["client1"]);
+        builder.set_token_type(LOGOUT_TOKEN_TYPE);
+        builder.set_issued_at(now);
+        builder.add_event(BACKCHANNEL_LOGOUT_EVENT, Map::new());
+        builder.set_claim("exp", Some(json!(1_700_000_120)))?;
+        builder.set_claim("sid", Some(json!("08a5019c-17e1-4977-8f42-65a12843ea02")))?;
+        let logout_token = builder.sign(&signer)?;
+
+        let mut validator = LogoutTokenValidator::new("https://server.example.com", "client1");
+        validator.payload_validator_mut().set_base_time(now);
+        let (payload, _) = validator.decode_with_verifier(&logout_token, &verifier)?;
+        assert_eq!(
+            payload.claim("sid"),
+            Some(&json!("08a5019c-17e1-4977-8f42-65a12843ea02"))
+        );
+
+        let mut builder2 = builder.clone();
+        builder2.set_claim("sid", None)?;
+        let logout_token = builder2.sign(&signer)?;
+        assert!(validator
+            .decode_with_verifier(&logout_token, &verifier)
+            .is_err());
+        builder2.set_claim("sub", Some(json!("248289761001")))?;
+        let logout_token = builder2.sign(&signer)?;
+        validator.decode_with_verifier(&logout_token, &verifier)?;
+
+        let mut builder2 = builder.clone();
+        builder2.set_claim("nonce", Some(json!("n-0S6_WzA2Mj")))?;
+        let logout_token = builder2.sign(&signer)?;
+        assert!(validator
+            .decode_with_verifier(&logout_token, &verifier)
+            .is_err());
+
+        let mut builder2 = builder.clone();
+        builder2.set_claim("exp", None)?;
+        let logout_token = builder2.sign(&signer)?;
+        assert!(validator
+            .decode_with_verifier(&logout_token, &verifier)
+            .is_err());
+
+        let mut builder2 =
+            SecurityEventTokenBuilder::new("https://server.example.com", vec!["client1"]);
+        builder2.set_issued_at(now);
+        builder2.add_event("https://example.com/other-event", Map::new());
+        builder2.set_claim("exp", Some(json!(1_700_000_120)))?;
+        builder2.set_claim("sid", Some(json!("sid")))?;
+        let logout_token = builder2.sign(&signer)?;
+        assert!(validator
+            .decode_with_verifier(&logout_token, &verifier)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_logout_token_type() -> Result<()> {
+        let secret = util::random_bytes(32);
+        let signer = HS256.signer_from_bytes(&secret)?;
+        let verifier = HS256.verifier_from_bytes(&secret)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut builder =
+            SecurityEventTokenBuilder::new("https://server.example.com", vec!["client1"]);
+        builder.set_issued_at(now);
+        builder.add_event(BACKCHANNEL_LOGOUT_EVENT, Map::new());
+        builder.set_claim("exp", Some(json!(1_700_000_120)))?;
+        builder.set_claim("sid", Some(json!("sid")))?;
+
+        let mut validator = LogoutTokenValidator::new("https://server.example.com", "client1");
+        validator.payload_validator_mut().set_base_time(now);
+
+        for typ in [
+            "logout+jwt",
+            "application/LOGOUT+JWT",
+            "JWT",
+            "application/jwt",
+        ] {
+            builder.set_token_type(typ);
+            let logout_token = builder.sign(&signer)?;
+            validator.decode_with_verifier(&logout_token, &verifier)?;
+        }
+
+        for typ in ["secevent+jwt", "aéééééé"] {
+            builder.set_token_type(typ);
+            let logout_token = builder.sign(&signer)?;
+            assert!(validator
+                .decode_with_verifier(&logout_token, &verifier)
+                .is_err());
+        }
+
+        Ok(())
+    }
+}
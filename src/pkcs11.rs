@@ -0,0 +1,148 @@
+//! PKCS#11 hardware token support.
+//!
+//! The signers and decrypters pass only the messages and the encrypted keys
+//! to the token, so the private keys never leave it.
+//! They are created from a key object by the algorithms,
+//! e.g. `ES256.signer_from_pkcs11(key)` or `RSA_OAEP.decrypter_from_pkcs11(key)`.
+
+mod ffi;
+mod pkcs11_jwe_decrypter;
+mod pkcs11_jws_signer;
+mod pkcs11_module;
+
+pub use crate::pkcs11::pkcs11_jwe_decrypter::Pkcs11JweDecrypter;
+pub use crate::pkcs11::pkcs11_jws_signer::Pkcs11JwsSigner;
+pub use crate::pkcs11::pkcs11_module::{Pkcs11Key, Pkcs11Module, Pkcs11Session};
+
+pub(crate) use crate::pkcs11::pkcs11_jws_signer::Pkcs11SignatureScheme;
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+    #[allow(deprecated)]
+    use crate::jwe::{JweHeader, RSA1_5, RSA_OAEP, RSA_OAEP_256, RSA_OAEP_384, RSA_OAEP_512};
+    use crate::jws::{
+        EdDSA, JwsHeader, ES256, ES384, ES512, PS256, PS384, PS512, RS256, RS384, RS512,
+    };
+
+    /// Runs against a token which the private keys in data/pem are imported into
+    /// with the file names as labels, and is skipped unless JOSEKIT_PKCS11_MODULE is set.
+    /// e.g. for SoftHSM:
+    ///
+    /// ```sh
+    /// softhsm2-util --init-token --free --label josekit --pin 1234 --so-pin 1234
+    /// softhsm2-util --import data/pem/RSA_2048bit_private.pem --token josekit \
+    ///     --label RSA_2048bit --id 01 --pin 1234
+    /// # ... and EC_P-256, EC_P-384, EC_P-521 and ED25519 in the same way.
+    /// JOSEKIT_PKCS11_MODULE=/usr/lib/softhsm/libsofthsm2.so JOSEKIT_PKCS11_PIN=1234 \
+    ///     cargo test --features pkcs11 pkcs11
+    /// ```
+    #[test]
+    #[allow(deprecated)]
+    fn sign_and_decrypt_with_pkcs11_token() -> Result<()> {
+        let module_path = match env::var("JOSEKIT_PKCS11_MODULE") {
+            Ok(val) => val,
+            Err(_) => return Ok(()),
+        };
+        let module = Pkcs11Module::load(module_path)?;
+        let pin = env::var("JOSEKIT_PKCS11_PIN")?;
+        let slot_id = match module.slot_ids()?.first() {
+            Some(val) => *val,
+            None => panic!("No token is present."),
+        };
+        let session = module.open_session(slot_id, Some(&pin))?;
+
+        let input = b"abcde12345";
+
+        for alg in &[RS256, RS384, RS512] {
+            let key = session.find_private_key_by_label("RSA_2048bit")?;
+            let signer = alg.signer_from_pkcs11(key)?;
+            let signature = signer.sign(input)?;
+
+            let public_key = load_file("pem/RSA_2048bit_public.pem")?;
+            let verifier = alg.verifier_from_pem(&public_key)?;
+            verifier.verify(input, &signature)?;
+        }
+
+        for alg in &[PS256, PS384, PS512] {
+            let key = session.find_private_key_by_label("RSA_2048bit")?;
+            let signer = alg.signer_from_pkcs11(key)?;
+            let signature = signer.sign(input)?;
+
+            let public_key = load_file("pem/RSA_2048bit_traditional_public.pem")?;
+            let verifier = alg.verifier_from_pem(&public_key)?;
+            verifier.verify(input, &signature)?;
+        }
+
+        for (alg, label) in &[
+            (ES256, "EC_P-256"),
+            (ES384, "EC_P-384"),
+            (ES512, "EC_P-521"),
+        ] {
+            let key = session.find_private_key_by_label(label)?;
+            let signer = alg.signer_from_pkcs11(key)?;
+            let signature = signer.sign(input)?;
+            assert_eq!(signature.len(), signer.signature_len());
+
+            let public_key = load_file(&format!("pem/{}_public.pem", label))?;
+            let verifier = alg.verifier_from_pem(&public_key)?;
+            verifier.verify(input, &signature)?;
+        }
+
+        {
+            let key = session.find_private_key_by_label("ED25519")?;
+            let signer = EdDSA.signer_from_pkcs11(key)?;
+            let signature = signer.sign(input)?;
+
+            let public_key = load_file("pem/ED25519_public.pem")?;
+            let verifier = EdDSA.verifier_from_pem(&public_key)?;
+            verifier.verify(input, &signature)?;
+        }
+
+        for alg in &[RSA1_5, RSA_OAEP, RSA_OAEP_256, RSA_OAEP_384, RSA_OAEP_512] {
+            let mut header = JweHeader::new();
+            header.set_content_encryption("A128CBC-HS256");
+
+            let public_key = load_file("pem/RSA_2048bit_public.pem")?;
+            let encrypter = alg.encrypter_from_pem(&public_key)?;
+            let jwe = crate::jwe::serialize_compact(input, &header, &encrypter)?;
+
+            let key = session.find_private_key_by_label("RSA_2048bit")?;
+            let decrypter = alg.decrypter_from_pkcs11(key)?;
+            let (payload, _) = crate::jwe::deserialize_compact(&jwe, &decrypter)?;
+            assert_eq!(payload, input);
+        }
+
+        {
+            let mut header = JwsHeader::new();
+            header.set_token_type("JWT");
+
+            let key = session.find_private_key_by_label("EC_P-256")?;
+            let signer = ES256.signer_from_pkcs11(key)?;
+            let jws = crate::jws::serialize_compact(input, &header, &signer)?;
+
+            let public_key = load_file("pem/EC_P-256_public.pem")?;
+            let verifier = ES256.verifier_from_pem(&public_key)?;
+            let (payload, _) = crate::jws::deserialize_compact(&jws, &verifier)?;
+            assert_eq!(payload, input);
+        }
+
+        assert!(session.find_private_key_by_label("unknown").is_err());
+
+        Ok(())
+    }
+
+    fn load_file(path: &str) -> Result<Vec<u8>> {
+        let mut pb = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        pb.push("data");
+        pb.push(path);
+
+        let data = fs::read(&pb)?;
+        Ok(data)
+    }
+}
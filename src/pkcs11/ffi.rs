@@ -0,0 +1,239 @@
+//! The subset of the PKCS#11 v2.40 C interface used by this crate.
+
+#![allow(non_camel_case_types, non_snake_case)]
+
+use std::os::raw::{c_uchar, c_ulong, c_void};
+
+pub type CK_ULONG = c_ulong;
+pub type CK_RV = CK_ULONG;
+pub type CK_SLOT_ID = CK_ULONG;
+pub type CK_SESSION_HANDLE = CK_ULONG;
+pub type CK_OBJECT_HANDLE = CK_ULONG;
+pub type CK_FLAGS = CK_ULONG;
+pub type CK_BBOOL = c_uchar;
+
+pub const CKR_OK: CK_RV = 0x0;
+pub const CKR_USER_ALREADY_LOGGED_IN: CK_RV = 0x100;
+pub const CKR_BUFFER_TOO_SMALL: CK_RV = 0x150;
+pub const CKR_CRYPTOKI_ALREADY_INITIALIZED: CK_RV = 0x191;
+
+pub const CKF_OS_LOCKING_OK: CK_FLAGS = 0x2;
+pub const CKF_SERIAL_SESSION: CK_FLAGS = 0x4;
+
+pub const CKU_USER: CK_ULONG = 1;
+
+pub const CKA_CLASS: CK_ULONG = 0x0;
+pub const CKA_LABEL: CK_ULONG = 0x3;
+pub const CKA_KEY_TYPE: CK_ULONG = 0x100;
+pub const CKA_ID: CK_ULONG = 0x102;
+pub const CKA_MODULUS: CK_ULONG = 0x120;
+pub const CKA_EC_PARAMS: CK_ULONG = 0x180;
+
+pub const CKO_PRIVATE_KEY: CK_ULONG = 0x3;
+
+pub const CKK_RSA: CK_ULONG = 0x0;
+pub const CKK_EC: CK_ULONG = 0x3;
+pub const CKK_EC_EDWARDS: CK_ULONG = 0x40;
+
+pub const CKM_RSA_PKCS: CK_ULONG = 0x1;
+pub const CKM_RSA_PKCS_OAEP: CK_ULONG = 0x9;
+pub const CKM_SHA256_RSA_PKCS: CK_ULONG = 0x40;
+pub const CKM_SHA384_RSA_PKCS: CK_ULONG = 0x41;
+pub const CKM_SHA512_RSA_PKCS: CK_ULONG = 0x42;
+pub const CKM_SHA256_RSA_PKCS_PSS: CK_ULONG = 0x43;
+pub const CKM_SHA384_RSA_PKCS_PSS: CK_ULONG = 0x44;
+pub const CKM_SHA512_RSA_PKCS_PSS: CK_ULONG = 0x45;
+pub const CKM_SHA_1: CK_ULONG = 0x220;
+pub const CKM_SHA256: CK_ULONG = 0x250;
+pub const CKM_SHA384: CK_ULONG = 0x260;
+pub const CKM_SHA512: CK_ULONG = 0x270;
+pub const CKM_ECDSA: CK_ULONG = 0x1041;
+pub const CKM_EDDSA: CK_ULONG = 0x1057;
+
+pub const CKG_MGF1_SHA1: CK_ULONG = 0x1;
+pub const CKG_MGF1_SHA256: CK_ULONG = 0x2;
+pub const CKG_MGF1_SHA384: CK_ULONG = 0x3;
+pub const CKG_MGF1_SHA512: CK_ULONG = 0x4;
+
+pub const CKZ_DATA_SPECIFIED: CK_ULONG = 0x1;
+
+// PKCS#11 structures are packed to 1 byte on Windows.
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+pub struct CK_VERSION {
+    pub major: c_uchar,
+    pub minor: c_uchar,
+}
+
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+pub struct CK_ATTRIBUTE {
+    pub type_: CK_ULONG,
+    pub pValue: *mut c_void,
+    pub ulValueLen: CK_ULONG,
+}
+
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+pub struct CK_MECHANISM {
+    pub mechanism: CK_ULONG,
+    pub pParameter: *mut c_void,
+    pub ulParameterLen: CK_ULONG,
+}
+
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+#[derive(Debug, Clone, Copy)]
+pub struct CK_RSA_PKCS_PSS_PARAMS {
+    pub hashAlg: CK_ULONG,
+    pub mgf: CK_ULONG,
+    pub sLen: CK_ULONG,
+}
+
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+#[derive(Debug, Clone, Copy)]
+pub struct CK_RSA_PKCS_OAEP_PARAMS {
+    pub hashAlg: CK_ULONG,
+    pub mgf: CK_ULONG,
+    pub source: CK_ULONG,
+    pub pSourceData: *mut c_void,
+    pub ulSourceDataLen: CK_ULONG,
+}
+
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+pub struct CK_C_INITIALIZE_ARGS {
+    pub CreateMutex: *mut c_void,
+    pub DestroyMutex: *mut c_void,
+    pub LockMutex: *mut c_void,
+    pub UnlockMutex: *mut c_void,
+    pub flags: CK_FLAGS,
+    pub pReserved: *mut c_void,
+}
+
+type Unused = Option<unsafe extern "C" fn()>;
+
+/// The leading part of CK_FUNCTION_LIST up to C_Sign.
+/// The remaining entries are never accessed.
+#[cfg_attr(windows, repr(C, packed(1)))]
+#[cfg_attr(not(windows), repr(C))]
+pub struct CK_FUNCTION_LIST {
+    pub version: CK_VERSION,
+    pub C_Initialize: Option<unsafe extern "C" fn(pInitArgs: *mut c_void) -> CK_RV>,
+    pub C_Finalize: Option<unsafe extern "C" fn(pReserved: *mut c_void) -> CK_RV>,
+    pub C_GetInfo: Unused,
+    pub C_GetFunctionList: Unused,
+    pub C_GetSlotList: Option<
+        unsafe extern "C" fn(
+            tokenPresent: CK_BBOOL,
+            pSlotList: *mut CK_SLOT_ID,
+            pulCount: *mut CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_GetSlotInfo: Unused,
+    pub C_GetTokenInfo: Unused,
+    pub C_GetMechanismList: Unused,
+    pub C_GetMechanismInfo: Unused,
+    pub C_InitToken: Unused,
+    pub C_InitPIN: Unused,
+    pub C_SetPIN: Unused,
+    pub C_OpenSession: Option<
+        unsafe extern "C" fn(
+            slotID: CK_SLOT_ID,
+            flags: CK_FLAGS,
+            pApplication: *mut c_void,
+            Notify: *mut c_void,
+            phSession: *mut CK_SESSION_HANDLE,
+        ) -> CK_RV,
+    >,
+    pub C_CloseSession: Option<unsafe extern "C" fn(hSession: CK_SESSION_HANDLE) -> CK_RV>,
+    pub C_CloseAllSessions: Unused,
+    pub C_GetSessionInfo: Unused,
+    pub C_GetOperationState: Unused,
+    pub C_SetOperationState: Unused,
+    pub C_Login: Option<
+        unsafe extern "C" fn(
+            hSession: CK_SESSION_HANDLE,
+            userType: CK_ULONG,
+            pPin: *const c_uchar,
+            ulPinLen: CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_Logout: Unused,
+    pub C_CreateObject: Unused,
+    pub C_CopyObject: Unused,
+    pub C_DestroyObject: Unused,
+    pub C_GetObjectSize: Unused,
+    pub C_GetAttributeValue: Option<
+        unsafe extern "C" fn(
+            hSession: CK_SESSION_HANDLE,
+            hObject: CK_OBJECT_HANDLE,
+            pTemplate: *mut CK_ATTRIBUTE,
+            ulCount: CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_SetAttributeValue: Unused,
+    pub C_FindObjectsInit: Option<
+        unsafe extern "C" fn(
+            hSession: CK_SESSION_HANDLE,
+            pTemplate: *mut CK_ATTRIBUTE,
+            ulCount: CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_FindObjects: Option<
+        unsafe extern "C" fn(
+            hSession: CK_SESSION_HANDLE,
+            phObject: *mut CK_OBJECT_HANDLE,
+            ulMaxObjectCount: CK_ULONG,
+            pulObjectCount: *mut CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_FindObjectsFinal: Option<unsafe extern "C" fn(hSession: CK_SESSION_HANDLE) -> CK_RV>,
+    pub C_EncryptInit: Unused,
+    pub C_Encrypt: Unused,
+    pub C_EncryptUpdate: Unused,
+    pub C_EncryptFinal: Unused,
+    pub C_DecryptInit: Option<
+        unsafe extern "C" fn(
+            hSession: CK_SESSION_HANDLE,
+            pMechanism: *mut CK_MECHANISM,
+            hKey: CK_OBJECT_HANDLE,
+        ) -> CK_RV,
+    >,
+    pub C_Decrypt: Option<
+        unsafe extern "C" fn(
+            hSession: CK_SESSION_HANDLE,
+            pEncryptedData: *const c_uchar,
+            ulEncryptedDataLen: CK_ULONG,
+            pData: *mut c_uchar,
+            pulDataLen: *mut CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_DecryptUpdate: Unused,
+    pub C_DecryptFinal: Unused,
+    pub C_DigestInit: Unused,
+    pub C_Digest: Unused,
+    pub C_DigestUpdate: Unused,
+    pub C_DigestKey: Unused,
+    pub C_DigestFinal: Unused,
+    pub C_SignInit: Option<
+        unsafe extern "C" fn(
+            hSession: CK_SESSION_HANDLE,
+            pMechanism: *mut CK_MECHANISM,
+            hKey: CK_OBJECT_HANDLE,
+        ) -> CK_RV,
+    >,
+    pub C_Sign: Option<
+        unsafe extern "C" fn(
+            hSession: CK_SESSION_HANDLE,
+            pData: *const c_uchar,
+            ulDataLen: CK_ULONG,
+            pSignature: *mut c_uchar,
+            pulSignatureLen: *mut CK_ULONG,
+        ) -> CK_RV,
+    >,
+}
+
+pub type CK_C_GetFunctionList =
+    unsafe extern "C" fn(ppFunctionList: *mut *const CK_FUNCTION_LIST) -> CK_RV;
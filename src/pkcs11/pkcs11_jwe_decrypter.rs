@@ -0,0 +1,103 @@
+use std::borrow::Cow;
+use std::ops::Deref;
+
+use anyhow::bail;
+
+use crate::jwe::alg::rsaes::RsaesJweAlgorithm;
+use crate::jwe::{JweAlgorithm, JweContentEncryption, JweDecrypter, JweHeader};
+use crate::pkcs11::ffi;
+use crate::pkcs11::pkcs11_module::{Pkcs11Key, Pkcs11Mechanism};
+use crate::util::HashAlgorithm;
+use crate::JoseError;
+
+#[derive(Debug, Clone)]
+pub struct Pkcs11JweDecrypter {
+    algorithm: RsaesJweAlgorithm,
+    key: Pkcs11Key,
+    key_id: Option<String>,
+}
+
+impl Pkcs11JweDecrypter {
+    pub(crate) fn new(algorithm: RsaesJweAlgorithm, key: Pkcs11Key) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            if key.key_type()? != ffi::CKK_RSA {
+                bail!("The key must be a RSA key: {}", algorithm.name());
+            }
+            let modulus = key.attribute(ffi::CKA_MODULUS)?;
+            let zeros = modulus.iter().take_while(|b| **b == 0).count();
+            if (modulus.len() - zeros) * 8 < 2048 {
+                bail!("key length must be 2048 or more.");
+            }
+
+            Ok(Self {
+                algorithm,
+                key,
+                key_id: None,
+            })
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidKeyFormat(err),
+        })
+    }
+
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.key_id = Some(value.into());
+    }
+
+    pub fn remove_key_id(&mut self) {
+        self.key_id = None;
+    }
+}
+
+impl JweDecrypter for Pkcs11JweDecrypter {
+    fn algorithm(&self) -> &dyn JweAlgorithm {
+        &self.algorithm
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        match &self.key_id {
+            Some(val) => Some(val.as_ref()),
+            None => None,
+        }
+    }
+
+    #[allow(deprecated)]
+    fn decrypt(
+        &self,
+        encrypted_key: Option<&[u8]>,
+        _cencryption: &dyn JweContentEncryption,
+        _header: &JweHeader,
+    ) -> Result<Cow<'_, [u8]>, JoseError> {
+        (|| -> anyhow::Result<Cow<'_, [u8]>> {
+            let encrypted_key = match encrypted_key {
+                Some(val) => val,
+                None => bail!("A encrypted_key is required."),
+            };
+
+            let mechanism = match self.algorithm {
+                RsaesJweAlgorithm::Rsa1_5 => Pkcs11Mechanism::new(ffi::CKM_RSA_PKCS),
+                RsaesJweAlgorithm::RsaOaep => Pkcs11Mechanism::rsa_oaep(HashAlgorithm::Sha1),
+                RsaesJweAlgorithm::RsaOaep256 => Pkcs11Mechanism::rsa_oaep(HashAlgorithm::Sha256),
+                RsaesJweAlgorithm::RsaOaep384 => Pkcs11Mechanism::rsa_oaep(HashAlgorithm::Sha384),
+                RsaesJweAlgorithm::RsaOaep512 => Pkcs11Mechanism::rsa_oaep(HashAlgorithm::Sha512),
+            };
+            let key = self.key.decrypt(&mechanism, encrypted_key)?;
+
+            Ok(Cow::Owned(key))
+        })()
+        .map_err(JoseError::InvalidJweFormat)
+    }
+
+    fn box_clone(&self) -> Box<dyn JweDecrypter> {
+        Box::new(self.clone())
+    }
+}
+
+impl Deref for Pkcs11JweDecrypter {
+    type Target = dyn JweDecrypter;
+
+    fn deref(&self) -> &Self::Target {
+        self
+    }
+}
@@ -0,0 +1,278 @@
+use std::ops::Deref;
+
+use anyhow::bail;
+use openssl::hash;
+
+use crate::jwk::alg::ec::EcCurve;
+use crate::jwk::alg::ed::EdCurve;
+use crate::jws::alg::ecdsa;
+use crate::jws::{JwsAlgorithm, JwsSigner};
+use crate::pkcs11::ffi;
+use crate::pkcs11::pkcs11_module::{Pkcs11Key, Pkcs11Mechanism};
+use crate::util::der::{DerReader, DerType};
+use crate::util::HashAlgorithm;
+use crate::JoseError;
+
+/// The signature scheme of a JWS algorithm on a PKCS#11 token.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Pkcs11SignatureScheme {
+    RsaPkcs1(HashAlgorithm),
+    RsaPss(HashAlgorithm),
+    Ecdsa(HashAlgorithm, EcCurve),
+    Eddsa,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pkcs11JwsSigner {
+    algorithm: Box<dyn JwsAlgorithm>,
+    scheme: Pkcs11SignatureScheme,
+    signature_len: usize,
+    key: Pkcs11Key,
+    key_id: Option<String>,
+}
+
+impl Pkcs11JwsSigner {
+    pub(crate) fn new(
+        algorithm: Box<dyn JwsAlgorithm>,
+        scheme: Pkcs11SignatureScheme,
+        key: Pkcs11Key,
+    ) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            let key_type = key.key_type()?;
+            let signature_len = match scheme {
+                Pkcs11SignatureScheme::RsaPkcs1(_) | Pkcs11SignatureScheme::RsaPss(_) => {
+                    if key_type != ffi::CKK_RSA {
+                        bail!("The key must be a RSA key: {}", algorithm.name());
+                    }
+                    let modulus = key.attribute(ffi::CKA_MODULUS)?;
+                    let zeros = modulus.iter().take_while(|b| **b == 0).count();
+                    let key_len = modulus.len() - zeros;
+                    if key_len * 8 < 2048 {
+                        bail!("key length must be 2048 or more.");
+                    }
+                    key_len
+                }
+                Pkcs11SignatureScheme::Ecdsa(_, curve) => {
+                    if key_type != ffi::CKK_EC {
+                        bail!("The key must be a EC key: {}", algorithm.name());
+                    }
+                    let params = key.attribute(ffi::CKA_EC_PARAMS)?;
+                    let mut reader = DerReader::from_bytes(&params);
+                    match reader.next()? {
+                        Some(DerType::ObjectIdentifier) => {
+                            if &reader.to_object_identifier()? != curve.oid() {
+                                bail!("The curve of the key must be {}.", curve.name());
+                            }
+                        }
+                        _ => bail!("The CKA_EC_PARAMS attribute must be a named curve."),
+                    }
+                    match curve {
+                        EcCurve::P256 | EcCurve::Secp256k1 => 64,
+                        EcCurve::P384 => 96,
+                        EcCurve::P521 => 132,
+                    }
+                }
+                Pkcs11SignatureScheme::Eddsa => {
+                    if key_type != ffi::CKK_EC_EDWARDS {
+                        bail!("The key must be a Edwards curve key: {}", algorithm.name());
+                    }
+                    let params = key.attribute(ffi::CKA_EC_PARAMS)?;
+                    match edwards_curve(&params)? {
+                        EdCurve::Ed25519 => 64,
+                        EdCurve::Ed448 => 114,
+                    }
+                }
+            };
+
+            Ok(Self {
+                algorithm,
+                scheme,
+                signature_len,
+                key,
+                key_id: None,
+            })
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidKeyFormat(err),
+        })
+    }
+
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.key_id = Some(value.into());
+    }
+
+    pub fn remove_key_id(&mut self) {
+        self.key_id = None;
+    }
+}
+
+impl JwsSigner for Pkcs11JwsSigner {
+    fn algorithm(&self) -> &dyn JwsAlgorithm {
+        self.algorithm.as_ref()
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        match &self.key_id {
+            Some(val) => Some(val.as_ref()),
+            None => None,
+        }
+    }
+
+    fn signature_len(&self) -> usize {
+        self.signature_len
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, JoseError> {
+        (|| -> anyhow::Result<Vec<u8>> {
+            let signature = match self.scheme {
+                Pkcs11SignatureScheme::RsaPkcs1(hash) => {
+                    let mechanism = match hash {
+                        HashAlgorithm::Sha256 => ffi::CKM_SHA256_RSA_PKCS,
+                        HashAlgorithm::Sha384 => ffi::CKM_SHA384_RSA_PKCS,
+                        HashAlgorithm::Sha512 => ffi::CKM_SHA512_RSA_PKCS,
+                        _ => bail!("The hash algorithm is not supported: {}", hash),
+                    };
+                    self.key.sign(&Pkcs11Mechanism::new(mechanism), message)?
+                }
+                Pkcs11SignatureScheme::RsaPss(hash) => {
+                    let mechanism = match hash {
+                        HashAlgorithm::Sha256 => ffi::CKM_SHA256_RSA_PKCS_PSS,
+                        HashAlgorithm::Sha384 => ffi::CKM_SHA384_RSA_PKCS_PSS,
+                        HashAlgorithm::Sha512 => ffi::CKM_SHA512_RSA_PKCS_PSS,
+                        _ => bail!("The hash algorithm is not supported: {}", hash),
+                    };
+                    self.key
+                        .sign(&Pkcs11Mechanism::rsa_pss(mechanism, hash), message)?
+                }
+                Pkcs11SignatureScheme::Ecdsa(hash, _) => {
+                    // CKM_ECDSA signs a digest, which every token supports
+                    // unlike the mechanisms that hash the message.
+                    let digest = hash::hash(hash.message_digest(), message)?;
+                    self.key
+                        .sign(&Pkcs11Mechanism::new(ffi::CKM_ECDSA), &digest)?
+                }
+                Pkcs11SignatureScheme::Eddsa => self
+                    .key
+                    .sign(&Pkcs11Mechanism::new(ffi::CKM_EDDSA), message)?,
+            };
+
+            jws_signature(self.scheme, signature, self.signature_len)
+        })()
+        .map_err(JoseError::InvalidSignature)
+    }
+
+    fn box_clone(&self) -> Box<dyn JwsSigner> {
+        Box::new(self.clone())
+    }
+}
+
+impl Deref for Pkcs11JwsSigner {
+    type Target = dyn JwsSigner;
+
+    fn deref(&self) -> &Self::Target {
+        self
+    }
+}
+
+/// Convert a output of the signing mechanism into the JWS signature.
+fn jws_signature(
+    scheme: Pkcs11SignatureScheme,
+    signature: Vec<u8>,
+    signature_len: usize,
+) -> anyhow::Result<Vec<u8>> {
+    let signature = match scheme {
+        // PKCS#11 specifies R || S, but some tokens return DER.
+        Pkcs11SignatureScheme::Ecdsa(..) => ecdsa::to_raw_signature(signature, signature_len)?,
+        _ => signature,
+    };
+
+    if signature.len() != signature_len {
+        bail!(
+            "A signature size must be {}: {}",
+            signature_len,
+            signature.len()
+        );
+    }
+    Ok(signature)
+}
+
+/// Return the curve of the CKA_EC_PARAMS attribute of a Edwards curve key,
+/// which is either a object identifier or a printable string.
+fn edwards_curve(params: &[u8]) -> anyhow::Result<EdCurve> {
+    let mut reader = DerReader::from_bytes(&params);
+    match reader.next()? {
+        Some(DerType::ObjectIdentifier) => {
+            let oid = reader.to_object_identifier()?;
+            if &oid == EdCurve::Ed25519.oid() {
+                Ok(EdCurve::Ed25519)
+            } else if &oid == EdCurve::Ed448.oid() {
+                Ok(EdCurve::Ed448)
+            } else {
+                bail!("The curve of the key is not supported.");
+            }
+        }
+        Some(DerType::PrintableString) => match reader.contents() {
+            Some(b"edwards25519") => Ok(EdCurve::Ed25519),
+            Some(b"edwards448") => Ok(EdCurve::Ed448),
+            _ => bail!("The curve of the key is not supported."),
+        },
+        _ => bail!("The CKA_EC_PARAMS attribute must be a named curve."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::Result;
+
+    use crate::jws::{ES256, ES384, ES512};
+
+    #[test]
+    fn convert_signature() -> Result<()> {
+        for (alg, curve, signature_len) in [
+            (ES256, EcCurve::P256, 64),
+            (ES384, EcCurve::P384, 96),
+            (ES512, EcCurve::P521, 132),
+        ] {
+            let scheme = Pkcs11SignatureScheme::Ecdsa(HashAlgorithm::Sha256, curve);
+            let key_pair = alg.generate_key_pair()?;
+            let signer = alg.signer_from_der(key_pair.to_der_private_key())?;
+            let raw = signer.sign(b"abcde12345")?;
+            let der = ecdsa::raw_to_der_signature(&raw, signature_len)?;
+
+            assert_eq!(jws_signature(scheme, raw.clone(), signature_len)?, raw);
+            assert_eq!(jws_signature(scheme, der, signature_len)?, raw);
+            assert!(jws_signature(scheme, vec![0; 10], signature_len).is_err());
+        }
+
+        let scheme = Pkcs11SignatureScheme::RsaPkcs1(HashAlgorithm::Sha256);
+        assert_eq!(jws_signature(scheme, vec![1; 256], 256)?, vec![1; 256]);
+        assert!(jws_signature(scheme, vec![1; 255], 256).is_err());
+
+        let scheme = Pkcs11SignatureScheme::Eddsa;
+        assert_eq!(jws_signature(scheme, vec![1; 64], 64)?, vec![1; 64]);
+        assert!(jws_signature(scheme, vec![1; 114], 64).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_edwards_curve() -> Result<()> {
+        assert_eq!(edwards_curve(&[6, 3, 43, 101, 112])?, EdCurve::Ed25519);
+        assert_eq!(edwards_curve(&[6, 3, 43, 101, 113])?, EdCurve::Ed448);
+
+        let mut params = vec![19, 12];
+        params.extend_from_slice(b"edwards25519");
+        assert_eq!(edwards_curve(&params)?, EdCurve::Ed25519);
+
+        let mut params = vec![19, 10];
+        params.extend_from_slice(b"edwards448");
+        assert_eq!(edwards_curve(&params)?, EdCurve::Ed448);
+
+        assert!(edwards_curve(&[6, 3, 43, 101, 110]).is_err());
+
+        Ok(())
+    }
+}
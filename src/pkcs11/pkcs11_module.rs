@@ -0,0 +1,715 @@
+use std::fmt::{self, Debug};
+use std::os::raw::c_void;
+use std::path::Path;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::bail;
+use libloading::Library;
+
+use crate::pkcs11::ffi;
+use crate::util::HashAlgorithm;
+use crate::JoseError;
+
+/// The number of times to call a function whose output length grows between calls.
+const MAX_OUTPUT_ATTEMPTS: usize = 3;
+
+/// A loaded PKCS#11 module, e.g. libsofthsm2.so.
+///
+/// The module is initialized with OS locking enabled
+/// and finalized when the last reference is dropped.
+#[derive(Clone)]
+pub struct Pkcs11Module {
+    inner: Arc<ModuleInner>,
+}
+
+impl Pkcs11Module {
+    /// Load and initialize a PKCS#11 module.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the PKCS#11 shared library.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            let path = path.as_ref();
+
+            // Loading a PKCS#11 module runs its initialization routines.
+            // The caller is responsible for choosing a trusted library.
+            let library = unsafe { Library::new(path)? };
+            let functions = unsafe {
+                let get_function_list =
+                    library.get::<ffi::CK_C_GetFunctionList>(b"C_GetFunctionList\0")?;
+                let mut functions = ptr::null();
+                check("C_GetFunctionList", get_function_list(&mut functions))?;
+                functions
+            };
+            if functions.is_null() {
+                bail!("The PKCS#11 module has no function list.");
+            }
+
+            let initialize = require("C_Initialize", unsafe { (*functions).C_Initialize })?;
+            let mut args = ffi::CK_C_INITIALIZE_ARGS {
+                CreateMutex: ptr::null_mut(),
+                DestroyMutex: ptr::null_mut(),
+                LockMutex: ptr::null_mut(),
+                UnlockMutex: ptr::null_mut(),
+                flags: ffi::CKF_OS_LOCKING_OK,
+                pReserved: ptr::null_mut(),
+            };
+            let finalize = match unsafe { initialize(&mut args as *mut _ as *mut c_void) } {
+                ffi::CKR_OK => true,
+                ffi::CKR_CRYPTOKI_ALREADY_INITIALIZED => false,
+                rv => bail!("C_Initialize failed: 0x{:x}", rv),
+            };
+
+            Ok(Self {
+                inner: Arc::new(ModuleInner {
+                    path: path.to_string_lossy().to_string(),
+                    functions,
+                    finalize,
+                    _library: library,
+                }),
+            })
+        })()
+        .map_err(JoseError::InvalidKeyFormat)
+    }
+
+    /// Return the IDs of the slots that have a token.
+    pub fn slot_ids(&self) -> Result<Vec<u64>, JoseError> {
+        (|| -> anyhow::Result<Vec<u64>> {
+            let get_slot_list = require("C_GetSlotList", self.functions().C_GetSlotList)?;
+
+            let mut count = 0;
+            check("C_GetSlotList", unsafe {
+                get_slot_list(1, ptr::null_mut(), &mut count)
+            })?;
+            let mut slot_ids = vec![0; count as usize];
+            check("C_GetSlotList", unsafe {
+                get_slot_list(1, slot_ids.as_mut_ptr(), &mut count)
+            })?;
+            slot_ids.truncate(count as usize);
+
+            // CK_SLOT_ID is 32 bits wide on Windows.
+            #[allow(clippy::unnecessary_cast)]
+            Ok(slot_ids.into_iter().map(|val| val as u64).collect())
+        })()
+        .map_err(JoseError::InvalidKeyFormat)
+    }
+
+    /// Open a session on the token in the slot.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_id` - The slot ID.
+    /// * `pin` - The user PIN to log in with, if the keys are private objects.
+    pub fn open_session(
+        &self,
+        slot_id: u64,
+        pin: Option<&str>,
+    ) -> Result<Pkcs11Session, JoseError> {
+        (|| -> anyhow::Result<Pkcs11Session> {
+            let open_session = require("C_OpenSession", self.functions().C_OpenSession)?;
+
+            let mut handle = 0;
+            check("C_OpenSession", unsafe {
+                open_session(
+                    slot_id as ffi::CK_SLOT_ID,
+                    ffi::CKF_SERIAL_SESSION,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    &mut handle,
+                )
+            })?;
+            let session = Pkcs11Session {
+                inner: Arc::new(SessionInner {
+                    module: self.clone(),
+                    handle,
+                    lock: Mutex::new(()),
+                }),
+            };
+
+            if let Some(pin) = pin {
+                let login = require("C_Login", self.functions().C_Login)?;
+                match unsafe {
+                    login(
+                        handle,
+                        ffi::CKU_USER,
+                        pin.as_ptr(),
+                        pin.len() as ffi::CK_ULONG,
+                    )
+                } {
+                    ffi::CKR_OK | ffi::CKR_USER_ALREADY_LOGGED_IN => {}
+                    rv => bail!("C_Login failed: 0x{:x}", rv),
+                }
+            }
+
+            Ok(session)
+        })()
+        .map_err(JoseError::InvalidKeyFormat)
+    }
+
+    fn functions(&self) -> &ffi::CK_FUNCTION_LIST {
+        unsafe { &*self.inner.functions }
+    }
+}
+
+impl Debug for Pkcs11Module {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Pkcs11Module")
+            .field("path", &self.inner.path)
+            .finish()
+    }
+}
+
+struct ModuleInner {
+    path: String,
+    functions: *const ffi::CK_FUNCTION_LIST,
+    finalize: bool,
+    _library: Library,
+}
+
+// The function list is immutable and the module is initialized
+// with CKF_OS_LOCKING_OK, so it may be called from any thread.
+unsafe impl Send for ModuleInner {}
+unsafe impl Sync for ModuleInner {}
+
+impl Drop for ModuleInner {
+    fn drop(&mut self) {
+        if self.finalize {
+            if let Some(finalize) = unsafe { (*self.functions).C_Finalize } {
+                unsafe { finalize(ptr::null_mut()) };
+            }
+        }
+    }
+}
+
+/// A session on a PKCS#11 token.
+///
+/// A session runs one cryptographic operation at a time,
+/// so the operations of the clones are serialized.
+#[derive(Debug, Clone)]
+pub struct Pkcs11Session {
+    inner: Arc<SessionInner>,
+}
+
+impl Pkcs11Session {
+    /// Return the private key object that has the label.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The CKA_LABEL attribute of the key.
+    pub fn find_private_key_by_label(&self, label: &str) -> Result<Pkcs11Key, JoseError> {
+        self.find_private_key(ffi::CKA_LABEL, label.as_bytes(), label)
+    }
+
+    /// Return the private key object that has the ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The CKA_ID attribute of the key.
+    pub fn find_private_key_by_id(&self, id: &[u8]) -> Result<Pkcs11Key, JoseError> {
+        self.find_private_key(ffi::CKA_ID, id, &hex(id))
+    }
+
+    fn find_private_key(
+        &self,
+        attribute_type: ffi::CK_ULONG,
+        value: &[u8],
+        name: &str,
+    ) -> Result<Pkcs11Key, JoseError> {
+        let handle = (|| -> anyhow::Result<Option<ffi::CK_OBJECT_HANDLE>> {
+            let functions = self.inner.module.functions();
+            let find_objects_init = require("C_FindObjectsInit", functions.C_FindObjectsInit)?;
+            let find_objects = require("C_FindObjects", functions.C_FindObjects)?;
+            let find_objects_final = require("C_FindObjectsFinal", functions.C_FindObjectsFinal)?;
+
+            let mut class = ffi::CKO_PRIVATE_KEY;
+            let mut template = [
+                ffi::CK_ATTRIBUTE {
+                    type_: ffi::CKA_CLASS,
+                    pValue: &mut class as *mut _ as *mut c_void,
+                    ulValueLen: std::mem::size_of::<ffi::CK_ULONG>() as ffi::CK_ULONG,
+                },
+                ffi::CK_ATTRIBUTE {
+                    type_: attribute_type,
+                    pValue: value.as_ptr() as *mut c_void,
+                    ulValueLen: value.len() as ffi::CK_ULONG,
+                },
+            ];
+
+            let _guard = self.lock();
+            let handle = self.inner.handle;
+            check("C_FindObjectsInit", unsafe {
+                find_objects_init(
+                    handle,
+                    template.as_mut_ptr(),
+                    template.len() as ffi::CK_ULONG,
+                )
+            })?;
+            let mut object = 0;
+            let mut count = 0;
+            let rv = unsafe { find_objects(handle, &mut object, 1, &mut count) };
+            check("C_FindObjectsFinal", unsafe { find_objects_final(handle) })?;
+            check("C_FindObjects", rv)?;
+
+            Ok(if count > 0 { Some(object) } else { None })
+        })()
+        .map_err(JoseError::InvalidKeyFormat)?;
+
+        match handle {
+            Some(handle) => Ok(Pkcs11Key {
+                session: self.clone(),
+                handle,
+            }),
+            None => Err(JoseError::KeyNotFound(Some(name.to_string()))),
+        }
+    }
+
+    fn attribute(
+        &self,
+        object: ffi::CK_OBJECT_HANDLE,
+        attribute_type: ffi::CK_ULONG,
+    ) -> anyhow::Result<Vec<u8>> {
+        let get_attribute_value = require(
+            "C_GetAttributeValue",
+            self.inner.module.functions().C_GetAttributeValue,
+        )?;
+
+        let _guard = self.lock();
+        let mut template = ffi::CK_ATTRIBUTE {
+            type_: attribute_type,
+            pValue: ptr::null_mut(),
+            ulValueLen: 0,
+        };
+        check("C_GetAttributeValue", unsafe {
+            get_attribute_value(self.inner.handle, object, &mut template, 1)
+        })?;
+
+        let mut value = vec![0u8; template.ulValueLen as usize];
+        template.pValue = value.as_mut_ptr() as *mut c_void;
+        check("C_GetAttributeValue", unsafe {
+            get_attribute_value(self.inner.handle, object, &mut template, 1)
+        })?;
+        value.truncate(template.ulValueLen as usize);
+
+        Ok(value)
+    }
+
+    fn sign(
+        &self,
+        key: ffi::CK_OBJECT_HANDLE,
+        mechanism: &Pkcs11Mechanism,
+        data: &[u8],
+    ) -> anyhow::Result<Vec<u8>> {
+        let functions = self.inner.module.functions();
+        let sign_init = require("C_SignInit", functions.C_SignInit)?;
+        let sign = require("C_Sign", functions.C_Sign)?;
+
+        let mut parameter = mechanism.parameter;
+        let mut mechanism = parameter.ck_mechanism(mechanism.mechanism);
+
+        let _guard = self.lock();
+        let handle = self.inner.handle;
+        check("C_SignInit", unsafe {
+            sign_init(handle, &mut mechanism, key)
+        })?;
+        output("C_Sign", |buf, len| unsafe {
+            sign(handle, data.as_ptr(), data.len() as ffi::CK_ULONG, buf, len)
+        })
+    }
+
+    fn decrypt(
+        &self,
+        key: ffi::CK_OBJECT_HANDLE,
+        mechanism: &Pkcs11Mechanism,
+        data: &[u8],
+    ) -> anyhow::Result<Vec<u8>> {
+        let functions = self.inner.module.functions();
+        let decrypt_init = require("C_DecryptInit", functions.C_DecryptInit)?;
+        let decrypt = require("C_Decrypt", functions.C_Decrypt)?;
+
+        let mut parameter = mechanism.parameter;
+        let mut mechanism = parameter.ck_mechanism(mechanism.mechanism);
+
+        let _guard = self.lock();
+        let handle = self.inner.handle;
+        check("C_DecryptInit", unsafe {
+            decrypt_init(handle, &mut mechanism, key)
+        })?;
+        output("C_Decrypt", |buf, len| unsafe {
+            decrypt(handle, data.as_ptr(), data.len() as ffi::CK_ULONG, buf, len)
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ()> {
+        match self.inner.lock.lock() {
+            Ok(val) => val,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SessionInner {
+    module: Pkcs11Module,
+    handle: ffi::CK_SESSION_HANDLE,
+    lock: Mutex<()>,
+}
+
+impl Drop for SessionInner {
+    fn drop(&mut self) {
+        if let Some(close_session) = self.module.functions().C_CloseSession {
+            unsafe { close_session(self.handle) };
+        }
+    }
+}
+
+/// A private key object on a PKCS#11 token.
+///
+/// The key material never leaves the token.
+#[derive(Debug, Clone)]
+pub struct Pkcs11Key {
+    session: Pkcs11Session,
+    handle: ffi::CK_OBJECT_HANDLE,
+}
+
+impl Pkcs11Key {
+    pub(crate) fn key_type(&self) -> anyhow::Result<ffi::CK_ULONG> {
+        let value = self.session.attribute(self.handle, ffi::CKA_KEY_TYPE)?;
+        if value.len() != std::mem::size_of::<ffi::CK_ULONG>() {
+            bail!("The CKA_KEY_TYPE attribute is invalid.");
+        }
+        let mut buf = [0u8; std::mem::size_of::<ffi::CK_ULONG>()];
+        buf.copy_from_slice(&value);
+        Ok(ffi::CK_ULONG::from_ne_bytes(buf))
+    }
+
+    pub(crate) fn attribute(&self, attribute_type: ffi::CK_ULONG) -> anyhow::Result<Vec<u8>> {
+        self.session.attribute(self.handle, attribute_type)
+    }
+
+    pub(crate) fn sign(&self, mechanism: &Pkcs11Mechanism, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        self.session.sign(self.handle, mechanism, data)
+    }
+
+    pub(crate) fn decrypt(
+        &self,
+        mechanism: &Pkcs11Mechanism,
+        data: &[u8],
+    ) -> anyhow::Result<Vec<u8>> {
+        self.session.decrypt(self.handle, mechanism, data)
+    }
+}
+
+/// A PKCS#11 mechanism with its parameter.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Pkcs11Mechanism {
+    mechanism: ffi::CK_ULONG,
+    parameter: Pkcs11MechanismParameter,
+}
+
+impl Pkcs11Mechanism {
+    pub fn new(mechanism: ffi::CK_ULONG) -> Self {
+        Self {
+            mechanism,
+            parameter: Pkcs11MechanismParameter::None,
+        }
+    }
+
+    pub fn rsa_pss(mechanism: ffi::CK_ULONG, hash: HashAlgorithm) -> Self {
+        let (hash_alg, mgf) = hash_mechanism(hash);
+        Self {
+            mechanism,
+            parameter: Pkcs11MechanismParameter::RsaPss(ffi::CK_RSA_PKCS_PSS_PARAMS {
+                hashAlg: hash_alg,
+                mgf,
+                sLen: hash.output_len() as ffi::CK_ULONG,
+            }),
+        }
+    }
+
+    pub fn rsa_oaep(hash: HashAlgorithm) -> Self {
+        let (hash_alg, mgf) = hash_mechanism(hash);
+        Self {
+            mechanism: ffi::CKM_RSA_PKCS_OAEP,
+            parameter: Pkcs11MechanismParameter::RsaOaep(ffi::CK_RSA_PKCS_OAEP_PARAMS {
+                hashAlg: hash_alg,
+                mgf,
+                source: ffi::CKZ_DATA_SPECIFIED,
+                pSourceData: ptr::null_mut(),
+                ulSourceDataLen: 0,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Pkcs11MechanismParameter {
+    None,
+    RsaPss(ffi::CK_RSA_PKCS_PSS_PARAMS),
+    RsaOaep(ffi::CK_RSA_PKCS_OAEP_PARAMS),
+}
+
+impl Pkcs11MechanismParameter {
+    /// Return a CK_MECHANISM that points to the parameter,
+    /// so the parameter must outlive it.
+    fn ck_mechanism(&mut self, mechanism: ffi::CK_ULONG) -> ffi::CK_MECHANISM {
+        match self {
+            Self::None => ffi::CK_MECHANISM {
+                mechanism,
+                pParameter: ptr::null_mut(),
+                ulParameterLen: 0,
+            },
+            Self::RsaPss(params) => ffi::CK_MECHANISM {
+                mechanism,
+                pParameter: params as *mut _ as *mut c_void,
+                ulParameterLen: std::mem::size_of::<ffi::CK_RSA_PKCS_PSS_PARAMS>() as ffi::CK_ULONG,
+            },
+            Self::RsaOaep(params) => ffi::CK_MECHANISM {
+                mechanism,
+                pParameter: params as *mut _ as *mut c_void,
+                ulParameterLen: std::mem::size_of::<ffi::CK_RSA_PKCS_OAEP_PARAMS>()
+                    as ffi::CK_ULONG,
+            },
+        }
+    }
+}
+
+fn hash_mechanism(hash: HashAlgorithm) -> (ffi::CK_ULONG, ffi::CK_ULONG) {
+    match hash {
+        HashAlgorithm::Sha1 => (ffi::CKM_SHA_1, ffi::CKG_MGF1_SHA1),
+        HashAlgorithm::Sha256 => (ffi::CKM_SHA256, ffi::CKG_MGF1_SHA256),
+        HashAlgorithm::Sha384 => (ffi::CKM_SHA384, ffi::CKG_MGF1_SHA384),
+        HashAlgorithm::Sha512 => (ffi::CKM_SHA512, ffi::CKG_MGF1_SHA512),
+    }
+}
+
+/// Call a function that returns its output in a caller allocated buffer,
+/// first with a null buffer to learn the length.
+///
+/// The call is repeated with a larger buffer if the function reports
+/// that the buffer is too small, which the operation survives.
+fn output<F>(name: &str, call: F) -> anyhow::Result<Vec<u8>>
+where
+    F: Fn(*mut u8, *mut ffi::CK_ULONG) -> ffi::CK_RV,
+{
+    let mut len = 0;
+    check(name, call(ptr::null_mut(), &mut len))?;
+    for _ in 0..MAX_OUTPUT_ATTEMPTS {
+        let mut buf = vec![0u8; len as usize];
+        match call(buf.as_mut_ptr(), &mut len) {
+            ffi::CKR_BUFFER_TOO_SMALL if len as usize > buf.len() => continue,
+            rv => check(name, rv)?,
+        }
+        if len as usize > buf.len() {
+            bail!("{} failed: the output length is invalid.", name);
+        }
+        buf.truncate(len as usize);
+        return Ok(buf);
+    }
+    bail!("{} failed: the output length keeps growing.", name)
+}
+
+fn require<T>(name: &str, function: Option<T>) -> anyhow::Result<T> {
+    match function {
+        Some(val) => Ok(val),
+        None => bail!("The PKCS#11 module does not provide {}.", name),
+    }
+}
+
+fn check(name: &str, rv: ffi::CK_RV) -> anyhow::Result<()> {
+    match rv {
+        ffi::CKR_OK => Ok(()),
+        ffi::CKR_BUFFER_TOO_SMALL => bail!("{} failed: the buffer is too small.", name),
+        _ => bail!("{} failed: 0x{:x}", name, rv),
+    }
+}
+
+fn hex(value: &[u8]) -> String {
+    value.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::Result;
+    use std::cell::{Cell, RefCell};
+
+    #[test]
+    fn build_mechanism_parameters() -> Result<()> {
+        let mut parameter = Pkcs11MechanismParameter::None;
+        let mechanism = parameter.ck_mechanism(ffi::CKM_ECDSA);
+        let (mech, param, param_len) = (
+            mechanism.mechanism,
+            mechanism.pParameter,
+            mechanism.ulParameterLen,
+        );
+        assert_eq!(mech, ffi::CKM_ECDSA);
+        assert!(param.is_null());
+        assert_eq!(param_len, 0);
+
+        for (hash, hash_alg, mgf, salt_len) in [
+            (
+                HashAlgorithm::Sha256,
+                ffi::CKM_SHA256,
+                ffi::CKG_MGF1_SHA256,
+                32,
+            ),
+            (
+                HashAlgorithm::Sha384,
+                ffi::CKM_SHA384,
+                ffi::CKG_MGF1_SHA384,
+                48,
+            ),
+            (
+                HashAlgorithm::Sha512,
+                ffi::CKM_SHA512,
+                ffi::CKG_MGF1_SHA512,
+                64,
+            ),
+        ] {
+            let mechanism = Pkcs11Mechanism::rsa_pss(ffi::CKM_SHA256_RSA_PKCS_PSS, hash);
+            let mut parameter = mechanism.parameter;
+            let mechanism = parameter.ck_mechanism(mechanism.mechanism);
+            let (mech, param, param_len) = (
+                mechanism.mechanism,
+                mechanism.pParameter,
+                mechanism.ulParameterLen,
+            );
+            assert_eq!(mech, ffi::CKM_SHA256_RSA_PKCS_PSS);
+            assert_eq!(
+                param_len as usize,
+                std::mem::size_of::<ffi::CK_RSA_PKCS_PSS_PARAMS>()
+            );
+            let params = unsafe { *(param as *const ffi::CK_RSA_PKCS_PSS_PARAMS) };
+            let (actual_hash_alg, actual_mgf, actual_salt_len) =
+                (params.hashAlg, params.mgf, params.sLen);
+            assert_eq!(actual_hash_alg, hash_alg);
+            assert_eq!(actual_mgf, mgf);
+            assert_eq!(actual_salt_len, salt_len);
+        }
+
+        for (hash, hash_alg, mgf) in [
+            (HashAlgorithm::Sha1, ffi::CKM_SHA_1, ffi::CKG_MGF1_SHA1),
+            (HashAlgorithm::Sha256, ffi::CKM_SHA256, ffi::CKG_MGF1_SHA256),
+            (HashAlgorithm::Sha384, ffi::CKM_SHA384, ffi::CKG_MGF1_SHA384),
+            (HashAlgorithm::Sha512, ffi::CKM_SHA512, ffi::CKG_MGF1_SHA512),
+        ] {
+            let mechanism = Pkcs11Mechanism::rsa_oaep(hash);
+            let mut parameter = mechanism.parameter;
+            let mechanism = parameter.ck_mechanism(mechanism.mechanism);
+            let (mech, param, param_len) = (
+                mechanism.mechanism,
+                mechanism.pParameter,
+                mechanism.ulParameterLen,
+            );
+            assert_eq!(mech, ffi::CKM_RSA_PKCS_OAEP);
+            assert_eq!(
+                param_len as usize,
+                std::mem::size_of::<ffi::CK_RSA_PKCS_OAEP_PARAMS>()
+            );
+            let params = unsafe { *(param as *const ffi::CK_RSA_PKCS_OAEP_PARAMS) };
+            let (actual_hash_alg, actual_mgf, source, source_data, source_data_len) = (
+                params.hashAlg,
+                params.mgf,
+                params.source,
+                params.pSourceData,
+                params.ulSourceDataLen,
+            );
+            assert_eq!(actual_hash_alg, hash_alg);
+            assert_eq!(actual_mgf, mgf);
+            assert_eq!(source, ffi::CKZ_DATA_SPECIFIED);
+            assert!(source_data.is_null());
+            assert_eq!(source_data_len, 0);
+        }
+
+        Ok(())
+    }
+
+    /// A fake C_Sign that reports `lens` as the output length of successive calls
+    /// and writes `data` once the buffer is large enough.
+    struct FakeOutput {
+        lens: RefCell<Vec<ffi::CK_ULONG>>,
+        data: Vec<u8>,
+        calls: Cell<usize>,
+    }
+
+    impl FakeOutput {
+        fn new(lens: Vec<ffi::CK_ULONG>, data: &[u8]) -> Self {
+            Self {
+                lens: RefCell::new(lens),
+                data: data.to_vec(),
+                calls: Cell::new(0),
+            }
+        }
+
+        fn call(&self, buf: *mut u8, len: *mut ffi::CK_ULONG) -> ffi::CK_RV {
+            self.calls.set(self.calls.get() + 1);
+            let capacity = unsafe { *len } as usize;
+            let reported = {
+                let mut lens = self.lens.borrow_mut();
+                if lens.len() > 1 {
+                    lens.remove(0)
+                } else {
+                    lens[0]
+                }
+            };
+            unsafe { *len = reported };
+            if buf.is_null() {
+                ffi::CKR_OK
+            } else if capacity < self.data.len() {
+                ffi::CKR_BUFFER_TOO_SMALL
+            } else {
+                unsafe { ptr::copy_nonoverlapping(self.data.as_ptr(), buf, self.data.len()) };
+                unsafe { *len = self.data.len() as ffi::CK_ULONG };
+                ffi::CKR_OK
+            }
+        }
+    }
+
+    #[test]
+    fn query_output_length() -> Result<()> {
+        // The length query returns the maximum length, e.g. of a DER signature.
+        let fake = FakeOutput::new(vec![72], &[1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(
+            output("C_Sign", |buf, len| fake.call(buf, len))?,
+            vec![1, 2, 3, 4, 5, 6, 7]
+        );
+        assert_eq!(fake.calls.get(), 2);
+
+        // The output grows after the length query.
+        let fake = FakeOutput::new(vec![4, 8], &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            output("C_Sign", |buf, len| fake.call(buf, len))?,
+            vec![1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert_eq!(fake.calls.get(), 3);
+
+        // The output keeps growing.
+        let fake = FakeOutput::new(vec![1, 2, 3, 4, 5], &[0; 100]);
+        assert!(output("C_Sign", |buf, len| fake.call(buf, len)).is_err());
+        assert_eq!(fake.calls.get(), 1 + MAX_OUTPUT_ATTEMPTS);
+
+        // The buffer is too small without a larger length.
+        let fake = FakeOutput::new(vec![4], &[0; 8]);
+        assert!(output("C_Sign", |buf, len| fake.call(buf, len)).is_err());
+
+        // An error of the length query.
+        assert!(output("C_Sign", |_, _| 0x6).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_return_value() -> Result<()> {
+        check("C_Sign", ffi::CKR_OK)?;
+        assert!(check("C_Sign", ffi::CKR_BUFFER_TOO_SMALL).is_err());
+        assert!(check("C_Sign", 0x6).is_err());
+
+        assert!(require::<fn()>("C_Sign", None).is_err());
+        assert_eq!(hex(&[0x01, 0xab]), "01ab");
+
+        Ok(())
+    }
+}
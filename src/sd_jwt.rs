@@ -0,0 +1,348 @@
+//! Selective Disclosure for JWTs (SD-JWT) support.
+
+mod disclosure;
+mod issuer_metadata_resolver;
+mod key_binding_jwt_builder;
+mod sd_jwt_builder;
+mod sd_jwt_token;
+mod sd_jwt_validator;
+mod sd_jwt_vc;
+mod sd_jwt_vc_builder;
+mod sd_jwt_vc_validator;
+
+pub use crate::sd_jwt::disclosure::Disclosure;
+pub use crate::sd_jwt::issuer_metadata_resolver::{
+    IssuerMetadataResolver, MemoryIssuerMetadataResolver,
+};
+pub use crate::sd_jwt::key_binding_jwt_builder::KeyBindingJwtBuilder;
+pub use crate::sd_jwt::sd_jwt_builder::SdJwtBuilder;
+pub use crate::sd_jwt::sd_jwt_token::SdJwt;
+pub use crate::sd_jwt::sd_jwt_validator::SdJwtValidator;
+pub use crate::sd_jwt::sd_jwt_vc::SdJwtVc;
+pub use crate::sd_jwt::sd_jwt_vc_builder::SdJwtVcBuilder;
+pub use crate::sd_jwt::sd_jwt_vc_validator::SdJwtVcValidator;
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail};
+
+use crate::jwk::Jwk;
+use crate::jws::{self, JwsVerifier};
+use crate::jwt::JwtPayload;
+use crate::util::{self, HashAlgorithm};
+use crate::{JoseError, Map, Value};
+
+/// The claim name that contains the digests of the selectively disclosable claims.
+pub const SD_CLAIM: &str = "_sd";
+
+/// The claim name of the hash algorithm used for the digests.
+pub const SD_ALG_CLAIM: &str = "_sd_alg";
+
+/// The key name of a selectively disclosable array element.
+pub const ARRAY_ELEMENT_KEY: &str = "...";
+
+/// The media type of a key binding JWT.
+pub const KEY_BINDING_JWT_TYPE: &str = "kb+jwt";
+
+/// The media type of a SD-JWT VC.
+pub const DC_SD_JWT_TYPE: &str = "dc+sd-jwt";
+
+/// The former media type of a SD-JWT VC that is still accepted.
+pub const VC_SD_JWT_TYPE: &str = "vc+sd-jwt";
+
+/// The claims of a SD-JWT VC that must not be selectively disclosable.
+pub const NON_DISCLOSABLE_VC_CLAIMS: [&str; 6] = ["iss", "nbf", "exp", "cnf", "vct", "status"];
+
+/// Return the holder's public key of the confirmation claim (cnf.jwk).
+///
+/// # Arguments
+///
+/// * `payload` - a processed payload of the issuer-signed JWT.
+pub fn confirmation_key(payload: &JwtPayload) -> Result<Jwk, JoseError> {
+    (|| -> anyhow::Result<Jwk> {
+        let cnf = match payload.confirmation() {
+            Some(val) => val,
+            None => bail!(JoseError::MissingClaim("cnf".to_string())),
+        };
+        let jwk = match cnf.jwk() {
+            Some(val) => val,
+            None => bail!(JoseError::MissingClaim("cnf.jwk".to_string())),
+        };
+        if jwk.key_type() == "oct" || jwk.parameter("d").is_some() {
+            bail!("Key cnf.jwk must be a public key.");
+        }
+        Ok(jwk)
+    })()
+    .map_err(|err| match err.downcast::<JoseError>() {
+        Ok(err) => err,
+        Err(err) => JoseError::InvalidClaim(err),
+    })
+}
+
+/// Return a verifier of the key binding JWT from the confirmation claim (cnf.jwk).
+///
+/// # Arguments
+///
+/// * `payload` - a processed payload of the issuer-signed JWT.
+/// * `alg` - a alg header claim of the key binding JWT.
+pub fn verifier_from_confirmation(
+    payload: &JwtPayload,
+    alg: &str,
+) -> Result<Box<dyn JwsVerifier>, JoseError> {
+    let jwk = confirmation_key(payload)?;
+    jws::verifier_from_jwk(alg, &jwk)
+}
+
+/// Return the hash algorithm name for the _sd_alg claim.
+///
+/// # Arguments
+///
+/// * `hash_algorithm` - a hash algorithm.
+pub fn hash_algorithm_name(hash_algorithm: HashAlgorithm) -> &'static str {
+    match hash_algorithm {
+        HashAlgorithm::Sha1 => "sha-1",
+        HashAlgorithm::Sha256 => "sha-256",
+        HashAlgorithm::Sha384 => "sha-384",
+        HashAlgorithm::Sha512 => "sha-512",
+    }
+}
+
+pub(crate) fn hash_algorithm_from_name(name: &str) -> anyhow::Result<HashAlgorithm> {
+    let hash_algorithm = match name {
+        "sha-256" => HashAlgorithm::Sha256,
+        "sha-384" => HashAlgorithm::Sha384,
+        "sha-512" => HashAlgorithm::Sha512,
+        _ => bail!("The _sd_alg is not supported: {}", name),
+    };
+    Ok(hash_algorithm)
+}
+
+pub(crate) fn hash_algorithm_of(claims: &Map<String, Value>) -> anyhow::Result<HashAlgorithm> {
+    match claims.get(SD_ALG_CLAIM) {
+        Some(Value::String(val)) => hash_algorithm_from_name(val),
+        Some(val) => bail!("Key _sd_alg must be a string: {}", val),
+        None => Ok(HashAlgorithm::Sha256),
+    }
+}
+
+pub(crate) fn digest(hash_algorithm: HashAlgorithm, input: &[u8]) -> String {
+    let digest = openssl::hash::hash(hash_algorithm.message_digest(), input).unwrap();
+    util::encode_base64_urlsafe_nopad(digest)
+}
+
+pub(crate) fn check_claim_name(name: &str) -> anyhow::Result<()> {
+    if name == SD_CLAIM || name == SD_ALG_CLAIM || name == ARRAY_ELEMENT_KEY {
+        bail!("The claim name {} cannot be selectively disclosable.", name);
+    }
+    Ok(())
+}
+
+/// Return the segments of a JSON pointer (RFC 6901).
+pub(crate) fn parse_pointer(pointer: &str) -> anyhow::Result<Vec<String>> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        bail!("The JSON pointer must start with '/': {}", pointer);
+    }
+
+    let segments = pointer[1..]
+        .split('/')
+        .map(|val| val.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    Ok(segments)
+}
+
+/// The claims that the disclosures are embedded in.
+pub(crate) struct Processed {
+    pub claims: Map<String, Value>,
+    /// The paths of each disclosure in the processed claims.
+    pub paths: Vec<Vec<String>>,
+}
+
+/// Return the claims that the disclosures are embedded in (RFC 9901 section 7.1).
+pub(crate) fn process(
+    claims: &Map<String, Value>,
+    disclosures: &[Disclosure],
+) -> anyhow::Result<Processed> {
+    let hash_algorithm = hash_algorithm_of(claims)?;
+
+    let mut digests = HashMap::new();
+    for (i, disclosure) in disclosures.iter().enumerate() {
+        if digests
+            .insert(disclosure.digest(hash_algorithm), i)
+            .is_some()
+        {
+            bail!("The disclosure is duplicated: {}", disclosure);
+        }
+    }
+
+    let mut processor = Processor {
+        disclosures,
+        digests,
+        paths: vec![None; disclosures.len()],
+    };
+    let mut path = Vec::new();
+    let mut processed = processor.process_object(claims, &mut path)?;
+    processed.remove(SD_ALG_CLAIM);
+
+    let paths = processor
+        .paths
+        .into_iter()
+        .zip(disclosures)
+        .map(|(path, disclosure)| {
+            path.ok_or_else(|| anyhow!("The disclosure is not referenced: {}", disclosure))
+        })
+        .collect::<anyhow::Result<Vec<Vec<String>>>>()?;
+
+    Ok(Processed {
+        claims: processed,
+        paths,
+    })
+}
+
+struct Processor<'a> {
+    disclosures: &'a [Disclosure],
+    digests: HashMap<String, usize>,
+    paths: Vec<Option<Vec<String>>>,
+}
+
+impl<'a> Processor<'a> {
+    fn take(&mut self, digest: &str) -> anyhow::Result<Option<(usize, &'a Disclosure)>> {
+        let index = match self.digests.get(digest) {
+            Some(val) => *val,
+            None => return Ok(None),
+        };
+        if self.paths[index].is_some() {
+            bail!("The digest is referenced more than once: {}", digest);
+        }
+        self.paths[index] = Some(Vec::new());
+        Ok(Some((index, &self.disclosures[index])))
+    }
+
+    fn process_value(&mut self, value: &Value, path: &mut Vec<String>) -> anyhow::Result<Value> {
+        let processed = match value {
+            Value::Object(val) => Value::Object(self.process_object(val, path)?),
+            Value::Array(val) => Value::Array(self.process_array(val, path)?),
+            val => val.clone(),
+        };
+        Ok(processed)
+    }
+
+    fn process_object(
+        &mut self,
+        map: &Map<String, Value>,
+        path: &mut Vec<String>,
+    ) -> anyhow::Result<Map<String, Value>> {
+        let mut processed = Map::new();
+        for (key, value) in map {
+            if key == SD_CLAIM {
+                continue;
+            }
+            path.push(key.clone());
+            let value = self.process_value(value, path)?;
+            path.pop();
+            processed.insert(key.clone(), value);
+        }
+
+        let digests = match map.get(SD_CLAIM) {
+            Some(Value::Array(vals)) => vals,
+            Some(val) => bail!("Key _sd must be a array: {}", val),
+            None => return Ok(processed),
+        };
+        for digest in digests {
+            let digest = match digest {
+                Value::String(val) => val,
+                val => bail!("The digest must be a string: {}", val),
+            };
+
+            let (index, disclosure) = match self.take(digest)? {
+                Some(val) => val,
+                None => continue,
+            };
+            let name = match disclosure.claim_name() {
+                Some(val) => val,
+                None => bail!(
+                    "The disclosure must be for a object property: {}",
+                    disclosure
+                ),
+            };
+            if processed.contains_key(name) {
+                bail!("The claim {} is already present.", name);
+            }
+
+            path.push(name.to_string());
+            self.paths[index] = Some(path.clone());
+            let value = self.process_value(disclosure.claim_value(), path)?;
+            path.pop();
+            processed.insert(name.to_string(), value);
+        }
+
+        Ok(processed)
+    }
+
+    fn process_array(
+        &mut self,
+        array: &[Value],
+        path: &mut Vec<String>,
+    ) -> anyhow::Result<Vec<Value>> {
+        let mut processed = Vec::with_capacity(array.len());
+        for value in array {
+            path.push(processed.len().to_string());
+            match array_element_digest(value)? {
+                Some(digest) => {
+                    if let Some((index, disclosure)) = self.take(digest)? {
+                        if !disclosure.is_array_element() {
+                            bail!("The disclosure must be for a array element: {}", disclosure);
+                        }
+                        self.paths[index] = Some(path.clone());
+                        let value = self.process_value(disclosure.claim_value(), path)?;
+                        processed.push(value);
+                    }
+                }
+                None => {
+                    let value = self.process_value(value, path)?;
+                    processed.push(value);
+                }
+            }
+            path.pop();
+        }
+        Ok(processed)
+    }
+}
+
+fn array_element_digest(value: &Value) -> anyhow::Result<Option<&str>> {
+    match value {
+        Value::Object(map) if map.contains_key(ARRAY_ELEMENT_KEY) => {
+            if map.len() != 1 {
+                bail!("The array element must contain only the key '...'.");
+            }
+            match map.get(ARRAY_ELEMENT_KEY) {
+                Some(Value::String(val)) => Ok(Some(val)),
+                Some(val) => bail!("The digest must be a string: {}", val),
+                None => unreachable!(),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::sd_jwt;
+
+    #[test]
+    fn test_parse_pointer() -> Result<()> {
+        assert!(sd_jwt::parse_pointer("")?.is_empty());
+        assert_eq!(
+            sd_jwt::parse_pointer("/address/street_address")?,
+            vec!["address", "street_address"]
+        );
+        assert_eq!(sd_jwt::parse_pointer("/a~1b/m~0n")?, vec!["a/b", "m~n"]);
+        assert!(sd_jwt::parse_pointer("address").is_err());
+
+        Ok(())
+    }
+}
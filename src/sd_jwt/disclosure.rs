@@ -0,0 +1,201 @@
+use std::convert::Into;
+use std::fmt::Display;
+
+use anyhow::bail;
+
+use crate::util::{self, HashAlgorithm};
+use crate::{JoseError, Value};
+
+/// Represents a disclosure of a selectively disclosable claim (RFC 9901 section 4.2).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Disclosure {
+    encoded: String,
+    salt: String,
+    claim_name: Option<String>,
+    claim_value: Value,
+}
+
+impl Disclosure {
+    /// Return a new Disclosure.
+    ///
+    /// # Arguments
+    ///
+    /// * `salt` - a salt string.
+    /// * `claim_name` - a claim name for a object property or None for a array element.
+    /// * `claim_value` - a claim value.
+    pub fn new(
+        salt: impl Into<String>,
+        claim_name: Option<String>,
+        claim_value: Value,
+    ) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            let salt = salt.into();
+            let mut array = vec![Value::String(salt.clone())];
+            if let Some(name) = &claim_name {
+                super::check_claim_name(name)?;
+                array.push(Value::String(name.clone()));
+            }
+            array.push(claim_value.clone());
+
+            let json = serde_json::to_string(&Value::Array(array))?;
+            let encoded = util::encode_base64_urlsafe_nopad(json);
+
+            Ok(Self {
+                encoded,
+                salt,
+                claim_name,
+                claim_value,
+            })
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })
+    }
+
+    /// Return a new Disclosure for a object property with a random salt.
+    ///
+    /// # Arguments
+    ///
+    /// * `claim_name` - a claim name.
+    /// * `claim_value` - a claim value.
+    pub fn new_object_property(
+        claim_name: impl Into<String>,
+        claim_value: Value,
+    ) -> Result<Self, JoseError> {
+        Self::new(random_salt(), Some(claim_name.into()), claim_value)
+    }
+
+    /// Return a new Disclosure for a array element with a random salt.
+    ///
+    /// # Arguments
+    ///
+    /// * `claim_value` - a array element.
+    pub fn new_array_element(claim_value: Value) -> Result<Self, JoseError> {
+        Self::new(random_salt(), None, claim_value)
+    }
+
+    /// Return the Disclosure decoded from a base64url encoded string.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a base64url encoded disclosure.
+    pub fn from_encoded(input: impl Into<String>) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            let encoded = input.into();
+            let json = util::decode_base64_urlsafe_no_pad(&encoded)?;
+            let array = match serde_json::from_slice::<Value>(&json)? {
+                Value::Array(val) => val,
+                val => bail!("The disclosure must be a array: {}", val),
+            };
+
+            let mut iter = array.into_iter();
+            let (salt, claim_name, claim_value) =
+                match (iter.next(), iter.next(), iter.next(), iter.next()) {
+                    (Some(Value::String(salt)), Some(value), None, None) => (salt, None, value),
+                    (Some(Value::String(salt)), Some(Value::String(name)), Some(value), None) => {
+                        super::check_claim_name(&name)?;
+                        (salt, Some(name), value)
+                    }
+                    _ => bail!("The disclosure format is invalid: {}", encoded),
+                };
+
+            Ok(Self {
+                encoded,
+                salt,
+                claim_name,
+                claim_value,
+            })
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })
+    }
+
+    /// Return the base64url encoded string representation.
+    pub fn encoded(&self) -> &str {
+        &self.encoded
+    }
+
+    /// Return the salt.
+    pub fn salt(&self) -> &str {
+        &self.salt
+    }
+
+    /// Return the claim name if the disclosure is for a object property.
+    pub fn claim_name(&self) -> Option<&str> {
+        self.claim_name.as_deref()
+    }
+
+    /// Return the claim value.
+    pub fn claim_value(&self) -> &Value {
+        &self.claim_value
+    }
+
+    /// Return true if the disclosure is for a array element.
+    pub fn is_array_element(&self) -> bool {
+        self.claim_name.is_none()
+    }
+
+    /// Return the base64url encoded digest of the disclosure.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_algorithm` - a hash algorithm specified by the _sd_alg claim.
+    pub fn digest(&self, hash_algorithm: HashAlgorithm) -> String {
+        super::digest(hash_algorithm, self.encoded.as_bytes())
+    }
+}
+
+impl Display for Disclosure {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        fmt.write_str(&self.encoded)
+    }
+}
+
+fn random_salt() -> String {
+    util::encode_base64_urlsafe_nopad(util::random_bytes(16))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::sd_jwt::Disclosure;
+    use crate::util::HashAlgorithm;
+
+    #[test]
+    fn test_disclosure_digest() -> Result<()> {
+        // RFC 9901 section 4.2.1 and 4.2.3
+        let disclosure = Disclosure::from_encoded(
+            "WyJfMjZiYzRMVC1hYzZxMktJNmNCVzVlcyIsICJmYW1pbHlfbmFtZSIsICJNw7ZiaXVzIl0",
+        )?;
+        assert_eq!(disclosure.salt(), "_26bc4LT-ac6q2KI6cBW5es");
+        assert_eq!(disclosure.claim_name(), Some("family_name"));
+        assert_eq!(disclosure.claim_value(), &json!("Möbius"));
+        assert_eq!(
+            disclosure.digest(HashAlgorithm::Sha256),
+            "X9yH0Ajrdm1Oij4tWso9UzzKJvPoDxwmuEcO3XAdRC0"
+        );
+
+        // RFC 9901 section 4.2.2
+        let disclosure = Disclosure::from_encoded("WyJsa2x4RjVqTVlsR1RQVW92TU5JdkNBIiwgIkZSIl0")?;
+        assert!(disclosure.is_array_element());
+        assert_eq!(disclosure.claim_value(), &json!("FR"));
+        assert_eq!(
+            disclosure.digest(HashAlgorithm::Sha256),
+            "w0I8EKcdCtUPkGCNUrfwVp2xEgNjtoIDlOxc9-PlOhs"
+        );
+
+        let disclosure = Disclosure::new_object_property("given_name", json!("Erika"))?;
+        let decoded = Disclosure::from_encoded(disclosure.encoded())?;
+        assert_eq!(disclosure, decoded);
+
+        assert!(Disclosure::new_object_property("_sd", json!("x")).is_err());
+        assert!(Disclosure::from_encoded("WyJzYWx0Il0").is_err());
+
+        Ok(())
+    }
+}
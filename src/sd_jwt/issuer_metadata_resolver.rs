@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::convert::Into;
+use std::fmt::Debug;
+
+use crate::jwk::JwkSet;
+use crate::JoseError;
+
+/// Represent a source of the issuer's public keys for SD-JWT VC.
+///
+/// A implementation typically fetches the JWT VC Issuer Metadata
+/// (/.well-known/jwt-vc-issuer) and returns its jwks or the keys at jwks_uri.
+pub trait IssuerMetadataResolver: Debug + Send + Sync {
+    /// Return the JWK set of a issuer.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a issuer identifier of the iss claim.
+    fn resolve(&self, issuer: &str) -> Result<JwkSet, JoseError>;
+}
+
+/// A issuer metadata resolver that returns JWK sets registered in advance.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryIssuerMetadataResolver {
+    issuers: HashMap<String, JwkSet>,
+}
+
+impl MemoryIssuerMetadataResolver {
+    /// Return a new empty MemoryIssuerMetadataResolver.
+    pub fn new() -> Self {
+        Self {
+            issuers: HashMap::new(),
+        }
+    }
+
+    /// Register a JWK set of a issuer.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a issuer identifier.
+    /// * `jwk_set` - a JWK set of the issuer.
+    pub fn add_issuer(&mut self, issuer: impl Into<String>, jwk_set: JwkSet) {
+        self.issuers.insert(issuer.into(), jwk_set);
+    }
+}
+
+impl IssuerMetadataResolver for MemoryIssuerMetadataResolver {
+    fn resolve(&self, issuer: &str) -> Result<JwkSet, JoseError> {
+        match self.issuers.get(issuer) {
+            Some(val) => Ok(val.clone()),
+            None => Err(JoseError::IssuerMismatch(issuer.to_string())),
+        }
+    }
+}
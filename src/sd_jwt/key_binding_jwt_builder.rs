@@ -0,0 +1,86 @@
+use std::convert::Into;
+use std::time::SystemTime;
+
+use crate::jws::{JwsHeader, JwsSigner};
+use crate::jwt::{JwtContext, JwtPayload};
+use crate::sd_jwt::{SdJwt, KEY_BINDING_JWT_TYPE};
+use crate::{JoseError, Value};
+
+/// Represents key binding JWT builder for a holder (RFC 9901 section 4.3).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct KeyBindingJwtBuilder {
+    context: JwtContext,
+    nonce: String,
+    audience: String,
+    issued_at: Option<SystemTime>,
+}
+
+impl KeyBindingJwtBuilder {
+    /// Return a new KeyBindingJwtBuilder.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - a nonce provided by the verifier.
+    /// * `audience` - a identifier of the verifier.
+    pub fn new(nonce: impl Into<String>, audience: impl Into<String>) -> Self {
+        Self {
+            context: JwtContext::new(),
+            nonce: nonce.into(),
+            audience: audience.into(),
+            issued_at: None,
+        }
+    }
+
+    /// Set a value for issued at claim (iat). The default value is the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issued time
+    pub fn set_issued_at(&mut self, value: SystemTime) {
+        self.issued_at = Some(value);
+    }
+
+    /// Return the payload and the header of the key binding JWT for the presentation.
+    ///
+    /// # Arguments
+    ///
+    /// * `sd_jwt` - a SD-JWT that contains the disclosures to be presented.
+    pub fn build(&self, sd_jwt: &SdJwt) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        let hash_algorithm = sd_jwt
+            .unverified_hash_algorithm()
+            .map_err(JoseError::InvalidJwtFormat)?;
+
+        let mut payload = JwtPayload::new();
+        let issued_at = match self.issued_at {
+            Some(val) => val,
+            None => SystemTime::now(),
+        };
+        payload.set_issued_at(&issued_at);
+        payload.set_audience(vec![self.audience.clone()]);
+        payload.set_claim("nonce", Some(Value::String(self.nonce.clone())))?;
+        payload.set_claim(
+            "sd_hash",
+            Some(Value::String(sd_jwt.sd_hash(hash_algorithm))),
+        )?;
+
+        let mut header = JwsHeader::new();
+        header.set_token_type(KEY_BINDING_JWT_TYPE);
+
+        Ok((payload, header))
+    }
+
+    /// Return the SD-JWT+KB that the key binding JWT signed by the holder's key is appended to.
+    ///
+    /// # Arguments
+    ///
+    /// * `sd_jwt` - a SD-JWT that contains the disclosures to be presented.
+    /// * `signer` - a signer object of the holder's key.
+    pub fn sign(&self, sd_jwt: &SdJwt, signer: &dyn JwsSigner) -> Result<SdJwt, JoseError> {
+        let (payload, header) = self.build(sd_jwt)?;
+        let key_binding_jwt = self.context.encode_with_signer(&payload, &header, signer)?;
+
+        let mut sd_jwt = sd_jwt.clone();
+        sd_jwt.set_key_binding_jwt(Some(key_binding_jwt));
+        Ok(sd_jwt)
+    }
+}
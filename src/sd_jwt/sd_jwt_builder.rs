@@ -0,0 +1,266 @@
+use std::cmp::Reverse;
+use std::convert::Into;
+
+use anyhow::bail;
+
+use crate::jwk::Jwk;
+use crate::jws::{JwsHeader, JwsSigner};
+use crate::jwt::{Confirmation, JwtContext, JwtPayload};
+use crate::sd_jwt::{self, Disclosure, SdJwt, ARRAY_ELEMENT_KEY, SD_ALG_CLAIM, SD_CLAIM};
+use crate::util::{self, HashAlgorithm};
+use crate::{JoseError, Map, Value};
+
+/// Represents SD-JWT builder for a issuer (RFC 9901 section 4).
+///
+/// Claims to be selectively disclosable are specified by JSON pointers
+/// that refer the plain payload.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SdJwtBuilder {
+    context: JwtContext,
+    payload: JwtPayload,
+    header: JwsHeader,
+    hash_algorithm: HashAlgorithm,
+    concealed: Vec<Vec<String>>,
+    decoys: Vec<(Vec<String>, usize)>,
+}
+
+impl SdJwtBuilder {
+    /// Return a new SdJwtBuilder.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - a plain payload that contains all claims.
+    pub fn new(payload: JwtPayload) -> Self {
+        Self {
+            context: JwtContext::new(),
+            payload,
+            header: JwsHeader::new(),
+            hash_algorithm: HashAlgorithm::Sha256,
+            concealed: Vec::new(),
+            decoys: Vec::new(),
+        }
+    }
+
+    /// Set a hash algorithm for the digests. The default value is SHA-256.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a hash algorithm.
+    pub fn set_hash_algorithm(&mut self, value: HashAlgorithm) -> Result<(), JoseError> {
+        if let HashAlgorithm::Sha1 = value {
+            return Err(JoseError::UnsupportedSignatureAlgorithm(anyhow::anyhow!(
+                "The _sd_alg is not supported: {}",
+                sd_jwt::hash_algorithm_name(value)
+            )));
+        }
+        self.hash_algorithm = value;
+        Ok(())
+    }
+
+    /// Make a claim selectively disclosable.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer` - a JSON pointer to a object property or a array element like "/address/street_address".
+    pub fn conceal(&mut self, pointer: &str) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            let segments = sd_jwt::parse_pointer(pointer)?;
+            match segments.last() {
+                Some(val) => sd_jwt::check_claim_name(val)?,
+                None => bail!("The root object cannot be selectively disclosable."),
+            }
+            if !self.concealed.contains(&segments) {
+                self.concealed.push(segments);
+            }
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+
+    /// Add decoy digests to a object or a array.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer` - a JSON pointer to a object or a array. "" means the root object.
+    /// * `count` - a number of decoy digests.
+    pub fn add_decoys(&mut self, pointer: &str, count: usize) -> Result<(), JoseError> {
+        let segments = sd_jwt::parse_pointer(pointer).map_err(JoseError::InvalidClaim)?;
+        self.decoys.push((segments, count));
+        Ok(())
+    }
+
+    /// Set a value for a claim of the plain payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of claim
+    /// * `value` - a value of claim
+    pub fn set_claim(&mut self, key: &str, value: Option<Value>) -> Result<(), JoseError> {
+        self.payload.set_claim(key, value)
+    }
+
+    /// Set a holder's public key for the confirmation claim (cnf.jwk)
+    /// that is used to verify the key binding JWT.
+    ///
+    /// # Arguments
+    ///
+    /// * `jwk` - a holder's key.
+    pub fn set_confirmation_key(&mut self, jwk: &Jwk) -> Result<(), JoseError> {
+        let mut cnf = Confirmation::new();
+        cnf.set_jwk(jwk)?;
+        self.payload.set_confirmation(cnf);
+        Ok(())
+    }
+
+    /// Set a value for token type header claim (typ) like "example+sd-jwt".
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a token type
+    pub fn set_token_type(&mut self, value: impl Into<String>) {
+        self.header.set_token_type(value);
+    }
+
+    /// Set a value for key ID header claim (kid).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.header.set_key_id(value);
+    }
+
+    /// Return the payload, the header and the disclosures of the SD-JWT.
+    pub fn build(&self) -> Result<(JwtPayload, JwsHeader, Vec<Disclosure>), JoseError> {
+        (|| -> anyhow::Result<(JwtPayload, JwsHeader, Vec<Disclosure>)> {
+            if self.payload.claims_set().contains_key(SD_ALG_CLAIM) {
+                bail!("The payload must not contain the _sd_alg claim.");
+            }
+
+            let mut root = Value::Object(self.payload.claims_set().clone());
+
+            for (segments, count) in &self.decoys {
+                match lookup_mut(&mut root, segments)? {
+                    Value::Object(map) => {
+                        for _ in 0..*count {
+                            add_digest(map, decoy_digest(self.hash_algorithm))?;
+                        }
+                    }
+                    Value::Array(array) => {
+                        for _ in 0..*count {
+                            array.push(array_element(decoy_digest(self.hash_algorithm)));
+                        }
+                    }
+                    _ => bail!("The decoys must be added to a object or a array."),
+                }
+            }
+
+            // Deeper claims are concealed first so that their digests are
+            // contained in the disclosures of their parents.
+            let mut concealed: Vec<&Vec<String>> = self.concealed.iter().collect();
+            concealed.sort_by_key(|segments| Reverse(segments.len()));
+
+            let mut disclosures = Vec::with_capacity(concealed.len());
+            for segments in concealed {
+                let (last, parent) = segments.split_last().unwrap();
+                let disclosure = match lookup_mut(&mut root, parent)? {
+                    Value::Object(map) => {
+                        let value = match map.remove(last) {
+                            Some(val) => val,
+                            None => bail!("The claim is not found: {}", last),
+                        };
+                        let disclosure = Disclosure::new_object_property(last, value)?;
+                        add_digest(map, disclosure.digest(self.hash_algorithm))?;
+                        disclosure
+                    }
+                    Value::Array(array) => {
+                        let element = match last.parse::<usize>().ok() {
+                            Some(index) => array.get_mut(index),
+                            None => None,
+                        };
+                        let element = match element {
+                            Some(val) => val,
+                            None => bail!("The array element is not found: {}", last),
+                        };
+                        let disclosure = Disclosure::new_array_element(element.clone())?;
+                        *element = array_element(disclosure.digest(self.hash_algorithm));
+                        disclosure
+                    }
+                    _ => bail!("The parent of {} must be a object or a array.", last),
+                };
+                disclosures.push(disclosure);
+            }
+
+            let mut claims = match root {
+                Value::Object(val) => val,
+                _ => unreachable!(),
+            };
+            claims.insert(
+                SD_ALG_CLAIM.to_string(),
+                Value::String(sd_jwt::hash_algorithm_name(self.hash_algorithm).to_string()),
+            );
+            let payload = JwtPayload::from_map(claims)?;
+
+            Ok((payload, self.header.clone(), disclosures))
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+
+    /// Return the SD-JWT signed by the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    pub fn sign(&self, signer: &dyn JwsSigner) -> Result<SdJwt, JoseError> {
+        let (payload, header, disclosures) = self.build()?;
+        let jwt = self.context.encode_with_signer(&payload, &header, signer)?;
+        Ok(SdJwt::new(jwt, disclosures, None))
+    }
+}
+
+fn lookup_mut<'a>(root: &'a mut Value, segments: &[String]) -> anyhow::Result<&'a mut Value> {
+    let mut current = root;
+    for segment in segments {
+        current = match current {
+            Value::Object(map) => map.get_mut(segment),
+            Value::Array(array) => match segment.parse::<usize>() {
+                Ok(index) => array.get_mut(index),
+                Err(_) => None,
+            },
+            _ => None,
+        }
+        .ok_or_else(|| anyhow::anyhow!("The claim is not found: {}", segment))?;
+    }
+    Ok(current)
+}
+
+fn add_digest(map: &mut Map<String, Value>, digest: String) -> anyhow::Result<()> {
+    let entry = map
+        .entry(SD_CLAIM)
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let digests = match entry {
+        Value::Array(val) => val,
+        _ => bail!("Key _sd must be a array."),
+    };
+    digests.push(Value::String(digest));
+    // The digests are sorted to hide the original order of the claims.
+    digests.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+    Ok(())
+}
+
+fn array_element(digest: String) -> Value {
+    let mut map = Map::new();
+    map.insert(ARRAY_ELEMENT_KEY.to_string(), Value::String(digest));
+    Value::Object(map)
+}
+
+fn decoy_digest(hash_algorithm: HashAlgorithm) -> String {
+    let salt = util::encode_base64_urlsafe_nopad(util::random_bytes(16));
+    sd_jwt::digest(hash_algorithm, salt.as_bytes())
+}
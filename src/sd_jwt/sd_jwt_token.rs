@@ -0,0 +1,223 @@
+use std::convert::Into;
+use std::fmt::Display;
+
+use anyhow::bail;
+
+use crate::sd_jwt::{self, Disclosure};
+use crate::util::{self, HashAlgorithm};
+use crate::{JoseError, Map, Value};
+
+/// Represents a SD-JWT: a issuer-signed JWT, disclosures and
+/// a optional key binding JWT (RFC 9901 section 4).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SdJwt {
+    issuer_signed_jwt: String,
+    disclosures: Vec<Disclosure>,
+    key_binding_jwt: Option<String>,
+}
+
+impl SdJwt {
+    /// Return a new SdJwt.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer_signed_jwt` - a issuer-signed JWT string representation.
+    /// * `disclosures` - disclosures.
+    /// * `key_binding_jwt` - a key binding JWT string representation.
+    pub fn new(
+        issuer_signed_jwt: impl Into<String>,
+        disclosures: Vec<Disclosure>,
+        key_binding_jwt: Option<String>,
+    ) -> Self {
+        Self {
+            issuer_signed_jwt: issuer_signed_jwt.into(),
+            disclosures,
+            key_binding_jwt,
+        }
+    }
+
+    /// Return the SdJwt parsed from the '~' separated serialization.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a SD-JWT or SD-JWT+KB string representation.
+    pub fn parse(input: impl AsRef<str>) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            let input = input.as_ref();
+            let mut parts: Vec<&str> = input.split('~').collect();
+            if parts.len() < 2 {
+                bail!("The SD-JWT must contain at least one '~'.");
+            }
+
+            let issuer_signed_jwt = parts.remove(0);
+            if !is_compact_jws(issuer_signed_jwt) {
+                bail!("The issuer-signed JWT must be a compact JWS.");
+            }
+
+            let key_binding_jwt = match parts.pop() {
+                Some("") | None => None,
+                Some(val) if is_compact_jws(val) => Some(val.to_string()),
+                Some(_) => bail!("The key binding JWT must be a compact JWS."),
+            };
+
+            let mut disclosures = Vec::with_capacity(parts.len());
+            for part in parts {
+                if part.is_empty() {
+                    bail!("The disclosure must not be empty.");
+                }
+                disclosures.push(Disclosure::from_encoded(part)?);
+            }
+
+            Ok(Self {
+                issuer_signed_jwt: issuer_signed_jwt.to_string(),
+                disclosures,
+                key_binding_jwt,
+            })
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })
+    }
+
+    /// Return the issuer-signed JWT string representation.
+    pub fn issuer_signed_jwt(&self) -> &str {
+        &self.issuer_signed_jwt
+    }
+
+    /// Return the disclosures.
+    pub fn disclosures(&self) -> &[Disclosure] {
+        &self.disclosures
+    }
+
+    /// Return the key binding JWT string representation.
+    pub fn key_binding_jwt(&self) -> Option<&str> {
+        self.key_binding_jwt.as_deref()
+    }
+
+    /// Set the key binding JWT string representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key binding JWT string representation.
+    pub fn set_key_binding_jwt(&mut self, value: Option<String>) {
+        self.key_binding_jwt = value;
+    }
+
+    /// Return the '~' separated serialization.
+    pub fn serialize(&self) -> String {
+        let mut output = self.serialize_without_key_binding();
+        if let Some(val) = &self.key_binding_jwt {
+            output.push_str(val);
+        }
+        output
+    }
+
+    /// Return the base64url encoded digest of the serialization without
+    /// the key binding JWT for the sd_hash claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_algorithm` - a hash algorithm specified by the _sd_alg claim.
+    pub fn sd_hash(&self, hash_algorithm: HashAlgorithm) -> String {
+        sd_jwt::digest(
+            hash_algorithm,
+            self.serialize_without_key_binding().as_bytes(),
+        )
+    }
+
+    /// Return the claims with the disclosures embedded, without verifying the signature.
+    ///
+    /// This is intended for a holder to inspect the claims before the presentation.
+    pub fn unverified_claims(&self) -> Result<Map<String, Value>, JoseError> {
+        (|| -> anyhow::Result<Map<String, Value>> {
+            let claims = self.decode_unverified_payload()?;
+            let processed = sd_jwt::process(&claims, &self.disclosures)?;
+            Ok(processed.claims)
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })
+    }
+
+    /// Return a new SdJwt for the presentation that contains only disclosures
+    /// needed to reveal claims of the specified JSON pointers.
+    ///
+    /// A JSON pointer refers the processed claims. All disclosures under the pointer
+    /// and disclosures of its parents are selected. The key binding JWT is not
+    /// contained in the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointers` - JSON pointers of the claims to be disclosed.
+    pub fn present(&self, pointers: &[&str]) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            let mut selected_paths = Vec::with_capacity(pointers.len());
+            for pointer in pointers {
+                selected_paths.push(sd_jwt::parse_pointer(pointer)?);
+            }
+
+            let claims = self.decode_unverified_payload()?;
+            let paths = sd_jwt::process(&claims, &self.disclosures)?.paths;
+
+            let disclosures = self
+                .disclosures
+                .iter()
+                .zip(paths.iter())
+                .filter(|(_, path)| {
+                    selected_paths
+                        .iter()
+                        .any(|selected| path.starts_with(selected) || selected.starts_with(path))
+                })
+                .map(|(disclosure, _)| disclosure.clone())
+                .collect();
+
+            Ok(Self {
+                issuer_signed_jwt: self.issuer_signed_jwt.clone(),
+                disclosures,
+                key_binding_jwt: None,
+            })
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })
+    }
+
+    pub(crate) fn unverified_hash_algorithm(&self) -> anyhow::Result<HashAlgorithm> {
+        let claims = self.decode_unverified_payload()?;
+        sd_jwt::hash_algorithm_of(&claims)
+    }
+
+    fn serialize_without_key_binding(&self) -> String {
+        let mut output = self.issuer_signed_jwt.clone();
+        output.push('~');
+        for disclosure in &self.disclosures {
+            output.push_str(disclosure.encoded());
+            output.push('~');
+        }
+        output
+    }
+
+    pub(crate) fn decode_unverified_payload(&self) -> anyhow::Result<Map<String, Value>> {
+        let payload = match self.issuer_signed_jwt.split('.').nth(1) {
+            Some(val) => val,
+            None => bail!("The issuer-signed JWT must be a compact JWS."),
+        };
+        let payload = util::decode_base64_urlsafe_no_pad(payload)?;
+        let claims: Map<String, Value> = serde_json::from_slice(&payload)?;
+        Ok(claims)
+    }
+}
+
+impl Display for SdJwt {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        fmt.write_str(&self.serialize())
+    }
+}
+
+fn is_compact_jws(input: &str) -> bool {
+    let parts: Vec<&str> = input.split('.').collect();
+    parts.len() == 3 && !parts[0].is_empty()
+}
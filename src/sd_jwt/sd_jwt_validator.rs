@@ -0,0 +1,506 @@
+use std::convert::Into;
+use std::time::Duration;
+
+use anyhow::bail;
+
+use crate::jws::{JwsHeader, JwsVerifier};
+use crate::jwt::{JwtContext, JwtHeaderValidator, JwtPayload, JwtPayloadValidator};
+use crate::sd_jwt::{self, SdJwt, KEY_BINDING_JWT_TYPE};
+use crate::{JoseError, Value};
+
+/// Represents SD-JWT validator for a verifier (RFC 9901 section 7).
+#[derive(Debug)]
+pub struct SdJwtValidator {
+    context: JwtContext,
+    payload_validator: JwtPayloadValidator,
+    key_binding_required: bool,
+    key_binding_context: JwtContext,
+    key_binding_validator: JwtPayloadValidator,
+}
+
+impl SdJwtValidator {
+    /// Return a new SdJwtValidator.
+    pub fn new() -> Self {
+        let mut header_validator = JwtHeaderValidator::new();
+        header_validator.set_token_type(KEY_BINDING_JWT_TYPE);
+        let mut key_binding_context = JwtContext::new();
+        key_binding_context.set_header_validator(Some(header_validator));
+
+        let mut key_binding_validator = JwtPayloadValidator::new();
+        key_binding_validator.set_max_age(Duration::from_secs(300));
+        key_binding_validator.add_required_claim("iat");
+        key_binding_validator.add_required_claim("aud");
+        key_binding_validator.add_required_claim("nonce");
+        key_binding_validator.add_required_claim("sd_hash");
+
+        Self {
+            context: JwtContext::new(),
+            payload_validator: JwtPayloadValidator::new(),
+            key_binding_required: false,
+            key_binding_context,
+            key_binding_validator,
+        }
+    }
+
+    /// Return the JWT context that is used for decoding the issuer-signed JWT.
+    pub fn context(&self) -> &JwtContext {
+        &self.context
+    }
+
+    /// Return the mutable JWT context that is used for decoding the issuer-signed JWT.
+    pub fn context_mut(&mut self) -> &mut JwtContext {
+        &mut self.context
+    }
+
+    /// Return the payload validator that is applied to the processed payload.
+    pub fn payload_validator(&self) -> &JwtPayloadValidator {
+        &self.payload_validator
+    }
+
+    /// Return the mutable payload validator that is applied to the processed payload.
+    pub fn payload_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.payload_validator
+    }
+
+    /// Set whether the key binding JWT is required. The default value is false.
+    ///
+    /// A key binding JWT is always verified when it is presented.
+    /// If it is required, the expected nonce and audience must also be set,
+    /// otherwise decoding fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - true if the key binding JWT is required.
+    pub fn set_key_binding_required(&mut self, value: bool) {
+        self.key_binding_required = value;
+    }
+
+    /// Return whether the key binding JWT is required.
+    pub fn is_key_binding_required(&self) -> bool {
+        self.key_binding_required
+    }
+
+    /// Set a expected value for nonce claim of the key binding JWT.
+    ///
+    /// If it is not set, any nonce is accepted. So callers must set it
+    /// to prevent the replay of a presentation.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a nonce provided by the verifier.
+    pub fn set_nonce(&mut self, value: impl Into<String>) {
+        self.key_binding_validator
+            .set_claim("nonce", Value::String(value.into()));
+    }
+
+    /// Set a expected value for audience claim of the key binding JWT.
+    ///
+    /// If it is not set, any audience is accepted. So callers must set it
+    /// to prevent a presentation for other verifiers.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a identifier of the verifier.
+    pub fn set_audience(&mut self, value: impl Into<String>) {
+        self.key_binding_validator.set_audience(value);
+    }
+
+    /// Return the mutable payload validator that is applied to the key binding JWT.
+    ///
+    /// The max age of the iat claim is 300 seconds by default.
+    pub fn key_binding_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.key_binding_validator
+    }
+
+    /// Return the processed payload and the header of the SD-JWT verified by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a SD-JWT string representation.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_with_verifier(
+        &self,
+        input: impl AsRef<str>,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError> {
+        self.decode_with_verifier_selector(input, |_header| Ok(Some(verifier)))
+    }
+
+    /// Return the processed payload and the header of the SD-JWT verified
+    /// by the selected verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a SD-JWT string representation.
+    /// * `selector` - a function for selecting the verifying algorithm.
+    pub fn decode_with_verifier_selector<'a, F>(
+        &self,
+        input: impl AsRef<str>,
+        selector: F,
+    ) -> Result<(JwtPayload, JwsHeader), JoseError>
+    where
+        F: Fn(&JwsHeader) -> Result<Option<&'a dyn JwsVerifier>, JoseError>,
+    {
+        if self.key_binding_required {
+            if self.key_binding_validator.claim("nonce").is_none() {
+                return Err(JoseError::InvalidJwtFormat(anyhow::anyhow!(
+                    "The expected nonce of the key binding JWT is not set."
+                )));
+            }
+            if self.key_binding_validator.audience().is_none() {
+                return Err(JoseError::InvalidJwtFormat(anyhow::anyhow!(
+                    "The expected audience of the key binding JWT is not set."
+                )));
+            }
+        }
+
+        let sd_jwt = SdJwt::parse(input)?;
+        let (payload, header) = self
+            .context
+            .decode_with_verifier_selector(sd_jwt.issuer_signed_jwt(), selector)?;
+        let processed_payload = self.process(&sd_jwt, &payload, &header)?;
+
+        match sd_jwt.key_binding_jwt() {
+            Some(val) => self.verify_key_binding(&sd_jwt, val, &payload, &processed_payload)?,
+            None if self.key_binding_required => {
+                return Err(JoseError::InvalidJwtFormat(anyhow::anyhow!(
+                    "The key binding JWT is required."
+                )))
+            }
+            None => {}
+        }
+
+        Ok((processed_payload, header))
+    }
+
+    /// Return the processed payload that the disclosures are embedded in.
+    ///
+    /// # Arguments
+    ///
+    /// * `sd_jwt` - a parsed SD-JWT.
+    /// * `payload` - a verified payload of the issuer-signed JWT.
+    /// * `header` - a verified header of the issuer-signed JWT.
+    pub fn process(
+        &self,
+        sd_jwt: &SdJwt,
+        payload: &JwtPayload,
+        header: &JwsHeader,
+    ) -> Result<JwtPayload, JoseError> {
+        (|| -> anyhow::Result<JwtPayload> {
+            if let Some("none") | None = header.algorithm() {
+                bail!("The issuer-signed JWT must be signed.");
+            }
+
+            let processed = sd_jwt::process(payload.claims_set(), sd_jwt.disclosures())?;
+            let payload = JwtPayload::from_map(processed.claims)?;
+            self.payload_validator.validate(&payload)?;
+            Ok(payload)
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+
+    fn verify_key_binding(
+        &self,
+        sd_jwt: &SdJwt,
+        key_binding_jwt: &str,
+        payload: &JwtPayload,
+        processed_payload: &JwtPayload,
+    ) -> Result<(), JoseError> {
+        let kb_header = self.key_binding_context.decode_header(key_binding_jwt)?;
+        let alg = match kb_header.claim("alg") {
+            Some(Value::String(val)) if val != "none" => val,
+            _ => {
+                return Err(JoseError::InvalidJwtFormat(anyhow::anyhow!(
+                    "The key binding JWT must be signed."
+                )))
+            }
+        };
+        let verifier = sd_jwt::verifier_from_confirmation(processed_payload, alg)?;
+        let (kb_payload, _) = self
+            .key_binding_context
+            .decode_with_verifier(key_binding_jwt, verifier.as_ref())?;
+        self.key_binding_validator.validate(&kb_payload)?;
+
+        (|| -> anyhow::Result<()> {
+            let hash_algorithm = sd_jwt::hash_algorithm_of(payload.claims_set())?;
+            let expected = sd_jwt.sd_hash(hash_algorithm);
+            match kb_payload.claim("sd_hash") {
+                Some(Value::String(val)) if val == &expected => {}
+                Some(val) => bail!("Key sd_hash is invalid: {}", val),
+                None => bail!(JoseError::MissingClaim("sd_hash".to_string())),
+            }
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+}
+
+impl Default for SdJwtValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+
+    use std::time::{Duration, SystemTime};
+
+    use crate::jwk::alg::ec::EcCurve;
+    use crate::jwk::alg::ed::EdCurve;
+    use crate::jwk::Jwk;
+    use crate::jws::{EdDSA, JwsHeader, ES256};
+    use crate::jwt::{self, JwtPayload};
+    use crate::sd_jwt::{self, KeyBindingJwtBuilder, SdJwt, SdJwtBuilder, SdJwtValidator};
+    use crate::util::HashAlgorithm;
+    use crate::Map;
+
+    fn claims() -> Result<JwtPayload> {
+        let claims = json!({
+            "iss": "https://issuer.example.com",
+            "sub": "user_42",
+            "given_name": "John",
+            "family_name": "Doe",
+            "address": {
+                "street_address": "123 Main St",
+                "locality": "Anytown",
+                "country": "US"
+            },
+            "nationalities": ["US", "DE"]
+        });
+        let claims: Map<String, serde_json::Value> = serde_json::from_value(claims)?;
+        Ok(JwtPayload::from_map(claims)?)
+    }
+
+    #[test]
+    fn test_sd_jwt() -> Result<()> {
+        let keypair = ES256.generate_key_pair()?;
+        let signer = ES256.signer_from_der(keypair.to_der_private_key())?;
+        let verifier = ES256.verifier_from_der(keypair.to_der_public_key())?;
+
+        let mut builder = SdJwtBuilder::new(claims()?);
+        builder.set_token_type("example+sd-jwt");
+        builder.conceal("/given_name")?;
+        builder.conceal("/family_name")?;
+        builder.conceal("/address")?;
+        builder.conceal("/address/street_address")?;
+        builder.conceal("/nationalities/1")?;
+        builder.add_decoys("", 2)?;
+        builder.add_decoys("/nationalities", 1)?;
+        let sd_jwt = builder.sign(&signer)?;
+        assert_eq!(sd_jwt.disclosures().len(), 5);
+
+        let (issued_payload, _, _) = builder.build()?;
+        assert!(issued_payload.claim("given_name").is_none());
+        assert_eq!(issued_payload.claim("_sd_alg"), Some(&json!("sha-256")));
+
+        let serialized = sd_jwt.serialize();
+        assert!(serialized.ends_with('~'));
+        assert_eq!(SdJwt::parse(&serialized)?, sd_jwt);
+
+        let validator = SdJwtValidator::new();
+        let (payload, header) = validator.decode_with_verifier(&serialized, &verifier)?;
+        assert_eq!(header.token_type(), Some("example+sd-jwt"));
+        assert_eq!(payload.claims_set(), claims()?.claims_set());
+        assert_eq!(&sd_jwt.unverified_claims()?, claims()?.claims_set());
+
+        let presentation = sd_jwt.present(&["/address/locality", "/given_name"])?;
+        assert_eq!(presentation.disclosures().len(), 2);
+        let (payload, _) = validator.decode_with_verifier(presentation.serialize(), &verifier)?;
+        assert_eq!(payload.claim("given_name"), Some(&json!("John")));
+        assert!(payload.claim("family_name").is_none());
+        assert_eq!(
+            payload.claim("address"),
+            Some(&json!({"locality": "Anytown", "country": "US"}))
+        );
+        assert_eq!(payload.claim("nationalities"), Some(&json!(["US"])));
+
+        let presentation = sd_jwt.present(&["/address", "/nationalities"])?;
+        assert_eq!(presentation.disclosures().len(), 3);
+        let (payload, _) = validator.decode_with_verifier(presentation.serialize(), &verifier)?;
+        assert_eq!(payload.claim("address"), claims()?.claim("address"));
+        assert_eq!(payload.claim("nationalities"), Some(&json!(["US", "DE"])));
+
+        let mut duplicated = sd_jwt.disclosures().to_vec();
+        duplicated.push(duplicated[0].clone());
+        let invalid = SdJwt::new(sd_jwt.issuer_signed_jwt(), duplicated, None);
+        assert!(validator
+            .decode_with_verifier(invalid.serialize(), &verifier)
+            .is_err());
+
+        let mut other = SdJwtBuilder::new(claims()?);
+        other.conceal("/given_name")?;
+        let other = other.sign(&signer)?;
+        let invalid = SdJwt::new(
+            sd_jwt.issuer_signed_jwt(),
+            other.disclosures().to_vec(),
+            None,
+        );
+        assert!(validator
+            .decode_with_verifier(invalid.serialize(), &verifier)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sd_jwt_with_sha512() -> Result<()> {
+        let keypair = ES256.generate_key_pair()?;
+        let signer = ES256.signer_from_der(keypair.to_der_private_key())?;
+        let verifier = ES256.verifier_from_der(keypair.to_der_public_key())?;
+
+        let mut builder = SdJwtBuilder::new(claims()?);
+        builder.set_hash_algorithm(HashAlgorithm::Sha512)?;
+        builder.conceal("/sub")?;
+        let sd_jwt = builder.sign(&signer)?;
+
+        let validator = SdJwtValidator::new();
+        let (payload, _) = validator.decode_with_verifier(sd_jwt.serialize(), &verifier)?;
+        assert_eq!(payload.subject(), Some("user_42"));
+
+        assert!(builder.set_hash_algorithm(HashAlgorithm::Sha1).is_err());
+        assert!(builder.conceal("/_sd").is_err());
+        assert!(builder.conceal("").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sd_jwt_with_key_binding() -> Result<()> {
+        let keypair = ES256.generate_key_pair()?;
+        let signer = ES256.signer_from_der(keypair.to_der_private_key())?;
+        let verifier = ES256.verifier_from_der(keypair.to_der_public_key())?;
+        let holder_jwk = Jwk::generate_ed_key(EdCurve::Ed25519)?;
+        let holder_signer = EdDSA.signer_from_jwk(&holder_jwk)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut builder = SdJwtBuilder::new(claims()?);
+        builder.conceal("/given_name")?;
+        builder.conceal("/family_name")?;
+        builder.set_confirmation_key(&holder_jwk)?;
+        let sd_jwt = builder.sign(&signer)?;
+
+        let (issued_payload, _, _) = builder.build()?;
+        assert_eq!(
+            sd_jwt::confirmation_key(&issued_payload)?,
+            holder_jwk.to_public_key()?
+        );
+
+        let presentation = sd_jwt.present(&["/given_name"])?;
+        let mut kb_builder =
+            KeyBindingJwtBuilder::new("1234567890", "https://verifier.example.org");
+        kb_builder.set_issued_at(now);
+        let presentation = kb_builder.sign(&presentation, &holder_signer)?;
+        let serialized = presentation.serialize();
+        assert!(!serialized.ends_with('~'));
+        assert_eq!(SdJwt::parse(&serialized)?, presentation);
+
+        let mut validator = SdJwtValidator::new();
+        validator.set_key_binding_required(true);
+        validator.set_nonce("1234567890");
+        validator.set_audience("https://verifier.example.org");
+        validator
+            .key_binding_validator_mut()
+            .set_base_time(now + Duration::from_secs(10));
+        let (payload, _) = validator.decode_with_verifier(&serialized, &verifier)?;
+        assert_eq!(payload.claim("given_name"), Some(&json!("John")));
+        assert!(payload.claim("family_name").is_none());
+
+        // the key binding JWT is required
+        assert!(validator
+            .decode_with_verifier(sd_jwt.present(&["/given_name"])?.serialize(), &verifier)
+            .is_err());
+
+        // a disclosure is added after the key binding
+        let mut tampered = SdJwt::new(
+            presentation.issuer_signed_jwt(),
+            sd_jwt.disclosures().to_vec(),
+            None,
+        );
+        tampered.set_key_binding_jwt(presentation.key_binding_jwt().map(|val| val.to_string()));
+        assert!(validator
+            .decode_with_verifier(tampered.serialize(), &verifier)
+            .is_err());
+
+        // a wrong nonce
+        let mut validator2 = SdJwtValidator::new();
+        validator2.set_nonce("other");
+        validator2.key_binding_validator_mut().set_base_time(now);
+        assert!(validator2
+            .decode_with_verifier(&serialized, &verifier)
+            .is_err());
+
+        // signed by a other key
+        let other_jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let other_signer = ES256.signer_from_jwk(&other_jwk)?;
+        let presentation = kb_builder.sign(&sd_jwt.present(&["/given_name"])?, &other_signer)?;
+        assert!(validator
+            .decode_with_verifier(presentation.serialize(), &verifier)
+            .is_err());
+
+        // the expected nonce and audience are not set
+        let mut validator2 = SdJwtValidator::new();
+        validator2.set_key_binding_required(true);
+        validator2.key_binding_validator_mut().set_base_time(now);
+        assert!(validator2
+            .decode_with_verifier(&serialized, &verifier)
+            .is_err());
+        validator2.set_nonce("1234567890");
+        assert!(validator2
+            .decode_with_verifier(&serialized, &verifier)
+            .is_err());
+        validator2.set_audience("https://verifier.example.org");
+        validator2.decode_with_verifier(&serialized, &verifier)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sd_jwt_key_binding_rejects_multibyte_token_type() -> Result<()> {
+        let keypair = ES256.generate_key_pair()?;
+        let signer = ES256.signer_from_der(keypair.to_der_private_key())?;
+        let verifier = ES256.verifier_from_der(keypair.to_der_public_key())?;
+        let holder_jwk = Jwk::generate_ed_key(EdCurve::Ed25519)?;
+        let holder_signer = EdDSA.signer_from_jwk(&holder_jwk)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut builder = SdJwtBuilder::new(claims()?);
+        builder.conceal("/given_name")?;
+        builder.set_confirmation_key(&holder_jwk)?;
+        let sd_jwt = builder.sign(&signer)?;
+        let presentation = sd_jwt.present(&["/given_name"])?;
+
+        let mut kb_header = JwsHeader::new();
+        kb_header.set_token_type("aéééééé");
+        let mut kb_payload = JwtPayload::new();
+        kb_payload.set_issued_at(&now);
+        kb_payload.set_audience(vec!["https://verifier.example.org"]);
+        kb_payload.set_claim("nonce", Some(json!("1234567890")))?;
+        kb_payload.set_claim(
+            "sd_hash",
+            Some(json!(presentation.sd_hash(HashAlgorithm::Sha256))),
+        )?;
+        let kb_jwt = jwt::encode_with_signer(&kb_payload, &kb_header, &holder_signer)?;
+        let mut presentation = presentation;
+        presentation.set_key_binding_jwt(Some(kb_jwt));
+
+        let mut validator = SdJwtValidator::new();
+        validator.set_key_binding_required(true);
+        validator.set_nonce("1234567890");
+        validator.set_audience("https://verifier.example.org");
+        validator.key_binding_validator_mut().set_base_time(now);
+        assert!(validator
+            .decode_with_verifier(presentation.serialize(), &verifier)
+            .is_err());
+
+        Ok(())
+    }
+}
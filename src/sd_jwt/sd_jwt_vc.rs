@@ -0,0 +1,70 @@
+use crate::jws::JwsHeader;
+use crate::jwt::JwtPayload;
+use crate::Value;
+
+/// Represents a verified SD-JWT VC with the disclosed claims.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SdJwtVc {
+    payload: JwtPayload,
+    header: JwsHeader,
+}
+
+impl SdJwtVc {
+    pub(crate) fn new(payload: JwtPayload, header: JwsHeader) -> Self {
+        Self { payload, header }
+    }
+
+    /// Return the processed payload that the disclosed claims are embedded in.
+    pub fn payload(&self) -> &JwtPayload {
+        &self.payload
+    }
+
+    /// Return the header of the issuer-signed JWT.
+    pub fn header(&self) -> &JwsHeader {
+        &self.header
+    }
+
+    /// Return the value for verifiable credential type claim (vct).
+    pub fn vct(&self) -> &str {
+        match self.payload.claim("vct") {
+            Some(Value::String(val)) => val,
+            _ => "",
+        }
+    }
+
+    /// Return the value for issuer claim (iss).
+    pub fn issuer(&self) -> &str {
+        self.payload.issuer().unwrap_or_default()
+    }
+
+    /// Return the URI of the status list of the status claim.
+    pub fn status_list_uri(&self) -> Option<&str> {
+        match self.status_list()?.get("uri") {
+            Some(Value::String(val)) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Return the index in the status list of the status claim.
+    pub fn status_list_index(&self) -> Option<u64> {
+        match self.status_list()?.get("idx") {
+            Some(Value::Number(val)) => val.as_u64(),
+            _ => None,
+        }
+    }
+
+    /// Return the payload.
+    pub fn into_payload(self) -> JwtPayload {
+        self.payload
+    }
+
+    fn status_list(&self) -> Option<&crate::Map<String, Value>> {
+        match self.payload.claim("status") {
+            Some(Value::Object(status)) => match status.get("status_list") {
+                Some(Value::Object(val)) => Some(val),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::convert::Into;
+use std::time::SystemTime;
+
+use anyhow::anyhow;
+
+use crate::jwk::Jwk;
+use crate::jws::JwsSigner;
+use crate::jwt::JwtPayload;
+use crate::sd_jwt::{self, SdJwt, SdJwtBuilder, DC_SD_JWT_TYPE};
+use crate::util::HashAlgorithm;
+use crate::{JoseError, Map, Value};
+
+/// Represents SD-JWT VC builder for a issuer.
+///
+/// Registered claims like iss, nbf, exp, cnf, vct and status are always
+/// issued as plain claims.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SdJwtVcBuilder {
+    builder: SdJwtBuilder,
+}
+
+impl SdJwtVcBuilder {
+    /// Return a new SdJwtVcBuilder.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a issuer identifier.
+    /// * `vct` - a verifiable credential type.
+    pub fn new(issuer: impl Into<String>, vct: impl Into<String>) -> Self {
+        let mut payload = JwtPayload::new();
+        payload.set_issuer(issuer);
+        payload
+            .set_claim("vct", Some(Value::String(vct.into())))
+            .unwrap();
+        payload.set_issued_at(&SystemTime::now());
+
+        let mut builder = SdJwtBuilder::new(payload);
+        builder.set_token_type(DC_SD_JWT_TYPE);
+
+        Self { builder }
+    }
+
+    /// Set a value for token type header claim (typ). The default value is "dc+sd-jwt".
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - "dc+sd-jwt" or "vc+sd-jwt".
+    pub fn set_token_type(&mut self, value: impl Into<String>) {
+        self.builder.set_token_type(value);
+    }
+
+    /// Set a value for key ID header claim (kid).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.builder.set_key_id(value);
+    }
+
+    /// Set a hash algorithm for the digests. The default value is SHA-256.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a hash algorithm.
+    pub fn set_hash_algorithm(&mut self, value: HashAlgorithm) -> Result<(), JoseError> {
+        self.builder.set_hash_algorithm(value)
+    }
+
+    /// Set a value for issued at claim (iat). The default value is the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issued time
+    pub fn set_issued_at(&mut self, value: SystemTime) {
+        self.set_time_claim("iat", value);
+    }
+
+    /// Set a value for expiration time claim (exp).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a expiration time
+    pub fn set_expires_at(&mut self, value: SystemTime) {
+        self.set_time_claim("exp", value);
+    }
+
+    /// Set a value for not before claim (nbf).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a time before which the credential must not be accepted.
+    pub fn set_not_before(&mut self, value: SystemTime) {
+        self.set_time_claim("nbf", value);
+    }
+
+    /// Set a reference to a status list for status claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - a URI of the status list token.
+    /// * `idx` - a index of the credential in the status list.
+    pub fn set_status_list(&mut self, uri: impl Into<String>, idx: u64) {
+        let mut status_list = Map::new();
+        status_list.insert("idx".to_string(), Value::Number(idx.into()));
+        status_list.insert("uri".to_string(), Value::String(uri.into()));
+        let mut status = Map::new();
+        status.insert("status_list".to_string(), Value::Object(status_list));
+        self.builder
+            .set_claim("status", Some(Value::Object(status)))
+            .unwrap();
+    }
+
+    /// Set a holder's public key for the confirmation claim (cnf.jwk).
+    ///
+    /// # Arguments
+    ///
+    /// * `jwk` - a holder's key.
+    pub fn set_confirmation_key(&mut self, jwk: &Jwk) -> Result<(), JoseError> {
+        self.builder.set_confirmation_key(jwk)
+    }
+
+    /// Set a value for a other claim like sub or a credential claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of claim
+    /// * `value` - a value of claim
+    pub fn set_claim(&mut self, key: &str, value: Option<Value>) -> Result<(), JoseError> {
+        if sd_jwt::NON_DISCLOSABLE_VC_CLAIMS.contains(&key) {
+            return Err(JoseError::InvalidClaim(anyhow!(
+                "The claim {} must be set by the dedicated method.",
+                key
+            )));
+        }
+        self.builder.set_claim(key, value)
+    }
+
+    /// Make a claim selectively disclosable.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer` - a JSON pointer to a object property or a array element.
+    pub fn conceal(&mut self, pointer: &str) -> Result<(), JoseError> {
+        let segments = sd_jwt::parse_pointer(pointer).map_err(JoseError::InvalidClaim)?;
+        if let [name] = segments.as_slice() {
+            if sd_jwt::NON_DISCLOSABLE_VC_CLAIMS.contains(&name.as_str()) {
+                return Err(JoseError::InvalidClaim(anyhow!(
+                    "The claim {} cannot be selectively disclosable.",
+                    name
+                )));
+            }
+        }
+        self.builder.conceal(pointer)
+    }
+
+    /// Add decoy digests to a object or a array.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer` - a JSON pointer to a object or a array. "" means the root object.
+    /// * `count` - a number of decoy digests.
+    pub fn add_decoys(&mut self, pointer: &str, count: usize) -> Result<(), JoseError> {
+        self.builder.add_decoys(pointer, count)
+    }
+
+    /// Return the SD-JWT VC signed by the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    pub fn sign(&self, signer: &dyn JwsSigner) -> Result<SdJwt, JoseError> {
+        self.builder.sign(signer)
+    }
+
+    fn set_time_claim(&mut self, key: &str, value: SystemTime) {
+        let secs = value
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|val| val.as_secs())
+            .unwrap_or(0);
+        self.builder
+            .set_claim(key, Some(Value::Number(secs.into())))
+            .unwrap();
+    }
+}
@@ -0,0 +1,325 @@
+use std::convert::Into;
+use std::sync::Arc;
+
+use anyhow::bail;
+
+use crate::jws::{self, JwsHeader};
+use crate::jwt::JwtPayload;
+use crate::sd_jwt::{
+    self, IssuerMetadataResolver, SdJwt, SdJwtValidator, SdJwtVc, DC_SD_JWT_TYPE,
+    NON_DISCLOSABLE_VC_CLAIMS, VC_SD_JWT_TYPE,
+};
+use crate::{JoseError, Value};
+
+/// Represents SD-JWT VC validator for a verifier.
+///
+/// The issuer's key is found in the JWK set that the issuer metadata resolver
+/// returns for the iss claim.
+#[derive(Debug)]
+pub struct SdJwtVcValidator {
+    validator: SdJwtValidator,
+    resolver: Arc<dyn IssuerMetadataResolver>,
+    vcts: Vec<String>,
+    status_required: bool,
+}
+
+impl SdJwtVcValidator {
+    /// Return a new SdJwtVcValidator.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolver` - a resolver of the issuer's JWK set.
+    pub fn new(resolver: Arc<dyn IssuerMetadataResolver>) -> Self {
+        Self {
+            validator: SdJwtValidator::new(),
+            resolver,
+            vcts: Vec::new(),
+            status_required: false,
+        }
+    }
+
+    /// Return the mutable SD-JWT validator to configure the payload validation
+    /// and the key binding.
+    pub fn sd_jwt_validator_mut(&mut self) -> &mut SdJwtValidator {
+        &mut self.validator
+    }
+
+    /// Add a acceptable value for verifiable credential type claim (vct).
+    /// Any type is accepted if no type is added.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a verifiable credential type.
+    pub fn add_vct(&mut self, value: impl Into<String>) {
+        self.vcts.push(value.into());
+    }
+
+    /// Set whether the status claim is required. The default value is false.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - true if the status claim is required.
+    pub fn set_status_required(&mut self, value: bool) {
+        self.status_required = value;
+    }
+
+    /// Return the SD-JWT VC verified by the key of the issuer.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a SD-JWT VC string representation.
+    pub fn validate(&self, input: impl AsRef<str>) -> Result<SdJwtVc, JoseError> {
+        let input = input.as_ref();
+        let sd_jwt = SdJwt::parse(input)?;
+
+        let issuer = (|| -> anyhow::Result<String> {
+            let claims = sd_jwt.decode_unverified_payload()?;
+            match claims.get("iss") {
+                Some(Value::String(val)) => Ok(val.clone()),
+                Some(val) => bail!("Key iss must be a string: {}", val),
+                None => bail!(JoseError::MissingClaim("iss".to_string())),
+            }
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidJwtFormat(err),
+        })?;
+
+        let jwk_set = self.resolver.resolve(&issuer)?;
+        let header = self
+            .validator
+            .context()
+            .decode_header(sd_jwt.issuer_signed_jwt())?;
+        let (alg, kid) = match (header.claim("alg"), header.claim("kid")) {
+            (Some(Value::String(alg)), Some(Value::String(kid))) => (alg, Some(kid.as_str())),
+            (Some(Value::String(alg)), None) => (alg, None),
+            _ => {
+                return Err(JoseError::InvalidJwtFormat(anyhow::anyhow!(
+                    "The alg header claim is required."
+                )))
+            }
+        };
+        let jwk = match kid {
+            Some(kid) => jwk_set.get(kid).into_iter().next(),
+            None => match jwk_set.keys().as_slice() {
+                [jwk] => Some(*jwk),
+                _ => None,
+            },
+        };
+        let jwk = match jwk {
+            Some(val) => val,
+            None => return Err(JoseError::KeyNotFound(kid.map(|val| val.to_string()))),
+        };
+        let verifier = jws::verifier_from_jwk(alg, jwk)?;
+
+        let (payload, header) = self
+            .validator
+            .decode_with_verifier(input, verifier.as_ref())?;
+        self.validate_credential(&sd_jwt, &payload, &header, &issuer)?;
+
+        Ok(SdJwtVc::new(payload, header))
+    }
+
+    fn validate_credential(
+        &self,
+        sd_jwt: &SdJwt,
+        payload: &JwtPayload,
+        header: &JwsHeader,
+        issuer: &str,
+    ) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            match header.token_type() {
+                Some(val) if val == DC_SD_JWT_TYPE || val == VC_SD_JWT_TYPE => {}
+                Some(val) => bail!("The typ header claim is invalid: {}", val),
+                None => bail!("The typ header claim is required."),
+            }
+
+            match payload.issuer() {
+                Some(val) if val == issuer => {}
+                Some(val) => bail!(JoseError::IssuerMismatch(val.to_string())),
+                None => bail!(JoseError::MissingClaim("iss".to_string())),
+            }
+
+            match payload.claim("vct") {
+                Some(Value::String(val)) => {
+                    if !self.vcts.is_empty() && !self.vcts.contains(val) {
+                        bail!("The vct is not acceptable: {}", val);
+                    }
+                }
+                Some(val) => bail!("Key vct must be a string: {}", val),
+                None => bail!(JoseError::MissingClaim("vct".to_string())),
+            }
+
+            let claims = sd_jwt.decode_unverified_payload()?;
+            let processed = sd_jwt::process(&claims, sd_jwt.disclosures())?;
+            for path in processed.paths {
+                if let [name] = path.as_slice() {
+                    if NON_DISCLOSABLE_VC_CLAIMS.contains(&name.as_str()) {
+                        bail!("The claim {} must not be selectively disclosed.", name);
+                    }
+                }
+            }
+
+            match payload.claim("status") {
+                Some(Value::Object(status)) => {
+                    if let Some(val) = status.get("status_list") {
+                        let status_list = match val {
+                            Value::Object(val) => val,
+                            val => bail!("Key status.status_list must be a object: {}", val),
+                        };
+                        match status_list.get("idx") {
+                            Some(Value::Number(val)) if val.is_u64() => {}
+                            Some(val) => bail!("Key status.status_list.idx is invalid: {}", val),
+                            None => bail!(JoseError::MissingClaim(
+                                "status.status_list.idx".to_string()
+                            )),
+                        }
+                        match status_list.get("uri") {
+                            Some(Value::String(_)) => {}
+                            Some(val) => bail!("Key status.status_list.uri is invalid: {}", val),
+                            None => bail!(JoseError::MissingClaim(
+                                "status.status_list.uri".to_string()
+                            )),
+                        }
+                    }
+                }
+                Some(val) => bail!("Key status must be a object: {}", val),
+                None => {
+                    if self.status_required {
+                        bail!(JoseError::MissingClaim("status".to_string()));
+                    }
+                }
+            }
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::jwk::alg::ec::EcCurve;
+    use crate::jwk::{Jwk, JwkSet};
+    use crate::jws::ES256;
+    use crate::jwt::JwtPayload;
+    use crate::sd_jwt::{
+        MemoryIssuerMetadataResolver, SdJwt, SdJwtBuilder, SdJwtVcBuilder, SdJwtVcValidator,
+    };
+    use crate::{Map, Value};
+
+    fn to_jwk_set(jwk: &Jwk) -> Result<JwkSet> {
+        let mut public_key = jwk.to_public_key()?;
+        if let Some(val) = jwk.key_id() {
+            public_key.set_key_id(val);
+        }
+        let public_key: Map<String, Value> = public_key.into();
+        let mut map = Map::new();
+        map.insert("keys".to_string(), json!([public_key]));
+        Ok(JwkSet::from_map(map)?)
+    }
+
+    #[test]
+    fn test_sd_jwt_vc() -> Result<()> {
+        let mut jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        jwk.set_key_id("issuer-key-1");
+        let signer = ES256.signer_from_jwk(&jwk)?;
+        let now = SystemTime::now();
+
+        let mut builder = SdJwtVcBuilder::new(
+            "https://issuer.example.com",
+            "https://credentials.example.com/identity_credential",
+        );
+        builder.set_key_id("issuer-key-1");
+        builder.set_expires_at(now + Duration::from_secs(3600));
+        builder.set_status_list("https://issuer.example.com/statuslists/1", 412);
+        builder.set_claim("given_name", Some(json!("John")))?;
+        builder.set_claim("family_name", Some(json!("Doe")))?;
+        builder.conceal("/given_name")?;
+        builder.conceal("/family_name")?;
+        assert!(builder.conceal("/vct").is_err());
+        assert!(builder.conceal("/status").is_err());
+        assert!(builder.set_claim("iss", Some(json!("other"))).is_err());
+        let sd_jwt = builder.sign(&signer)?;
+
+        let jwk_set = to_jwk_set(&jwk)?;
+        let mut resolver = MemoryIssuerMetadataResolver::new();
+        resolver.add_issuer("https://issuer.example.com", jwk_set);
+
+        let mut validator = SdJwtVcValidator::new(Arc::new(resolver));
+        validator.add_vct("https://credentials.example.com/identity_credential");
+        validator.set_status_required(true);
+
+        let presentation = sd_jwt.present(&["/given_name"])?;
+        let vc = validator.validate(presentation.serialize())?;
+        assert_eq!(
+            vc.vct(),
+            "https://credentials.example.com/identity_credential"
+        );
+        assert_eq!(vc.issuer(), "https://issuer.example.com");
+        assert_eq!(
+            vc.status_list_uri(),
+            Some("https://issuer.example.com/statuslists/1")
+        );
+        assert_eq!(vc.status_list_index(), Some(412));
+        assert_eq!(vc.header().token_type(), Some("dc+sd-jwt"));
+        assert_eq!(vc.payload().claim("given_name"), Some(&json!("John")));
+        assert!(vc.payload().claim("family_name").is_none());
+
+        let mut validator2 = SdJwtVcValidator::new(Arc::new(MemoryIssuerMetadataResolver::new()));
+        validator2.add_vct("https://credentials.example.com/identity_credential");
+        assert!(validator2.validate(presentation.serialize()).is_err());
+
+        let mut other_resolver = MemoryIssuerMetadataResolver::new();
+        let mut other_jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        other_jwk.set_key_id("issuer-key-1");
+        let other_jwk_set = to_jwk_set(&other_jwk)?;
+        other_resolver.add_issuer("https://issuer.example.com", other_jwk_set);
+        let validator3 = SdJwtVcValidator::new(Arc::new(other_resolver));
+        assert!(validator3.validate(presentation.serialize()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sd_jwt_vc_with_disclosed_registered_claim() -> Result<()> {
+        let mut jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        jwk.set_key_id("issuer-key-1");
+        let signer = ES256.signer_from_jwk(&jwk)?;
+
+        // A registered claim is made selectively disclosable by a non-conforming issuer.
+        let claims = json!({
+            "iss": "https://issuer.example.com",
+            "vct": "https://credentials.example.com/identity_credential",
+            "status": {"status_list": {"idx": 0, "uri": "https://issuer.example.com/sl"}}
+        });
+        let claims: Map<String, Value> = serde_json::from_value(claims)?;
+        let mut builder = SdJwtBuilder::new(JwtPayload::from_map(claims)?);
+        builder.set_token_type("vc+sd-jwt");
+        builder.set_key_id("issuer-key-1");
+        builder.conceal("/status")?;
+        let sd_jwt = builder.sign(&signer)?;
+
+        let jwk_set = to_jwk_set(&jwk)?;
+        let mut resolver = MemoryIssuerMetadataResolver::new();
+        resolver.add_issuer("https://issuer.example.com", jwk_set);
+        let validator = SdJwtVcValidator::new(Arc::new(resolver));
+        assert!(validator.validate(sd_jwt.serialize()).is_err());
+
+        // Without the disclosure, the status claim is simply not present.
+        let sd_jwt = SdJwt::new(sd_jwt.issuer_signed_jwt(), Vec::new(), None);
+        let vc = validator.validate(sd_jwt.serialize())?;
+        assert_eq!(vc.status_list_index(), None);
+
+        Ok(())
+    }
+}
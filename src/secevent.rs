@@ -0,0 +1,35 @@
+//! Security Event Token (SET) support.
+
+mod security_event_token;
+mod security_event_token_builder;
+mod security_event_token_validator;
+
+pub use crate::secevent::security_event_token::SecurityEventToken;
+pub use crate::secevent::security_event_token_builder::SecurityEventTokenBuilder;
+pub use crate::secevent::security_event_token_validator::SecurityEventTokenValidator;
+
+use anyhow::bail;
+
+use crate::jwt::JwtPayload;
+use crate::{Map, Value};
+
+/// The media type of a Security Event Token (RFC 8417).
+pub const SECURITY_EVENT_TOKEN_TYPE: &str = "secevent+jwt";
+
+/// Return the events claim after checking that every event is a JSON object.
+pub(crate) fn check_events(payload: &JwtPayload) -> anyhow::Result<&Map<String, Value>> {
+    let events = match payload.claim("events") {
+        Some(Value::Object(val)) => val,
+        Some(val) => bail!("Key events must be a object: {}", val),
+        None => bail!(crate::JoseError::MissingClaim("events".to_string())),
+    };
+    if events.is_empty() {
+        bail!("Key events must contain at least one event.");
+    }
+    for (key, value) in events {
+        if !value.is_object() {
+            bail!("The event {} must be a object: {}", key, value);
+        }
+    }
+    Ok(events)
+}
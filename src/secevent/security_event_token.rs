@@ -0,0 +1,69 @@
+use std::time::{Duration, SystemTime};
+
+use crate::jws::JwsHeader;
+use crate::jwt::JwtPayload;
+use crate::{Map, Value};
+
+/// Represents a decoded and validated Security Event Token (RFC 8417).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SecurityEventToken {
+    payload: JwtPayload,
+    header: JwsHeader,
+}
+
+impl SecurityEventToken {
+    pub(crate) fn new(payload: JwtPayload, header: JwsHeader) -> Self {
+        Self { payload, header }
+    }
+
+    /// Return the payload of the SET.
+    pub fn payload(&self) -> &JwtPayload {
+        &self.payload
+    }
+
+    /// Return the header of the SET.
+    pub fn header(&self) -> &JwsHeader {
+        &self.header
+    }
+
+    /// Return the event URIs of events claim.
+    pub fn event_types(&self) -> Vec<&str> {
+        match self.payload.claim("events") {
+            Some(Value::Object(val)) => val.keys().map(|key| key.as_str()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Return the event payload of a specified event URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_type` - a event URI
+    pub fn event(&self, event_type: &str) -> Option<&Map<String, Value>> {
+        match self.payload.claim("events") {
+            Some(Value::Object(val)) => match val.get(event_type) {
+                Some(Value::Object(val)) => Some(val),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Return the value for transaction identifier claim (txn).
+    pub fn transaction_id(&self) -> Option<&str> {
+        match self.payload.claim("txn") {
+            Some(Value::String(val)) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Return the value for time of event claim (toe).
+    pub fn time_of_event(&self) -> Option<SystemTime> {
+        match self.payload.claim("toe") {
+            Some(Value::Number(val)) => val
+                .as_u64()
+                .map(|val| SystemTime::UNIX_EPOCH + Duration::from_secs(val)),
+            _ => None,
+        }
+    }
+}
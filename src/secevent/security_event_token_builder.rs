@@ -0,0 +1,155 @@
+use std::convert::Into;
+use std::time::SystemTime;
+
+use crate::jws::{JwsHeader, JwsSigner};
+use crate::jwt::{JwtContext, JwtPayload};
+use crate::secevent::SECURITY_EVENT_TOKEN_TYPE;
+use crate::util;
+use crate::{JoseError, Map, Value};
+
+/// Represents Security Event Token builder (RFC 8417).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SecurityEventTokenBuilder {
+    context: JwtContext,
+    payload: JwtPayload,
+    header: JwsHeader,
+    events: Map<String, Value>,
+    issued_at: Option<SystemTime>,
+}
+
+impl SecurityEventTokenBuilder {
+    /// Return a new SecurityEventTokenBuilder.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a issuer of the SET.
+    /// * `audience` - audiences of the SET.
+    pub fn new(issuer: impl Into<String>, audience: Vec<impl Into<String>>) -> Self {
+        let mut payload = JwtPayload::new();
+        payload.set_issuer(issuer);
+        payload.set_audience(audience);
+
+        let mut header = JwsHeader::new();
+        header.set_token_type(SECURITY_EVENT_TOKEN_TYPE);
+
+        Self {
+            context: JwtContext::new(),
+            payload,
+            header,
+            events: Map::new(),
+            issued_at: None,
+        }
+    }
+
+    /// Add a event of a specified event URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_type` - a event URI
+    /// * `event` - a event payload
+    pub fn add_event(&mut self, event_type: impl Into<String>, event: Map<String, Value>) {
+        self.events.insert(event_type.into(), Value::Object(event));
+    }
+
+    /// Set a value for transaction identifier claim (txn).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a transaction identifier
+    pub fn set_transaction_id(&mut self, value: impl Into<String>) {
+        self.set_raw_claim("txn", Value::String(value.into()));
+    }
+
+    /// Set a value for time of event claim (toe).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a time of the event
+    pub fn set_time_of_event(&mut self, value: SystemTime) {
+        let secs = value
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|val| val.as_secs())
+            .unwrap_or(0);
+        self.set_raw_claim("toe", Value::Number(secs.into()));
+    }
+
+    /// Set a value for issued at claim (iat). The default value is the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issued time
+    pub fn set_issued_at(&mut self, value: SystemTime) {
+        self.issued_at = Some(value);
+    }
+
+    /// Set a value for JWT ID claim (jti). The default value is a random string.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a JWT ID
+    pub fn set_jwt_id(&mut self, value: impl Into<String>) {
+        self.payload.set_jwt_id(value);
+    }
+
+    /// Set a value for token type header claim (typ) like "logout+jwt".
+    /// The default value is "secevent+jwt".
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a token type
+    pub fn set_token_type(&mut self, value: impl Into<String>) {
+        self.header.set_token_type(value);
+    }
+
+    /// Set a value for key ID header claim (kid).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.header.set_key_id(value);
+    }
+
+    /// Set a value for a other claim like sub or sid.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - a key name of claim
+    /// * `value` - a value of claim
+    pub fn set_claim(&mut self, key: &str, value: Option<Value>) -> Result<(), JoseError> {
+        self.payload.set_claim(key, value)
+    }
+
+    /// Return the payload and the header of the SET.
+    pub fn build(&self) -> (JwtPayload, JwsHeader) {
+        let mut payload = self.payload.clone();
+
+        let issued_at = match self.issued_at {
+            Some(val) => val,
+            None => SystemTime::now(),
+        };
+        payload.set_issued_at(&issued_at);
+        if payload.jwt_id().is_none() {
+            payload.set_jwt_id(util::encode_base64_urlsafe_nopad(util::random_bytes(16)));
+        }
+        payload
+            .set_claim("events", Some(Value::Object(self.events.clone())))
+            .unwrap();
+
+        (payload, self.header.clone())
+    }
+
+    /// Return the string repsentation of the SET signed by the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    pub fn sign(&self, signer: &dyn JwsSigner) -> Result<String, JoseError> {
+        let (payload, header) = self.build();
+        self.context.encode_with_signer(&payload, &header, signer)
+    }
+
+    fn set_raw_claim(&mut self, key: &str, value: Value) {
+        self.payload.set_claim(key, Some(value)).unwrap();
+    }
+}
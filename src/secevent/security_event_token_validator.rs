@@ -0,0 +1,320 @@
+use std::convert::Into;
+
+use anyhow::bail;
+
+use crate::jws::{JwsHeader, JwsVerifier};
+use crate::jwt::{JwtContext, JwtHeaderValidator, JwtPayload, JwtPayloadValidator};
+use crate::secevent::{self, SecurityEventToken, SECURITY_EVENT_TOKEN_TYPE};
+use crate::{JoseError, Value};
+
+/// Represents Security Event Token validator for event receivers (RFC 8417).
+#[derive(Debug)]
+pub struct SecurityEventTokenValidator {
+    context: JwtContext,
+    payload_validator: JwtPayloadValidator,
+    issuer: String,
+    audience: String,
+    event_types: Vec<String>,
+    expires_at_allowed: bool,
+    subject_allowed: bool,
+}
+
+impl SecurityEventTokenValidator {
+    /// Return a new SecurityEventTokenValidator.
+    ///
+    /// The typ header claim "secevent+jwt" is required.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - a expected issuer of the SET.
+    /// * `audience` - a audience of the event receiver.
+    pub fn new(issuer: impl Into<String>, audience: impl Into<String>) -> Self {
+        let mut header_validator = JwtHeaderValidator::new();
+        header_validator.set_token_type(SECURITY_EVENT_TOKEN_TYPE);
+
+        let mut context = JwtContext::new();
+        context.set_header_validator(Some(header_validator));
+
+        let mut payload_validator = JwtPayloadValidator::new();
+        payload_validator.add_required_claim("iat");
+        payload_validator.add_required_claim("jti");
+
+        Self {
+            context,
+            payload_validator,
+            issuer: issuer.into(),
+            audience: audience.into(),
+            event_types: Vec::new(),
+            expires_at_allowed: false,
+            subject_allowed: true,
+        }
+    }
+
+    /// Return the mutable JWT context that is used for decoding.
+    pub fn context_mut(&mut self) -> &mut JwtContext {
+        &mut self.context
+    }
+
+    /// Return the mutable payload validator that checks the time claims.
+    pub fn payload_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.payload_validator
+    }
+
+    /// Add a event URI that the receiver accepts.
+    ///
+    /// If any event URI is added, the SET must contain at least one of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a event URI
+    pub fn add_event_type(&mut self, value: impl Into<String>) {
+        self.event_types.push(value.into());
+    }
+
+    /// Set whether the exp claim is allowed.
+    ///
+    /// A SET doesn't have a expiration time,
+    /// so the exp claim is rejected by default to prevent confusion with access tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - true if the exp claim is allowed.
+    pub fn set_expires_at_allowed(&mut self, value: bool) {
+        self.expires_at_allowed = value;
+    }
+
+    /// Set whether the top-level sub claim is allowed.
+    ///
+    /// Some profiles like Shared Signals identify the subject by the sub_id claim
+    /// or in the event payload instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - true if the sub claim is allowed.
+    pub fn set_subject_allowed(&mut self, value: bool) {
+        self.subject_allowed = value;
+    }
+
+    /// Return the SET decoded and validated by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a SET string representation.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_with_verifier(
+        &self,
+        input: impl AsRef<[u8]>,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<SecurityEventToken, JoseError> {
+        self.decode_with_verifier_selector(input, |_header| Ok(Some(verifier)))
+    }
+
+    /// Return the SET decoded and validated with a selected verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a SET string representation.
+    /// * `selector` - a function for selecting the verifying algorithm.
+    pub fn decode_with_verifier_selector<'a, F>(
+        &self,
+        input: impl AsRef<[u8]>,
+        selector: F,
+    ) -> Result<SecurityEventToken, JoseError>
+    where
+        F: Fn(&JwsHeader) -> Result<Option<&'a dyn JwsVerifier>, JoseError>,
+    {
+        let (payload, header) = self
+            .context
+            .decode_with_verifier_selector(input, selector)?;
+        self.validate(&payload, &header)?;
+        Ok(SecurityEventToken::new(payload, header))
+    }
+
+    /// Validate a decoded SET.
+    ///
+    /// The typ header claim must be secevent+jwt even if the header validator of the context
+    /// is replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - a decoded SET payload.
+    /// * `header` - a decoded SET header.
+    pub fn validate(&self, payload: &JwtPayload, header: &JwsHeader) -> Result<(), JoseError> {
+        (|| -> anyhow::Result<()> {
+            if let Some("none") | None = header.algorithm() {
+                bail!("The SET must be signed.");
+            }
+
+            let mut header_validator = JwtHeaderValidator::new();
+            header_validator.set_token_type(SECURITY_EVENT_TOKEN_TYPE);
+            header_validator.validate(header)?;
+
+            match payload.issuer() {
+                Some(val) if val == self.issuer => {}
+                Some(val) => bail!(JoseError::IssuerMismatch(val.to_string())),
+                None => bail!(JoseError::MissingClaim("iss".to_string())),
+            }
+
+            match payload.audience() {
+                Some(vals) if vals.contains(&self.audience.as_str()) => {}
+                Some(vals) => bail!(JoseError::AudienceMismatch(
+                    vals.iter().map(|val| val.to_string()).collect(),
+                )),
+                None => bail!(JoseError::MissingClaim("aud".to_string())),
+            }
+
+            if !self.expires_at_allowed && payload.claim("exp").is_some() {
+                bail!("Key exp must not be used in the SET.");
+            }
+            if !self.subject_allowed && payload.claim("sub").is_some() {
+                bail!("Key sub must not be used in the SET.");
+            }
+
+            let events = secevent::check_events(payload)?;
+            if !self.event_types.is_empty()
+                && !self.event_types.iter().any(|val| events.contains_key(val))
+            {
+                bail!(
+                    "The SET doesn't contain any acceptable event: {}",
+                    events.keys().cloned().collect::<Vec<String>>().join(", ")
+                );
+            }
+
+            match payload.claim("txn") {
+                Some(Value::String(_)) | None => {}
+                Some(val) => bail!("Key txn must be a string: {}", val),
+            }
+            match payload.claim("toe") {
+                Some(Value::Number(val)) if val.is_u64() => {}
+                Some(val) => bail!("Key toe must be a numeric date: {}", val),
+                None => {}
+            }
+
+            self.payload_validator.validate(payload)?;
+
+            Ok(())
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::jwk::alg::ec::EcCurve;
+    use crate::jwk::Jwk;
+    use crate::jws::{JwsHeader, ES256};
+    use crate::jwt;
+    use crate::secevent::{SecurityEventTokenBuilder, SecurityEventTokenValidator};
+    use crate::{JoseError, Map, Value};
+
+    const SESSION_REVOKED: &str =
+        "https://schemas.openid.net/secevent/caep/event-type/session-revoked";
+
+    #[test]
+    fn test_security_event_token() -> Result<()> {
+        let jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let signer = ES256.signer_from_jwk(&jwk)?;
+        let verifier = ES256.verifier_from_jwk(&jwk.to_public_key()?)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut event = Map::new();
+        event.insert(
+            "subject".to_string(),
+            json!({"format": "email", "email": "user@example.com"}),
+        );
+
+        let mut builder =
+            SecurityEventTokenBuilder::new("https://transmitter.example.com", vec!["receiver1"]);
+        builder.set_issued_at(now);
+        builder.set_transaction_id("txn-1");
+        builder.set_time_of_event(now - Duration::from_secs(10));
+        builder.add_event(SESSION_REVOKED, event.clone());
+        let set = builder.sign(&signer)?;
+
+        let mut validator =
+            SecurityEventTokenValidator::new("https://transmitter.example.com", "receiver1");
+        validator.payload_validator_mut().set_base_time(now);
+        validator.set_subject_allowed(false);
+        validator.add_event_type(SESSION_REVOKED);
+        let token = validator.decode_with_verifier(&set, &verifier)?;
+        assert_eq!(token.event_types(), vec![SESSION_REVOKED]);
+        assert_eq!(token.event(SESSION_REVOKED), Some(&event));
+        assert_eq!(token.transaction_id(), Some("txn-1"));
+        assert_eq!(token.time_of_event(), Some(now - Duration::from_secs(10)));
+
+        let mut validator2 =
+            SecurityEventTokenValidator::new("https://transmitter.example.com", "receiver1");
+        validator2.add_event_type("https://example.com/other-event");
+        assert!(validator2.decode_with_verifier(&set, &verifier).is_err());
+
+        let mut builder2 = builder.clone();
+        builder2.set_claim("exp", Some(json!(1_700_000_600)))?;
+        let set2 = builder2.sign(&signer)?;
+        assert!(validator.decode_with_verifier(&set2, &verifier).is_err());
+        validator.set_expires_at_allowed(true);
+        validator.decode_with_verifier(&set2, &verifier)?;
+
+        let mut builder2 = builder.clone();
+        builder2.set_claim("sub", Some(json!("user1")))?;
+        let set2 = builder2.sign(&signer)?;
+        assert!(validator.decode_with_verifier(&set2, &verifier).is_err());
+
+        let (payload, _) = builder.build();
+        let mut header = JwsHeader::new();
+        header.set_token_type("JWT");
+        let set2 = jwt::encode_with_signer(&payload, &header, &signer)?;
+        assert!(validator.decode_with_verifier(&set2, &verifier).is_err());
+
+        let mut payload2 = payload.clone();
+        payload2.set_claim("events", Some(json!({ SESSION_REVOKED: "revoked" })))?;
+        let set2 = jwt::encode_with_signer(&payload2, &builder.build().1, &signer)?;
+        assert!(validator.decode_with_verifier(&set2, &verifier).is_err());
+
+        payload2.set_claim("events", Some(Value::Object(Map::new())))?;
+        let set2 = jwt::encode_with_signer(&payload2, &builder.build().1, &signer)?;
+        assert!(validator.decode_with_verifier(&set2, &verifier).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_security_event_token_type_without_header_validator() -> Result<()> {
+        let jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let signer = ES256.signer_from_jwk(&jwk)?;
+        let verifier = ES256.verifier_from_jwk(&jwk.to_public_key()?)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut builder =
+            SecurityEventTokenBuilder::new("https://transmitter.example.com", vec!["receiver1"]);
+        builder.set_issued_at(now);
+        builder.add_event(SESSION_REVOKED, Map::new());
+
+        let mut validator =
+            SecurityEventTokenValidator::new("https://transmitter.example.com", "receiver1");
+        validator.payload_validator_mut().set_base_time(now);
+        validator.context_mut().set_header_validator(None);
+        validator.decode_with_verifier(builder.sign(&signer)?, &verifier)?;
+
+        let (payload, _) = builder.build();
+        let mut header = JwsHeader::new();
+        header.set_token_type("JWT");
+        let set = jwt::encode_with_signer(&payload, &header, &signer)?;
+        assert!(matches!(
+            validator.decode_with_verifier(&set, &verifier),
+            Err(JoseError::InvalidJwtFormat(_))
+        ));
+
+        header.set_algorithm("ES256");
+        assert!(validator.validate(&payload, &header).is_err());
+
+        Ok(())
+    }
+}
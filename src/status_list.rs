@@ -0,0 +1,23 @@
+//! Token Status List (draft-ietf-oauth-status-list) support.
+
+mod status_bits;
+mod status_list_token;
+mod status_list_token_builder;
+mod status_list_token_validator;
+
+pub use crate::status_list::status_bits::StatusList;
+pub use crate::status_list::status_list_token::StatusListToken;
+pub use crate::status_list::status_list_token_builder::StatusListTokenBuilder;
+pub use crate::status_list::status_list_token_validator::StatusListTokenValidator;
+
+/// The media type of a status list token.
+pub const STATUS_LIST_TOKEN_TYPE: &str = "statuslist+jwt";
+
+/// The status that the referenced token is valid.
+pub const STATUS_VALID: u8 = 0x00;
+
+/// The status that the referenced token is revoked.
+pub const STATUS_INVALID: u8 = 0x01;
+
+/// The status that the referenced token is temporarily suspended.
+pub const STATUS_SUSPENDED: u8 = 0x02;
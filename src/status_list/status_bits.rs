@@ -0,0 +1,272 @@
+use std::io::{Read, Write};
+
+use anyhow::bail;
+use flate2::read::{DeflateDecoder, ZlibDecoder};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::util;
+use crate::{JoseError, Map, Value};
+
+/// Represents a status list: a byte array of statuses of `bits` bits each.
+///
+/// The status of the index i is stored in the byte i * bits / 8,
+/// starting from the least significant bit.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct StatusList {
+    bits: u8,
+    bytes: Vec<u8>,
+}
+
+impl StatusList {
+    /// The maximum size of a status list in bytes.
+    pub const MAX_SIZE: usize = 16 * 1024 * 1024;
+
+    /// Return a new empty StatusList.
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - a number of bits per status: 1, 2, 4 or 8.
+    pub fn new(bits: u8) -> Result<Self, JoseError> {
+        check_bits(bits).map_err(JoseError::InvalidClaim)?;
+        Ok(Self {
+            bits,
+            bytes: Vec::new(),
+        })
+    }
+
+    /// Return the StatusList decoded from the bits and the lst value.
+    ///
+    /// The lst value is a base64url encoded byte array compressed by DEFLATE
+    /// with the ZLIB data format. A raw DEFLATE stream is also accepted.
+    /// The decompressed list must not exceed `StatusList::MAX_SIZE` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - a number of bits per status.
+    /// * `lst` - a compressed status list.
+    pub fn from_compressed(bits: u8, lst: &str) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            check_bits(bits)?;
+            let compressed = util::decode_base64_urlsafe_no_pad(lst)?;
+
+            let bytes = match inflate(ZlibDecoder::new(compressed.as_slice())) {
+                Ok(val) => val,
+                Err(_) => inflate(DeflateDecoder::new(compressed.as_slice()))?,
+            };
+            if bytes.len() > Self::MAX_SIZE {
+                bail!("The status list exceeds {} bytes.", Self::MAX_SIZE);
+            }
+
+            Ok(Self { bits, bytes })
+        })()
+        .map_err(JoseError::InvalidClaim)
+    }
+
+    /// Return the StatusList decoded from the status_list claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a status_list claim value.
+    pub fn from_value(value: &Value) -> Result<Self, JoseError> {
+        (|| -> anyhow::Result<Self> {
+            let map = match value {
+                Value::Object(val) => val,
+                val => bail!("Key status_list must be a object: {}", val),
+            };
+            let bits = match map.get("bits") {
+                Some(Value::Number(val)) => match val.as_u64() {
+                    Some(val) if val <= u8::MAX as u64 => val as u8,
+                    _ => bail!("Key bits is invalid: {}", val),
+                },
+                Some(val) => bail!("Key bits must be a number: {}", val),
+                None => bail!(JoseError::MissingClaim("status_list.bits".to_string())),
+            };
+            let lst = match map.get("lst") {
+                Some(Value::String(val)) => val,
+                Some(val) => bail!("Key lst must be a string: {}", val),
+                None => bail!(JoseError::MissingClaim("status_list.lst".to_string())),
+            };
+            Ok(Self::from_compressed(bits, lst)?)
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+
+    /// Return the number of bits per status.
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// Return the number of statuses that the list can hold.
+    pub fn len(&self) -> usize {
+        self.bytes.len() * 8 / self.bits as usize
+    }
+
+    /// Test whether the list holds no statuses.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Return the status of a index.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - a index of the referenced token.
+    pub fn get(&self, idx: usize) -> Option<u8> {
+        let (pos, shift) = self.position(idx)?;
+        let byte = self.bytes.get(pos)?;
+        Some((byte >> shift) & self.mask())
+    }
+
+    /// Set the status of a index. The list is extended as needed
+    /// up to `StatusList::MAX_SIZE` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - a index of the referenced token.
+    /// * `status` - a status value that fits in the bits.
+    pub fn set(&mut self, idx: usize, status: u8) -> Result<(), JoseError> {
+        if status & !self.mask() != 0 {
+            return Err(JoseError::InvalidClaim(anyhow::anyhow!(
+                "The status {} exceeds {} bits.",
+                status,
+                self.bits
+            )));
+        }
+
+        let (pos, shift) = match self.position(idx) {
+            Some((pos, shift)) if pos < Self::MAX_SIZE => (pos, shift),
+            _ => {
+                return Err(JoseError::InvalidClaim(anyhow::anyhow!(
+                    "The index {} exceeds the maximum size of the status list.",
+                    idx
+                )))
+            }
+        };
+        if pos >= self.bytes.len() {
+            self.bytes.resize(pos + 1, 0);
+        }
+        let mask = self.mask();
+        self.bytes[pos] = (self.bytes[pos] & !(mask << shift)) | (status << shift);
+        Ok(())
+    }
+
+    /// Return the status list as a byte array.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Return the base64url encoded compressed status list for the lst value.
+    pub fn to_compressed(&self) -> String {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&self.bytes).unwrap();
+        let compressed = encoder.finish().unwrap();
+        util::encode_base64_urlsafe_nopad(compressed)
+    }
+
+    /// Return the status_list claim value.
+    pub fn to_value(&self) -> Value {
+        let mut map = Map::new();
+        map.insert("bits".to_string(), Value::Number(self.bits.into()));
+        map.insert("lst".to_string(), Value::String(self.to_compressed()));
+        Value::Object(map)
+    }
+
+    fn mask(&self) -> u8 {
+        ((1u16 << self.bits) - 1) as u8
+    }
+
+    fn position(&self, idx: usize) -> Option<(usize, usize)> {
+        let bit = idx.checked_mul(self.bits as usize)?;
+        Some((bit / 8, bit % 8))
+    }
+}
+
+/// Read a decompressed status list, but no more than one byte over the maximum size.
+fn inflate(reader: impl Read) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader
+        .take(StatusList::MAX_SIZE as u64 + 1)
+        .read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn check_bits(bits: u8) -> anyhow::Result<()> {
+    match bits {
+        1 | 2 | 4 | 8 => Ok(()),
+        _ => bail!("The bits must be 1, 2, 4 or 8: {}", bits),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    use crate::jwe::zip::DEF;
+    use crate::jwe::JweCompression;
+    use crate::status_list::StatusList;
+    use crate::util;
+
+    #[test]
+    fn test_status_list() -> Result<()> {
+        // draft-ietf-oauth-status-list section 4.1
+        let list = StatusList::from_compressed(1, "eNrbuRgAAhcBXQ")?;
+        assert_eq!(list.as_bytes(), &[0xB9, 0xA3]);
+        let statuses = [1, 0, 0, 1, 1, 1, 0, 1, 1, 1, 0, 0, 0, 1, 0, 1];
+        for (idx, status) in statuses.iter().enumerate() {
+            assert_eq!(list.get(idx), Some(*status));
+        }
+        assert_eq!(list.get(16), None);
+
+        let mut list2 = StatusList::new(1)?;
+        for (idx, status) in statuses.iter().enumerate() {
+            list2.set(idx, *status)?;
+        }
+        assert_eq!(list2, list);
+        assert_eq!(StatusList::from_value(&list2.to_value())?, list);
+
+        let mut list = StatusList::new(2)?;
+        list.set(0, 1)?;
+        list.set(1, 2)?;
+        list.set(3, 3)?;
+        list.set(1, 0)?;
+        assert_eq!(list.as_bytes(), &[0xC1]);
+        assert_eq!(list.len(), 4);
+        assert!(list.set(4, 4).is_err());
+
+        // a raw DEFLATE stream
+        let raw = util::encode_base64_urlsafe_nopad(DEF.compress(&[0xB9, 0xA3])?);
+        assert_eq!(
+            StatusList::from_compressed(1, &raw)?.as_bytes(),
+            &[0xB9, 0xA3]
+        );
+
+        assert!(StatusList::new(3).is_err());
+
+        let list = StatusList::from_compressed(8, "eNrbuRgAAhcBXQ")?;
+        assert_eq!(list.get(usize::MAX), None);
+        let mut list = StatusList::new(8)?;
+        assert!(list.set(usize::MAX, 1).is_err());
+        assert!(list.set(StatusList::MAX_SIZE, 1).is_err());
+        assert!(list.is_empty());
+        list.set(StatusList::MAX_SIZE - 1, 1)?;
+        assert_eq!(list.as_bytes().len(), StatusList::MAX_SIZE);
+
+        // a compression bomb
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&vec![0; StatusList::MAX_SIZE + 1])?;
+        let lst = util::encode_base64_urlsafe_nopad(encoder.finish()?);
+        assert!(StatusList::from_compressed(1, &lst).is_err());
+        let lst =
+            util::encode_base64_urlsafe_nopad(DEF.compress(&vec![0; StatusList::MAX_SIZE + 1])?);
+        assert!(StatusList::from_compressed(1, &lst).is_err());
+
+        Ok(())
+    }
+}
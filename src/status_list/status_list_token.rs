@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use crate::jws::JwsHeader;
+use crate::jwt::JwtPayload;
+use crate::status_list::StatusList;
+use crate::{JoseError, Value};
+
+/// Represents a decoded and validated status list token.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct StatusListToken {
+    payload: JwtPayload,
+    header: JwsHeader,
+    status_list: StatusList,
+}
+
+impl StatusListToken {
+    pub(crate) fn new(payload: JwtPayload, header: JwsHeader, status_list: StatusList) -> Self {
+        Self {
+            payload,
+            header,
+            status_list,
+        }
+    }
+
+    /// Return the payload of the status list token.
+    pub fn payload(&self) -> &JwtPayload {
+        &self.payload
+    }
+
+    /// Return the header of the status list token.
+    pub fn header(&self) -> &JwsHeader {
+        &self.header
+    }
+
+    /// Return the status list.
+    pub fn status_list(&self) -> &StatusList {
+        &self.status_list
+    }
+
+    /// Return the value for time to live claim (ttl).
+    pub fn time_to_live(&self) -> Option<Duration> {
+        match self.payload.claim("ttl") {
+            Some(Value::Number(val)) => val.as_u64().map(Duration::from_secs),
+            _ => None,
+        }
+    }
+
+    /// Return the status of a referenced token.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - a index of the status_list claim of the referenced token.
+    pub fn status(&self, idx: u64) -> Result<u8, JoseError> {
+        let status = usize::try_from(idx)
+            .ok()
+            .and_then(|idx| self.status_list.get(idx));
+        match status {
+            Some(val) => Ok(val),
+            None => Err(JoseError::InvalidClaim(anyhow::anyhow!(
+                "The index is out of the status list: {}",
+                idx
+            ))),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::convert::Into;
+use std::time::{Duration, SystemTime};
+
+use crate::jws::{JwsHeader, JwsSigner};
+use crate::jwt::{JwtContext, JwtPayload};
+use crate::status_list::{StatusList, STATUS_LIST_TOKEN_TYPE};
+use crate::{JoseError, Value};
+
+/// Represents status list token builder.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct StatusListTokenBuilder {
+    context: JwtContext,
+    payload: JwtPayload,
+    header: JwsHeader,
+    status_list: StatusList,
+    issued_at: Option<SystemTime>,
+}
+
+impl StatusListTokenBuilder {
+    /// Return a new StatusListTokenBuilder.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - a URI of the status list token that referenced tokens contain.
+    /// * `status_list` - a status list.
+    pub fn new(subject: impl Into<String>, status_list: StatusList) -> Self {
+        let mut payload = JwtPayload::new();
+        payload.set_subject(subject);
+
+        let mut header = JwsHeader::new();
+        header.set_token_type(STATUS_LIST_TOKEN_TYPE);
+
+        Self {
+            context: JwtContext::new(),
+            payload,
+            header,
+            status_list,
+            issued_at: None,
+        }
+    }
+
+    /// Return the mutable status list to update statuses.
+    pub fn status_list_mut(&mut self) -> &mut StatusList {
+        &mut self.status_list
+    }
+
+    /// Set a value for issuer claim (iss).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issuer
+    pub fn set_issuer(&mut self, value: impl Into<String>) {
+        self.payload.set_issuer(value);
+    }
+
+    /// Set a value for issued at claim (iat). The default value is the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a issued time
+    pub fn set_issued_at(&mut self, value: SystemTime) {
+        self.issued_at = Some(value);
+    }
+
+    /// Set a value for expiration time claim (exp).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a expiration time
+    pub fn set_expires_at(&mut self, value: SystemTime) {
+        self.payload.set_expires_at(&value);
+    }
+
+    /// Set a value for time to live claim (ttl).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a maximum duration to cache the status list token
+    pub fn set_time_to_live(&mut self, value: Duration) {
+        self.payload
+            .set_claim("ttl", Some(Value::Number(value.as_secs().into())))
+            .unwrap();
+    }
+
+    /// Set a value for key ID header claim (kid).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - a key ID
+    pub fn set_key_id(&mut self, value: impl Into<String>) {
+        self.header.set_key_id(value);
+    }
+
+    /// Return the payload and the header of the status list token.
+    pub fn build(&self) -> (JwtPayload, JwsHeader) {
+        let mut payload = self.payload.clone();
+
+        let issued_at = match self.issued_at {
+            Some(val) => val,
+            None => SystemTime::now(),
+        };
+        payload.set_issued_at(&issued_at);
+        payload
+            .set_claim("status_list", Some(self.status_list.to_value()))
+            .unwrap();
+
+        (payload, self.header.clone())
+    }
+
+    /// Return the string repsentation of the status list token signed by the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer` - a signer object.
+    pub fn sign(&self, signer: &dyn JwsSigner) -> Result<String, JoseError> {
+        let (payload, header) = self.build();
+        self.context.encode_with_signer(&payload, &header, signer)
+    }
+}
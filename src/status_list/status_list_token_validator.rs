@@ -0,0 +1,226 @@
+use std::convert::Into;
+
+use anyhow::bail;
+
+use crate::jws::{JwsHeader, JwsVerifier};
+use crate::jwt::{JwtContext, JwtHeaderValidator, JwtPayload, JwtPayloadValidator};
+use crate::status_list::{StatusList, StatusListToken, STATUS_LIST_TOKEN_TYPE};
+use crate::JoseError;
+
+/// Represents status list token validator.
+///
+/// The sub claim must be the URI that the referenced token contains.
+#[derive(Debug)]
+pub struct StatusListTokenValidator {
+    context: JwtContext,
+    payload_validator: JwtPayloadValidator,
+    uri: String,
+}
+
+impl StatusListTokenValidator {
+    /// Return a new StatusListTokenValidator.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - a URI of the status_list claim of the referenced token.
+    pub fn new(uri: impl Into<String>) -> Self {
+        let mut header_validator = JwtHeaderValidator::new();
+        header_validator.set_token_type(STATUS_LIST_TOKEN_TYPE);
+
+        let mut context = JwtContext::new();
+        context.set_header_validator(Some(header_validator));
+
+        let mut payload_validator = JwtPayloadValidator::new();
+        payload_validator.add_required_claim("iat");
+
+        Self {
+            context,
+            payload_validator,
+            uri: uri.into(),
+        }
+    }
+
+    /// Return the mutable JWT context that is used for decoding.
+    pub fn context_mut(&mut self) -> &mut JwtContext {
+        &mut self.context
+    }
+
+    /// Return the mutable payload validator that checks the time claims.
+    pub fn payload_validator_mut(&mut self) -> &mut JwtPayloadValidator {
+        &mut self.payload_validator
+    }
+
+    /// Return the status list token decoded and validated by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a status list token string representation.
+    /// * `verifier` - a verifier of the signing algorithm.
+    pub fn decode_with_verifier(
+        &self,
+        input: impl AsRef<[u8]>,
+        verifier: &dyn JwsVerifier,
+    ) -> Result<StatusListToken, JoseError> {
+        self.decode_with_verifier_selector(input, |_header| Ok(Some(verifier)))
+    }
+
+    /// Return the status list token decoded and validated with a selected verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - a status list token string representation.
+    /// * `selector` - a function for selecting the verifying algorithm.
+    pub fn decode_with_verifier_selector<'a, F>(
+        &self,
+        input: impl AsRef<[u8]>,
+        selector: F,
+    ) -> Result<StatusListToken, JoseError>
+    where
+        F: Fn(&JwsHeader) -> Result<Option<&'a dyn JwsVerifier>, JoseError>,
+    {
+        let (payload, header) = self
+            .context
+            .decode_with_verifier_selector(input, selector)?;
+        let status_list = self.validate(&payload, &header)?;
+        Ok(StatusListToken::new(payload, header, status_list))
+    }
+
+    /// Validate a decoded status list token and return the status list.
+    ///
+    /// The typ header claim must be statuslist+jwt even if the header validator of the context
+    /// is replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - a decoded status list token payload.
+    /// * `header` - a decoded status list token header.
+    pub fn validate(
+        &self,
+        payload: &JwtPayload,
+        header: &JwsHeader,
+    ) -> Result<StatusList, JoseError> {
+        (|| -> anyhow::Result<StatusList> {
+            if let Some("none") | None = header.algorithm() {
+                bail!("The status list token must be signed.");
+            }
+
+            let mut header_validator = JwtHeaderValidator::new();
+            header_validator.set_token_type(STATUS_LIST_TOKEN_TYPE);
+            header_validator.validate(header)?;
+
+            match payload.subject() {
+                Some(val) if val == self.uri => {}
+                Some(val) => bail!("The sub claim does not match the status list URI: {}", val),
+                None => bail!(JoseError::MissingClaim("sub".to_string())),
+            }
+
+            let status_list = match payload.claim("status_list") {
+                Some(val) => StatusList::from_value(val)?,
+                None => bail!(JoseError::MissingClaim("status_list".to_string())),
+            };
+
+            self.payload_validator.validate(payload)?;
+
+            Ok(status_list)
+        })()
+        .map_err(|err| match err.downcast::<JoseError>() {
+            Ok(err) => err,
+            Err(err) => JoseError::InvalidClaim(err),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+
+    use crate::jwk::alg::ec::EcCurve;
+    use crate::jwk::Jwk;
+    use crate::jws::ES256;
+    use crate::jwt;
+    use crate::status_list::{
+        StatusList, StatusListTokenBuilder, StatusListTokenValidator, STATUS_INVALID,
+        STATUS_SUSPENDED, STATUS_VALID,
+    };
+    use crate::JoseError;
+
+    #[test]
+    fn test_status_list_token() -> Result<()> {
+        let jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let signer = ES256.signer_from_jwk(&jwk)?;
+        let verifier = ES256.verifier_from_jwk(&jwk.to_public_key()?)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let mut status_list = StatusList::new(2)?;
+        status_list.set(1000, STATUS_VALID)?;
+        status_list.set(412, STATUS_INVALID)?;
+
+        let uri = "https://example.com/statuslists/1";
+        let mut builder = StatusListTokenBuilder::new(uri, status_list);
+        builder.status_list_mut().set(7, STATUS_SUSPENDED)?;
+        builder.set_issued_at(now);
+        builder.set_expires_at(now + Duration::from_secs(86400));
+        builder.set_time_to_live(Duration::from_secs(43200));
+        let token = builder.sign(&signer)?;
+
+        let mut validator = StatusListTokenValidator::new(uri);
+        validator.payload_validator_mut().set_base_time(now);
+        let status_list_token = validator.decode_with_verifier(&token, &verifier)?;
+        assert_eq!(
+            status_list_token.header().token_type(),
+            Some("statuslist+jwt")
+        );
+        assert_eq!(
+            status_list_token.time_to_live(),
+            Some(Duration::from_secs(43200))
+        );
+        assert_eq!(status_list_token.status(412)?, STATUS_INVALID);
+        assert_eq!(status_list_token.status(7)?, STATUS_SUSPENDED);
+        assert_eq!(status_list_token.status(0)?, STATUS_VALID);
+        assert!(status_list_token.status(1004).is_err());
+
+        let mut validator2 = StatusListTokenValidator::new("https://example.com/statuslists/2");
+        validator2.payload_validator_mut().set_base_time(now);
+        assert!(validator2.decode_with_verifier(&token, &verifier).is_err());
+
+        let mut validator3 = StatusListTokenValidator::new(uri);
+        validator3
+            .payload_validator_mut()
+            .set_base_time(now + Duration::from_secs(86401));
+        assert!(validator3.decode_with_verifier(&token, &verifier).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_status_list_token_type_without_header_validator() -> Result<()> {
+        let jwk = Jwk::generate_ec_key(EcCurve::P256)?;
+        let signer = ES256.signer_from_jwk(&jwk)?;
+        let verifier = ES256.verifier_from_jwk(&jwk.to_public_key()?)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let uri = "https://example.com/statuslists/1";
+        let mut builder = StatusListTokenBuilder::new(uri, StatusList::new(1)?);
+        builder.set_issued_at(now);
+
+        let mut validator = StatusListTokenValidator::new(uri);
+        validator.payload_validator_mut().set_base_time(now);
+        validator.context_mut().set_header_validator(None);
+        validator.decode_with_verifier(builder.sign(&signer)?, &verifier)?;
+
+        let (payload, mut header) = builder.build();
+        header.set_token_type("JWT");
+        let token = jwt::encode_with_signer(&payload, &header, &signer)?;
+        assert!(matches!(
+            validator.decode_with_verifier(&token, &verifier),
+            Err(JoseError::InvalidJwtFormat(_))
+        ));
+
+        header.set_algorithm("ES256");
+        assert!(validator.validate(&payload, &header).is_err());
+
+        Ok(())
+    }
+}
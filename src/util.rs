@@ -1,8 +1,16 @@
+pub mod clock;
 pub mod der;
 pub mod hash_algorithm;
+#[cfg(all(test, feature = "async"))]
+pub(crate) mod mock_kms;
 pub mod oid;
 
+use std::io::{self, Read, Write};
+
 use anyhow::bail;
+use base64::engine::GeneralPurpose;
+use base64::read::DecoderReader;
+use base64::write::EncoderWriter;
 use base64::DecodeError;
 use base64::Engine as _;
 use once_cell::sync::Lazy;
@@ -10,6 +18,7 @@ use openssl::bn::BigNumRef;
 use openssl::rand;
 use regex;
 
+pub use crate::util::clock::{Clock, FixedClock, SystemClock};
 pub use crate::util::hash_algorithm::HashAlgorithm;
 
 pub use HashAlgorithm::Sha1 as SHA_1;
@@ -65,6 +74,27 @@ pub(crate) fn encode_base64_urlsafe_nopad_buf(input: impl AsRef<[u8]>, output_bu
     base64::engine::general_purpose::URL_SAFE_NO_PAD.encode_string(input, output_buf);
 }
 
+pub(crate) fn encode_base64_urlsafe_nopad_writer<W: Write>(
+    output: W,
+) -> EncoderWriter<'static, GeneralPurpose, W> {
+    EncoderWriter::new(output, &base64::engine::general_purpose::URL_SAFE_NO_PAD)
+}
+
+pub(crate) fn decode_base64_urlsafe_nopad_reader<R: Read>(
+    input: R,
+) -> DecoderReader<'static, GeneralPurpose, R> {
+    DecoderReader::new(input, &base64::engine::general_purpose::URL_SAFE_NO_PAD)
+}
+
+pub(crate) fn read_retrying<R: Read + ?Sized>(input: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match input.read(buf) {
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            result => return result,
+        }
+    }
+}
+
 pub(crate) fn decode_base64_urlsafe_no_pad(
     input: impl AsRef<[u8]>,
 ) -> Result<Vec<u8>, DecodeError> {
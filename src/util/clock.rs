@@ -0,0 +1,41 @@
+use std::fmt::Debug;
+use std::time::SystemTime;
+
+/// Represent a source of the current time.
+pub trait Clock: Debug + Send + Sync {
+    /// Return the current time.
+    fn now(&self) -> SystemTime;
+}
+
+/// A clock that returns the system time.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock that always returns the same time.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct FixedClock {
+    time: SystemTime,
+}
+
+impl FixedClock {
+    /// Return a new clock that always returns the specified time.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - a fixed time.
+    pub fn new(time: SystemTime) -> Self {
+        Self { time }
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.time
+    }
+}
@@ -0,0 +1,81 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::jwe::{AsyncJweDecrypter, JweAlgorithm, JweContentEncryption, JweDecrypter, JweHeader};
+use crate::jws::{AsyncJwsSigner, JwsAlgorithm, JwsSigner};
+use crate::JoseError;
+
+/// A signer that signs with a key held by a mock KMS,
+/// suspending once as a remote call would.
+#[derive(Debug)]
+pub struct MockKmsSigner {
+    pub key: Box<dyn JwsSigner>,
+}
+
+#[async_trait::async_trait]
+impl AsyncJwsSigner for MockKmsSigner {
+    fn algorithm(&self) -> &dyn JwsAlgorithm {
+        self.key.algorithm()
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        self.key.key_id()
+    }
+
+    fn signature_len(&self) -> usize {
+        self.key.signature_len()
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Vec<u8>, JoseError> {
+        YieldNow(false).await;
+        self.key.sign(message)
+    }
+}
+
+/// A decrypter that unwraps keys with a key held by a mock KMS,
+/// suspending once as a remote call would.
+#[derive(Debug)]
+pub struct MockKmsDecrypter {
+    pub key: Box<dyn JweDecrypter>,
+}
+
+#[async_trait::async_trait]
+impl AsyncJweDecrypter for MockKmsDecrypter {
+    fn algorithm(&self) -> &dyn JweAlgorithm {
+        self.key.algorithm()
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        self.key.key_id()
+    }
+
+    async fn decrypt(
+        &self,
+        encrypted_key: Option<&[u8]>,
+        cencryption: &dyn JweContentEncryption,
+        header: &JweHeader,
+    ) -> Result<Vec<u8>, JoseError> {
+        YieldNow(false).await;
+        let key = self.key.decrypt(encrypted_key, cencryption, header)?;
+        Ok(key.into_owned())
+    }
+}
+
+pub fn assert_send<T: Send>(_: &T) {}
+
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}